@@ -8,7 +8,7 @@ import type { Message, Conversation } from '@/types';
 import { MessageSquare, Info } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { useLocalIdentity } from '@/hooks/use-local-identity';
-import { fetchConversations, fetchMessages, markAsRead, editMessage } from '@/services/api';
+import { fetchConversations, fetchMessages, markAsRead, editMessage, logout } from '@/services/api';
 import { connectWebSocket, sendMessage, disconnectWebSocket } from '@/services/ws';
 
 function App() {
@@ -22,7 +22,12 @@ function App() {
     );
   }
 
-  return <Chat userId={identity.id} displayName={identity.displayName} onSignOut={clearIdentity} />;
+  const handleSignOut = () => {
+    logout().catch(() => {});
+    clearIdentity();
+  };
+
+  return <Chat userId={identity.id} token={identity.token} displayName={identity.displayName} onSignOut={handleSignOut} />;
 }
 
 function markConversationReadLocally(conversations: Conversation[], conversationId: string, userId: string, readAt: string): Conversation[] {
@@ -33,7 +38,7 @@ function markConversationReadLocally(conversations: Conversation[], conversation
   );
 }
 
-function Chat({ userId, displayName, onSignOut }: { userId: string; displayName: string; onSignOut: () => void }) {
+function Chat({ userId, token, displayName, onSignOut }: { userId: string; token: string; displayName: string; onSignOut: () => void }) {
   const [conversations, setConversations] = useState<Conversation[]>([]);
   const [currentConversationId, setCurrentConversationId] = useState<string | null>(null);
   const [messagesByConversation, setMessagesByConversation] = useState<Record<string, Message[]>>({});
@@ -66,7 +71,7 @@ function Chat({ userId, displayName, onSignOut }: { userId: string; displayName:
   }
 
   useEffect(() => {
-    fetchConversations(userId)
+    fetchConversations()
       .then((convs) => {
         setConversations(convs);
         Promise.all(
@@ -89,7 +94,7 @@ function Chat({ userId, displayName, onSignOut }: { userId: string; displayName:
   }, [userId]);
 
   useEffect(() => {
-    connectWebSocket(userId, {
+    connectWebSocket(token, {
       onMessage: (msg) => {
         setMessagesByConversation((prev) => ({
           ...prev,
@@ -100,7 +105,7 @@ function Chat({ userId, displayName, onSignOut }: { userId: string; displayName:
         // server's read pointer honest so a refresh mid-chat doesn't show it as unread
         if (msg.conversation_id === currentConversationIdRef.current && msg.sender_id !== userId) {
           const now = new Date().toISOString();
-          markAsRead(msg.conversation_id, userId).catch(() => {});
+          markAsRead(msg.conversation_id).catch(() => {});
           setConversations((prev) => markConversationReadLocally(prev, msg.conversation_id, userId, now));
         }
       },
@@ -117,7 +122,7 @@ function Chat({ userId, displayName, onSignOut }: { userId: string; displayName:
       },
     });
     return () => disconnectWebSocket();
-  }, [userId]);
+  }, [token, userId]);
 
   useEffect(() => {
     if (!currentConversationId) {
@@ -130,7 +135,7 @@ function Chat({ userId, displayName, onSignOut }: { userId: string; displayName:
       .catch(() => {});
 
     const now = new Date().toISOString();
-    markAsRead(currentConversationId, userId)
+    markAsRead(currentConversationId)
       .then(() => {
         setConversations((prev) => markConversationReadLocally(prev, currentConversationId, userId, now));
       })
@@ -142,15 +147,12 @@ function Chat({ userId, displayName, onSignOut }: { userId: string; displayName:
       if (!currentConversationId) {
         return;
       }
-      sendMessage(currentConversationId, userId, content, kind);
+      sendMessage(currentConversationId, content, kind);
     },
-    [currentConversationId, userId],
+    [currentConversationId],
   );
 
-  const handleEditMessage = useCallback(
-    (messageId: string, content: string) => editMessage(messageId, userId, content),
-    [userId],
-  );
+  const handleEditMessage = useCallback((messageId: string, content: string) => editMessage(messageId, content), []);
 
   const handleCreateConversation = useCallback((conversation: Conversation) => {
     setConversations((prev) => (prev.some((c) => c.id === conversation.id) ? prev : [conversation, ...prev]));
@@ -60,7 +60,6 @@ export function NewConversationDialog({ currentUserId, trigger, onCreate }: NewC
 
       const conversation = await createConversation({
         conversation_type: isGroup ? 'group' : 'direct',
-        sender_id: currentUserId,
         participants: participants,
         name: name,
       });
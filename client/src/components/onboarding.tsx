@@ -1,36 +1,72 @@
 import { useState } from 'react';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
-import { createUser } from '@/services/api';
+import { login, registerUser, verifyLogin, type Session } from '@/services/api';
 import type { LocalIdentity } from '@/hooks/use-local-identity';
 
 interface OnboardingProps {
   onComplete: (identity: LocalIdentity) => void;
 }
 
+type Mode = 'register' | 'sign-in';
+
+function toIdentity(session: Session): LocalIdentity {
+  return {
+    id: session.user.id,
+    username: session.user.username,
+    displayName: session.user.display_name,
+    token: session.token,
+  };
+}
+
 export default function Onboarding({ onComplete }: OnboardingProps) {
+  const [mode, setMode] = useState<Mode>('register');
   const [username, setUsername] = useState('');
   const [displayName, setDisplayName] = useState('');
+  const [password, setPassword] = useState('');
+  const [challengeToken, setChallengeToken] = useState<string | null>(null);
+  const [code, setCode] = useState('');
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const canSubmit = username.trim().length > 0 && displayName.trim().length > 0 && !loading;
+  const canSubmit = challengeToken
+    ? code.trim().length > 0 && !loading
+    : username.trim().length > 0 &&
+      password.length > 0 &&
+      (mode === 'sign-in' || displayName.trim().length > 0) &&
+      !loading;
 
   const handleSubmit = async () => {
     if (!canSubmit) return;
     setLoading(true);
     setError(null);
     try {
-      const id = crypto.randomUUID();
-      await createUser(id, username.trim(), displayName.trim());
-      onComplete({ id, username: username.trim(), displayName: displayName.trim() });
-    } catch {
-      setError('Could not create your account. Try again.');
+      if (challengeToken) {
+        onComplete(toIdentity(await verifyLogin(challengeToken, code.trim())));
+        return;
+      }
+
+      if (mode === 'register') {
+        await registerUser(username.trim(), displayName.trim(), password);
+      }
+      const result = await login(username.trim(), password);
+      if (result.status === 'two_factor_required') {
+        setChallengeToken(result.challenge_token);
+      } else {
+        onComplete(toIdentity(result));
+      }
+    } catch (e) {
+      setError(e instanceof Error ? e.message : 'Something went wrong. Try again.');
     } finally {
       setLoading(false);
     }
   };
 
+  const switchMode = () => {
+    setMode((prev) => (prev === 'register' ? 'sign-in' : 'register'));
+    setError(null);
+  };
+
   return (
     <div
       className="flex min-h-screen items-center justify-center"
@@ -40,24 +76,58 @@ export default function Onboarding({ onComplete }: OnboardingProps) {
         <div className="flex flex-col gap-1 text-center">
           <img src="/volt.svg" alt="Volt" className="mx-auto h-10 w-10" />
           <h1 className="text-lg font-semibold">Welcome to Volt</h1>
-          <p className="text-sm text-muted-foreground">Pick a username to get started.</p>
+          <p className="text-sm text-muted-foreground">
+            {challengeToken
+              ? 'Enter the code from your authenticator app.'
+              : mode === 'register'
+                ? 'Pick a username to get started.'
+                : 'Sign in to your account.'}
+          </p>
         </div>
-        <Input
-          placeholder="Username"
-          value={username}
-          onChange={(e) => setUsername(e.target.value)}
-          onKeyDown={(e) => e.key === 'Enter' && handleSubmit()}
-        />
-        <Input
-          placeholder="Display name"
-          value={displayName}
-          onChange={(e) => setDisplayName(e.target.value)}
-          onKeyDown={(e) => e.key === 'Enter' && handleSubmit()}
-        />
+        {challengeToken ? (
+          <Input
+            placeholder="Code"
+            autoComplete="one-time-code"
+            value={code}
+            onChange={(e) => setCode(e.target.value)}
+            onKeyDown={(e) => e.key === 'Enter' && handleSubmit()}
+          />
+        ) : (
+          <>
+            <Input
+              placeholder="Username"
+              autoComplete="username"
+              value={username}
+              onChange={(e) => setUsername(e.target.value)}
+              onKeyDown={(e) => e.key === 'Enter' && handleSubmit()}
+            />
+            {mode === 'register' && (
+              <Input
+                placeholder="Display name"
+                value={displayName}
+                onChange={(e) => setDisplayName(e.target.value)}
+                onKeyDown={(e) => e.key === 'Enter' && handleSubmit()}
+              />
+            )}
+            <Input
+              type="password"
+              placeholder="Password"
+              autoComplete={mode === 'register' ? 'new-password' : 'current-password'}
+              value={password}
+              onChange={(e) => setPassword(e.target.value)}
+              onKeyDown={(e) => e.key === 'Enter' && handleSubmit()}
+            />
+          </>
+        )}
         {error && <p className="text-sm text-red-500">{error}</p>}
         <Button onClick={handleSubmit} disabled={!canSubmit}>
-          {loading ? 'Creating...' : 'Continue'}
+          {loading ? 'Please wait...' : challengeToken ? 'Verify' : mode === 'register' ? 'Create account' : 'Sign in'}
         </Button>
+        {!challengeToken && (
+          <Button variant="link" size="sm" onClick={switchMode}>
+            {mode === 'register' ? 'Already have an account? Sign in' : 'New here? Create an account'}
+          </Button>
+        )}
       </div>
     </div>
   );
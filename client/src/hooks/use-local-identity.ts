@@ -1,25 +1,46 @@
-import { useCallback, useState } from 'react';
+import { useCallback, useEffect, useState } from 'react';
 
 export interface LocalIdentity {
   id: string;
   username: string;
   displayName: string;
+  token: string;
 }
 
 const STORAGE_KEY = 'volt.identity';
 
+// fired when the server turns the stored token away, so every mounted hook drops the identity
+const SESSION_EXPIRED_EVENT = 'volt:session-expired';
+
 function readIdentity(): LocalIdentity | null {
   try {
     const raw = localStorage.getItem(STORAGE_KEY);
-    return raw ? (JSON.parse(raw) as LocalIdentity) : null;
+    const identity = raw ? (JSON.parse(raw) as LocalIdentity) : null;
+    // identities saved before sign-in existed have no token and can't call the API
+    return identity?.token ? identity : null;
   } catch {
     return null;
   }
 }
 
+export function getSessionToken(): string | null {
+  return readIdentity()?.token ?? null;
+}
+
+export function expireSession(): void {
+  localStorage.removeItem(STORAGE_KEY);
+  window.dispatchEvent(new Event(SESSION_EXPIRED_EVENT));
+}
+
 export function useLocalIdentity() {
   const [identity, setIdentityState] = useState<LocalIdentity | null>(readIdentity);
 
+  useEffect(() => {
+    const onExpired = () => setIdentityState(null);
+    window.addEventListener(SESSION_EXPIRED_EVENT, onExpired);
+    return () => window.removeEventListener(SESSION_EXPIRED_EVENT, onExpired);
+  }, []);
+
   const setIdentity = useCallback((next: LocalIdentity) => {
     localStorage.setItem(STORAGE_KEY, JSON.stringify(next));
     setIdentityState(next);
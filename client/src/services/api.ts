@@ -1,29 +1,84 @@
 import type { User, Conversation, Message } from '@/types';
 import { env } from '@/lib/env';
+import { expireSession, getSessionToken } from '@/hooks/use-local-identity';
 
 const API_BASE = `${env.API_URL}/api/v1`;
 
+// the server works out who is asking from the session token, never from ids in the request
 async function sendHttpRequest(path: string, init?: RequestInit): Promise<Response> {
-  return fetch(`${API_BASE}${path}`, init);
+  const token = getSessionToken();
+  const headers = new Headers(init?.headers);
+  if (token) {
+    headers.set('Authorization', `Bearer ${token}`);
+  }
+
+  const res = await fetch(`${API_BASE}${path}`, { ...init, headers });
+  // signed out elsewhere or expired: back to the sign-in screen rather than a chat that can't load
+  if (res.status === 401 && token) {
+    expireSession();
+  }
+  return res;
+}
+
+export interface Session {
+  token: string;
+  expires_at: string;
+  user: User;
+}
+
+// a password alone isn't enough for accounts with two-factor on; the code goes to verifyLogin
+export type LoginResult =
+  | ({ status: 'authenticated' } & Session)
+  | { status: 'two_factor_required'; challenge_token: string; expires_at: string };
+
+async function responseError(res: Response, fallback: string): Promise<Error> {
+  const body = await res.json().catch(() => null);
+  return new Error(body?.error ?? fallback);
+}
+
+export async function registerUser(username: string, displayName: string, password: string): Promise<User> {
+  const res = await sendHttpRequest('/user', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ username, display_name: displayName, password }),
+  });
+  if (!res.ok) throw await responseError(res, 'Failed to create account');
+  return res.json();
+}
+
+export async function login(username: string, password: string): Promise<LoginResult> {
+  const res = await sendHttpRequest('/login', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ username, password }),
+  });
+  if (!res.ok) throw await responseError(res, 'Failed to sign in');
+  return res.json();
 }
 
-export async function createUser(id: string, username: string, displayName: string): Promise<void> {
-  await sendHttpRequest('/user', {
+export async function verifyLogin(challengeToken: string, code: string): Promise<Session> {
+  const res = await sendHttpRequest('/login/totp', {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify({ id, username, display_name: displayName }),
+    body: JSON.stringify({ challenge_token: challengeToken, code }),
   });
+  if (!res.ok) throw await responseError(res, 'Failed to verify code');
+  return res.json();
+}
+
+export async function logout(): Promise<void> {
+  await sendHttpRequest('/logout', { method: 'POST' });
 }
 
 export async function fetchUsers(currentUserId: string): Promise<User[]> {
   const res = await sendHttpRequest('/users');
+  if (!res.ok) throw new Error('Failed to load users');
   const data: User[] = await res.json();
   return data.filter((u) => u.id !== currentUserId);
 }
 
 export async function createConversation(params: {
   conversation_type: 'direct' | 'group';
-  sender_id: string;
   participants: string[];
   name: string | null;
 }): Promise<Conversation> {
@@ -36,19 +91,20 @@ export async function createConversation(params: {
   return res.json();
 }
 
-export async function fetchConversations(userId: string): Promise<Conversation[]> {
-  const res = await sendHttpRequest(`/conversations/${userId}`);
+export async function fetchConversations(): Promise<Conversation[]> {
+  const res = await sendHttpRequest('/conversations');
+  if (!res.ok) throw new Error('Failed to load conversations');
   return res.json();
 }
 
-export async function markAsRead(conversationId: string, userId: string): Promise<void> {
-  await sendHttpRequest(`/conversation/${conversationId}/read/${userId}`, {
+export async function markAsRead(conversationId: string): Promise<void> {
+  await sendHttpRequest(`/conversation/${conversationId}/read`, {
     method: 'POST',
   });
 }
 
-export async function leaveConversation(conversationId: string, userId: string): Promise<void> {
-  await sendHttpRequest(`/conversation/${conversationId}/leave/${userId}`, {
+export async function leaveConversation(conversationId: string): Promise<void> {
+  await sendHttpRequest(`/conversation/${conversationId}/leave`, {
     method: 'POST',
   });
 }
@@ -69,20 +125,18 @@ export async function fetchMessages(
 
   const queryString = query.toString();
   const res = await sendHttpRequest(`/messages/${conversationId}${queryString ? `?${queryString}` : ''}`);
+  if (!res.ok) throw new Error('Failed to load messages');
   return res.json();
 }
 
-export async function editMessage(messageId: string, editorId: string, content: string): Promise<void> {
+export async function editMessage(messageId: string, content: string): Promise<void> {
   const res = await sendHttpRequest(`/messages/${messageId}`, {
     method: 'PATCH',
     headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify({ editor_id: editorId, content }),
+    body: JSON.stringify({ content }),
   });
 
-  if (!res.ok) {
-    const body = await res.json().catch(() => null);
-    throw new Error(body?.error ?? 'Failed to edit message');
-  }
+  if (!res.ok) throw await responseError(res, 'Failed to edit message');
 }
 
 export async function uploadImage(file: File): Promise<{ url: string }> {
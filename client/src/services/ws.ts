@@ -12,12 +12,13 @@ interface WsHandlers {
   onConversation: (conversation: Conversation) => void;
 }
 
-export function connectWebSocket(userId: string, handlers: WsHandlers): void {
+export function connectWebSocket(token: string, handlers: WsHandlers): void {
   if (_ws) {
     disconnectWebSocket();
   }
 
-  const ws = new WebSocket(`${WS_BASE}/chat/${userId}`);
+  // browsers can't put an Authorization header on the upgrade, so the session token rides in the query
+  const ws = new WebSocket(`${WS_BASE}/chat?token=${encodeURIComponent(token)}`);
   _ws = ws;
 
   ws.onopen = () => {
@@ -47,8 +48,8 @@ export function connectWebSocket(userId: string, handlers: WsHandlers): void {
   };
 }
 
-export function sendMessage(conversationId: string, senderId: string, content: string, kind: 'text' | 'image' = 'text'): void {
-  const payload = JSON.stringify({ conversation_id: conversationId, sender_id: senderId, content, kind });
+export function sendMessage(conversationId: string, content: string, kind: 'text' | 'image' = 'text'): void {
+  const payload = JSON.stringify({ conversation_id: conversationId, content, kind });
   if (!_ws) {
     return;
   }
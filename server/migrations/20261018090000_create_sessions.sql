@@ -0,0 +1,12 @@
+CREATE TABLE sessions (
+    id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash BYTEA NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_sessions_user_id ON sessions (user_id);
+
+COMMENT ON TABLE sessions IS 'Login sessions backing bearer-token authentication';
+COMMENT ON COLUMN sessions.token_hash IS 'SHA-256 of the bearer token, the raw token is only ever held by the client';
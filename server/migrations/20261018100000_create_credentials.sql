@@ -0,0 +1,8 @@
+CREATE TABLE credentials (
+    user_id UUID NOT NULL PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    password_hash TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE credentials IS 'Password credentials, one row per user that can log in';
+COMMENT ON COLUMN credentials.password_hash IS 'Argon2id hash in PHC string format';
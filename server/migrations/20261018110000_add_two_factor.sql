@@ -0,0 +1,32 @@
+ALTER TABLE users ADD COLUMN two_factor_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE totp_enrollments (
+    user_id UUID NOT NULL PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret_ciphertext BYTEA NOT NULL,
+    secret_nonce BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    confirmed_at TIMESTAMPTZ,
+    last_used_step BIGINT
+);
+
+CREATE TABLE recovery_codes (
+    user_id UUID NOT NULL REFERENCES totp_enrollments(user_id) ON DELETE CASCADE,
+    code_hash BYTEA NOT NULL,
+    used_at TIMESTAMPTZ,
+    PRIMARY KEY (user_id, code_hash)
+);
+
+CREATE TABLE login_challenges (
+    id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash BYTEA NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    failed_attempts INT NOT NULL DEFAULT 0
+);
+
+COMMENT ON TABLE totp_enrollments IS 'TOTP secrets, unconfirmed until the user proves their authenticator app works';
+COMMENT ON COLUMN totp_enrollments.secret_ciphertext IS 'AES-256-GCM encrypted shared secret, keyed by TOTP_ENCRYPTION_KEY';
+COMMENT ON COLUMN totp_enrollments.last_used_step IS 'Time step of the last accepted code, earlier or equal steps are rejected as replays';
+COMMENT ON TABLE recovery_codes IS 'One-time fallback codes for when the authenticator is unavailable';
+COMMENT ON COLUMN recovery_codes.code_hash IS 'SHA-256 of the normalized recovery code';
+COMMENT ON TABLE login_challenges IS 'Pending second login step for users with two-factor enabled';
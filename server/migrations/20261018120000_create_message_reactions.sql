@@ -0,0 +1,10 @@
+CREATE TABLE message_reactions (
+    message_id UUID NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    emoji TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (message_id, user_id, emoji)
+);
+
+COMMENT ON TABLE message_reactions IS 'Emoji reactions, a user can react to a message with several different emoji but each only once';
@@ -0,0 +1,17 @@
+CREATE TYPE participant_role AS ENUM ('owner', 'admin', 'member');
+
+ALTER TABLE user_conversations ADD COLUMN role participant_role NOT NULL DEFAULT 'member';
+
+-- existing groups predate roles, so the longest-standing participant becomes the owner
+UPDATE user_conversations uc
+SET role = 'owner'
+FROM (
+    SELECT DISTINCT ON (uc.conversation_id) uc.conversation_id, uc.user_id
+    FROM user_conversations uc
+    JOIN conversations c ON c.id = uc.conversation_id
+    WHERE c.kind = 'group'
+    ORDER BY uc.conversation_id, uc.joined_at, uc.user_id
+) first_joined
+WHERE uc.conversation_id = first_joined.conversation_id AND uc.user_id = first_joined.user_id;
+
+COMMENT ON COLUMN user_conversations.role IS 'Owner and admins manage group membership; always member in direct conversations';
@@ -0,0 +1,3 @@
+ALTER TABLE messages ADD COLUMN deleted_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN messages.deleted_at IS 'Set when the message was unsent for everyone; content is wiped at the same time';
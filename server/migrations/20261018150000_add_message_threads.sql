@@ -0,0 +1,3 @@
+ALTER TABLE messages ADD COLUMN thread_root_id UUID REFERENCES messages(id);
+
+CREATE INDEX idx_messages_thread_root ON messages(thread_root_id, created_at) WHERE thread_root_id IS NOT NULL;
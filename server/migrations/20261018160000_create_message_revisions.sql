@@ -0,0 +1,10 @@
+CREATE TABLE message_revisions (
+    message_id UUID NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    written_at TIMESTAMPTZ NOT NULL,
+    replaced_at TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (message_id, replaced_at)
+);
+
+COMMENT ON TABLE message_revisions IS 'Every superseded version of an edited message, oldest first by written_at; the live content stays on messages';
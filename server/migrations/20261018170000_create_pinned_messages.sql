@@ -0,0 +1,10 @@
+CREATE TABLE pinned_messages (
+    conversation_id UUID NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    message_id UUID NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    pinned_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    pinned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (conversation_id, message_id)
+);
+
+COMMENT ON TABLE pinned_messages IS 'Messages pinned to the top of a conversation, newest pin first when listed';
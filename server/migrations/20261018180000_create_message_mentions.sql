@@ -0,0 +1,10 @@
+CREATE TABLE message_mentions (
+    message_id UUID NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (message_id, user_id)
+);
+
+CREATE INDEX idx_message_mentions_user ON message_mentions(user_id);
+
+COMMENT ON TABLE message_mentions IS 'Participants @mentioned by a message, backing each user''s mention inbox';
@@ -0,0 +1,4 @@
+ALTER TABLE conversations
+    ADD COLUMN description TEXT,
+    ADD COLUMN avatar_url TEXT,
+    ADD CONSTRAINT direct_no_details CHECK (kind != 'direct' OR (description IS NULL AND avatar_url IS NULL));
@@ -0,0 +1,18 @@
+-- at most one direct conversation per pair of users, keyed on the pair in sorted order
+CREATE TABLE direct_conversations (
+    user_low UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_high UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    conversation_id UUID NOT NULL UNIQUE REFERENCES conversations(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (user_low, user_high),
+    CONSTRAINT direct_pair_sorted CHECK (user_low < user_high)
+);
+
+-- existing duplicates keep working, but only the oldest conversation per pair is handed out from now on
+INSERT INTO direct_conversations (user_low, user_high, conversation_id)
+SELECT DISTINCT ON (a.user_id, b.user_id) a.user_id, b.user_id, c.id
+FROM conversations c
+JOIN user_conversations a ON a.conversation_id = c.id
+JOIN user_conversations b ON b.conversation_id = c.id AND a.user_id < b.user_id
+WHERE c.kind = 'direct'
+ORDER BY a.user_id, b.user_id, c.created_at ASC;
@@ -0,0 +1,12 @@
+CREATE TABLE conversation_invitations (
+    conversation_id UUID NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invited_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (conversation_id, user_id)
+);
+
+CREATE INDEX idx_conversation_invitations_user ON conversation_invitations(user_id);
+
+COMMENT ON TABLE conversation_invitations IS 'Pending invitations to join a group; a row is removed once the invitee accepts or declines';
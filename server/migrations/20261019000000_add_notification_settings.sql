@@ -0,0 +1,8 @@
+CREATE TYPE notify_level AS ENUM ('all', 'mentions', 'none');
+
+ALTER TABLE user_conversations
+    ADD COLUMN notify_level notify_level NOT NULL DEFAULT 'all',
+    ADD COLUMN muted_until TIMESTAMPTZ;
+
+COMMENT ON COLUMN user_conversations.notify_level IS 'Which new messages alert this participant: every one, only mentions, or none';
+COMMENT ON COLUMN user_conversations.muted_until IS 'Messages stay quiet until this time; mentions still come through unless notify_level is none';
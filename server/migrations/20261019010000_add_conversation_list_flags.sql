@@ -0,0 +1,8 @@
+ALTER TABLE user_conversations
+    ADD COLUMN archived_at TIMESTAMPTZ,
+    ADD COLUMN pinned_at TIMESTAMPTZ;
+
+CREATE INDEX idx_user_conversations_user_pinned ON user_conversations (user_id, pinned_at DESC NULLS LAST);
+
+COMMENT ON COLUMN user_conversations.archived_at IS 'Hidden from this participant''s main list; a new message clears it unless the conversation is muted';
+COMMENT ON COLUMN user_conversations.pinned_at IS 'Pinned to the top of this participant''s list, most recently pinned first';
@@ -0,0 +1,26 @@
+-- the dispatcher moves a row to 'sending' before it goes out, so a cancel can't race the send. a
+-- crash part way through leaves it there until the claim goes stale and another run picks it up
+CREATE TYPE scheduled_status AS ENUM ('pending', 'sending', 'sent', 'failed', 'cancelled');
+
+CREATE TABLE scheduled_messages (
+    id UUID PRIMARY KEY,
+    conversation_id UUID NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    sender_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    kind message_kind NOT NULL DEFAULT 'text',
+    reply_to UUID REFERENCES messages(id) ON DELETE SET NULL,
+    send_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    status scheduled_status NOT NULL DEFAULT 'pending',
+    message_id UUID REFERENCES messages(id) ON DELETE SET NULL,
+    failure TEXT,
+    -- how many times the dispatcher has claimed it, and when it last did
+    attempts INT NOT NULL DEFAULT 0,
+    claimed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_scheduled_messages_due ON scheduled_messages(send_at) WHERE status = 'pending';
+CREATE INDEX idx_scheduled_messages_claimed ON scheduled_messages(claimed_at) WHERE status = 'sending';
+CREATE INDEX idx_scheduled_messages_sender ON scheduled_messages(sender_id);
+
+COMMENT ON TABLE scheduled_messages IS 'Messages composed ahead of time and sent by the dispatcher once send_at passes';
@@ -0,0 +1,15 @@
+ALTER TABLE conversations
+    ADD COLUMN message_ttl_seconds INT CHECK (message_ttl_seconds > 0);
+
+ALTER TABLE messages ADD COLUMN expires_at TIMESTAMPTZ;
+
+CREATE INDEX idx_messages_expires_at ON messages (expires_at) WHERE expires_at IS NOT NULL;
+
+-- an expired thread root is hard-deleted on its own; its replies keep their own timers and media,
+-- and stay behind as top-level messages until those run out
+ALTER TABLE messages
+    DROP CONSTRAINT messages_thread_root_id_fkey,
+    ADD CONSTRAINT messages_thread_root_id_fkey FOREIGN KEY (thread_root_id) REFERENCES messages(id) ON DELETE SET NULL;
+
+COMMENT ON COLUMN conversations.message_ttl_seconds IS 'Disappearing-message timer: new messages expire this long after being sent, NULL to keep them';
+COMMENT ON COLUMN messages.expires_at IS 'When the reaper removes this message for good, NULL if it never expires';
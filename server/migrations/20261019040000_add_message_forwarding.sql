@@ -0,0 +1,9 @@
+ALTER TABLE messages
+    ADD COLUMN forwarded_from_message_id UUID,
+    ADD COLUMN forwarded_from_conversation_id UUID;
+
+-- forwarded images share one upload, which must outlive every message pointing at it
+CREATE INDEX idx_messages_image_content ON messages (content) WHERE kind = 'image';
+
+COMMENT ON COLUMN messages.forwarded_from_message_id IS 'Original message this was forwarded from; not a foreign key so provenance outlives the original';
+COMMENT ON COLUMN messages.forwarded_from_conversation_id IS 'Conversation the original message was sent in';
@@ -0,0 +1,22 @@
+ALTER TYPE message_kind ADD VALUE 'poll';
+
+CREATE TABLE message_polls (
+    message_id UUID PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    options TEXT[] NOT NULL,
+    multiple_choice BOOLEAN NOT NULL DEFAULT FALSE,
+    closes_at TIMESTAMPTZ
+);
+
+-- one row per voter, so voting again is a single upsert and concurrent votes can't add up to more
+-- choices than the poll allows
+CREATE TABLE poll_votes (
+    message_id UUID NOT NULL REFERENCES message_polls(message_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    choices SMALLINT[] NOT NULL CHECK (cardinality(choices) > 0),
+    voted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (message_id, user_id)
+);
+
+COMMENT ON TABLE message_polls IS 'Options and rules of poll messages; the question is the message content';
+COMMENT ON TABLE poll_votes IS 'Each voter''s current choices as option indexes, replaced as a whole when they vote again';
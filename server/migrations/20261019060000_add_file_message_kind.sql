@@ -0,0 +1,2 @@
+-- a new enum value can't be used in the transaction that adds it, so the table that needs it comes next
+ALTER TYPE message_kind ADD VALUE 'file';
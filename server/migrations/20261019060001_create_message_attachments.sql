@@ -0,0 +1,12 @@
+CREATE TABLE message_attachments (
+    message_id UUID PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    filename TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    mime_type TEXT NOT NULL
+);
+
+-- uploaded files can be forwarded and shared just like images
+DROP INDEX idx_messages_image_content;
+CREATE INDEX idx_messages_media_content ON messages (content) WHERE kind IN ('image', 'file');
+
+COMMENT ON TABLE message_attachments IS 'Name, size and type of the file behind a file message; the message content is its URL';
@@ -0,0 +1,2 @@
+-- same as 'file', the voice note table and index that use it come in the next migration
+ALTER TYPE message_kind ADD VALUE 'audio';
@@ -0,0 +1,10 @@
+CREATE TABLE message_voice_notes (
+    message_id UUID PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    duration_ms INTEGER NOT NULL CHECK (duration_ms > 0),
+    peaks BYTEA NOT NULL
+);
+
+DROP INDEX idx_messages_media_content;
+CREATE INDEX idx_messages_media_content ON messages (content) WHERE kind IN ('image', 'file', 'audio');
+
+COMMENT ON TABLE message_voice_notes IS 'Duration and waveform measured when a voice note was uploaded; the message content is its URL';
@@ -0,0 +1,19 @@
+-- keyed by the name each upload was stored under, so a message pointing at it gets what we measured
+-- rather than what the sender says about it
+CREATE TABLE uploaded_files (
+    stored_name TEXT PRIMARY KEY,
+    filename TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    mime_type TEXT NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE uploaded_voice_notes (
+    stored_name TEXT PRIMARY KEY,
+    duration_ms INTEGER NOT NULL CHECK (duration_ms > 0),
+    peaks BYTEA NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE uploaded_files IS 'Original name, size and type of each file taken by POST /upload/file';
+COMMENT ON TABLE uploaded_voice_notes IS 'Duration and waveform measured for each recording taken by POST /upload/audio';
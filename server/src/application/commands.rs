@@ -1,6 +1,41 @@
+pub mod accept_invitation;
+pub mod add_participants;
+pub mod add_reaction;
+pub mod archive_conversation;
+pub mod block_user;
+pub mod cancel_scheduled_message;
+pub mod cast_vote;
+pub mod change_participant_role;
+pub mod change_password;
+pub mod confirm_totp;
 pub mod create_conversation;
-pub mod create_user;
+pub mod create_invite;
+pub mod decline_invitation;
+pub mod delete_message;
+pub mod disable_totp;
+pub mod dispatch_scheduled_messages;
 pub mod edit_message;
+pub mod enroll_totp;
+pub mod expire_messages;
+pub mod forward_message;
+pub mod join_via_invite;
+pub mod kick_participant;
 pub mod leave_conversation;
+pub mod login;
+pub mod logout;
 pub mod mark_message_read;
+pub mod pin_conversation;
+pub mod pin_message;
+pub mod register;
+pub mod remove_reaction;
+pub mod rename_conversation;
+pub mod retract_vote;
+pub mod revoke_invite;
+pub mod schedule_message;
 pub mod send_message;
+pub mod set_message_timer;
+pub mod unblock_user;
+pub mod unpin_message;
+pub mod update_conversation_details;
+pub mod update_notification_settings;
+pub mod verify_login;
@@ -0,0 +1,225 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher, InvitationRepository},
+};
+
+pub struct AcceptInvitationCommand {
+    pub conversation_id: ConversationId,
+    pub user_id: UserId,
+}
+
+pub struct AcceptInvitationHandler<C: ConversationRepository, I: InvitationRepository, P: EventPublisher> {
+    conversations: C,
+    invitations: I,
+    events: P,
+}
+
+impl<C: ConversationRepository, I: InvitationRepository, P: EventPublisher> AcceptInvitationHandler<C, I, P> {
+    pub fn new(conversations: C, invitations: I, events: P) -> Self {
+        Self {
+            conversations,
+            invitations,
+            events,
+        }
+    }
+
+    pub async fn handle(&self, command: AcceptInvitationCommand) -> Result<(), DomainError> {
+        let invitation = self
+            .invitations
+            .find(&command.conversation_id, &command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::InvitationNotFound)?;
+
+        let mut conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let event = match invitation.accept(&mut conversation) {
+            Ok(event) => event,
+            // the inviter can no longer add people, so the offer is gone for good
+            Err(DomainError::InvitationNotFound) => {
+                self.invitations
+                    .delete(&invitation)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?;
+                return Err(DomainError::InvitationNotFound);
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.invitations
+            .delete(&invitation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+        saved: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = true;
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockInvitationRepository {
+        invitation: Mutex<Option<Invitation>>,
+        deleted: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl InvitationRepository for MockInvitationRepository {
+        async fn find(&self, _conversation_id: &ConversationId, user_id: &UserId) -> Result<Option<Invitation>, RepoError> {
+            let mut invitation = self.invitation.lock().unwrap();
+            if invitation.as_ref().is_some_and(|i| i.user_id() == user_id) {
+                return Ok(invitation.take());
+            }
+            Ok(None)
+        }
+
+        async fn delete(&self, _invitation: &Invitation) -> Result<(), RepoError> {
+            *self.deleted.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    // a group whose owner has invited `invitee`
+    fn handler(invitee: &UserId) -> AcceptInvitationHandler<MockConversationRepository, MockInvitationRepository, MockEventPublisher> {
+        let owner = UserId::new();
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let (invitation, _) = Invitation::issue(&conversation, &owner, invitee.clone()).unwrap();
+        AcceptInvitationHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                saved: Mutex::new(false),
+            },
+            MockInvitationRepository {
+                invitation: Mutex::new(Some(invitation)),
+                deleted: Mutex::new(false),
+            },
+            MockEventPublisher::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn handle_joins_the_group_and_clears_the_invitation() {
+        let invitee = UserId::new();
+        let handler = handler(&invitee);
+
+        handler
+            .handle(AcceptInvitationCommand {
+                conversation_id: ConversationId::new(),
+                user_id: invitee.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert!(*handler.conversations.saved.lock().unwrap());
+        assert!(*handler.invitations.deleted.lock().unwrap());
+        let published = handler.events.published.lock().unwrap();
+        assert!(matches!(&*published, Some(DomainEvent::ParticipantAdded { user_id, .. }) if user_id == &invitee));
+    }
+
+    #[tokio::test]
+    async fn handle_clears_the_invitation_once_the_inviter_has_left() {
+        let invitee = UserId::new();
+        let handler = handler(&invitee);
+        let owner = handler
+            .invitations
+            .invitation
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .invited_by()
+            .clone();
+        {
+            let mut conversation = handler.conversations.conversation.lock().unwrap();
+            let conversation = conversation.as_mut().unwrap();
+            conversation.add_participant(&owner, UserId::new()).unwrap();
+            conversation.remove_participant(&owner, &owner).unwrap();
+        }
+
+        let result = handler
+            .handle(AcceptInvitationCommand {
+                conversation_id: ConversationId::new(),
+                user_id: invitee,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvitationNotFound));
+        assert!(*handler.invitations.deleted.lock().unwrap());
+        assert!(!*handler.conversations.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_users_who_were_not_invited() {
+        let handler = handler(&UserId::new());
+
+        let result = handler
+            .handle(AcceptInvitationCommand {
+                conversation_id: ConversationId::new(),
+                user_id: UserId::new(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvitationNotFound));
+        assert!(!*handler.conversations.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher, UserRepository},
+};
+
+pub struct AddParticipantsCommand {
+    pub conversation_id: ConversationId,
+    pub actor_id: UserId,
+    pub user_ids: Vec<UserId>,
+}
+
+pub struct AddParticipantsHandler<C: ConversationRepository, U: UserRepository, P: EventPublisher> {
+    conversations: C,
+    users: U,
+    events: P,
+}
+
+impl<C: ConversationRepository, U: UserRepository, P: EventPublisher> AddParticipantsHandler<C, U, P> {
+    pub fn new(conversations: C, users: U, events: P) -> Self {
+        Self {
+            conversations,
+            users,
+            events,
+        }
+    }
+
+    // all or nothing: one unknown user or existing member rejects the whole batch
+    pub async fn handle(&self, command: AddParticipantsCommand) -> Result<(), DomainError> {
+        let mut user_ids: Vec<UserId> = Vec::with_capacity(command.user_ids.len());
+        for user_id in command.user_ids {
+            if !user_ids.contains(&user_id) {
+                user_ids.push(user_id);
+            }
+        }
+
+        let mut conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let found = self
+            .users
+            .find_many(&user_ids)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        if found.len() != user_ids.len() {
+            return Err(DomainError::UserNotFound);
+        }
+
+        let mut events = Vec::with_capacity(user_ids.len());
+        for user_id in user_ids {
+            events.push(conversation.add_participant(&command.actor_id, user_id)?);
+        }
+
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        for event in events {
+            self.events.publish(event).await.ok();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::credentials::Credentials;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::{PublishError, RepoError};
+    use crate::domain::user::{DisplayName, User, Username};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+        saved_count: Mutex<Option<usize>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, conversation: &Conversation) -> Result<(), RepoError> {
+            *self.saved_count.lock().unwrap() = Some(conversation.participants().len());
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockUserRepository {
+        known: Vec<UserId>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(self.known.contains(id).then(|| {
+                User::new(
+                    id.clone(),
+                    Username::new("someone".into()).unwrap(),
+                    DisplayName::new("Someone".into()).unwrap(),
+                )
+            }))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Vec<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            self.published.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    type TestHandler = AddParticipantsHandler<MockConversationRepository, MockUserRepository, MockEventPublisher>;
+
+    fn handler(owner: &UserId, known: Vec<UserId>) -> TestHandler {
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        AddParticipantsHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                saved_count: Mutex::new(None),
+            },
+            MockUserRepository { known },
+            MockEventPublisher::default(),
+        )
+    }
+
+    fn command(actor_id: UserId, user_ids: Vec<UserId>) -> AddParticipantsCommand {
+        AddParticipantsCommand {
+            conversation_id: ConversationId::new(),
+            actor_id,
+            user_ids,
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_unknown_user_without_saving() {
+        let owner = UserId::new();
+        let known = UserId::new();
+        let handler = handler(&owner, vec![known.clone()]);
+
+        let result = handler.handle(command(owner, vec![known, UserId::new()])).await;
+
+        assert_eq!(result.err(), Some(DomainError::UserNotFound));
+        assert!(handler.conversations.saved_count.lock().unwrap().is_none());
+        assert!(handler.events.published.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_non_admin() {
+        let owner = UserId::new();
+        let stranger = UserId::new();
+        let handler = handler(&owner, vec![stranger.clone()]);
+
+        let result = handler.handle(command(UserId::new(), vec![stranger])).await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAParticipant));
+    }
+
+    #[tokio::test]
+    async fn handle_saves_new_members_and_publishes_one_event_each() {
+        let owner = UserId::new();
+        let a = UserId::new();
+        let b = UserId::new();
+        let handler = handler(&owner, vec![a.clone(), b.clone()]);
+
+        handler.handle(command(owner, vec![a.clone(), b.clone(), a.clone()])).await.unwrap();
+
+        assert_eq!(*handler.conversations.saved_count.lock().unwrap(), Some(3));
+        let published = handler.events.published.lock().unwrap();
+        let added: Vec<&UserId> = published
+            .iter()
+            .filter_map(|event| match event {
+                DomainEvent::ParticipantAdded { user_id, .. } => Some(user_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(added, vec![&a, &b]);
+    }
+}
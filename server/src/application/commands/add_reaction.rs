@@ -0,0 +1,289 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{MessageId, UserId},
+    reaction::{Emoji, Reaction},
+    repository::{ConversationRepository, EventPublisher, MessageRepository, ReactionRepository},
+};
+
+pub struct AddReactionCommand {
+    pub message_id: MessageId,
+    pub user_id: UserId,
+    pub emoji: String,
+}
+
+pub struct AddReactionHandler<C: ConversationRepository, M: MessageRepository, R: ReactionRepository, P: EventPublisher> {
+    conversations: C,
+    messages: M,
+    reactions: R,
+    events: P,
+}
+
+impl<C: ConversationRepository, M: MessageRepository, R: ReactionRepository, P: EventPublisher> AddReactionHandler<C, M, R, P> {
+    pub fn new(conversations: C, messages: M, reactions: R, events: P) -> Self {
+        Self {
+            conversations,
+            messages,
+            reactions,
+            events,
+        }
+    }
+
+    pub async fn handle(&self, command: AddReactionCommand) -> Result<(), DomainError> {
+        let emoji = Emoji::new(command.emoji)?;
+        let message = self
+            .messages
+            .find_by_id(&command.message_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::MessageNotFound)?;
+
+        let conversation = self
+            .conversations
+            .find_by_id(message.conversation_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+        if !conversation.is_participant(&command.user_id) {
+            return Err(DomainError::NotAParticipant);
+        }
+        if message.is_deleted() {
+            return Err(DomainError::MessageDeleted);
+        }
+
+        // reacting twice with the same emoji is a no-op, not an error, so double taps are harmless
+        let existing = self
+            .reactions
+            .find(message.id(), &command.user_id, &emoji)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        if existing.is_some() {
+            return Ok(());
+        }
+
+        let (reaction, event) = Reaction::add(&message, command.user_id, emoji);
+
+        self.reactions
+            .save(&reaction)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::ids::ConversationId;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{Message, MessageKind, MessageRevision};
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockMessageRepository {
+        message: Mutex<Option<Message>>,
+    }
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(self.message.lock().unwrap().take())
+        }
+
+        async fn save(&self, _message: &Message) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockReactionRepository {
+        existing: bool,
+        saved: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ReactionRepository for MockReactionRepository {
+        async fn find(&self, message_id: &MessageId, user_id: &UserId, emoji: &Emoji) -> Result<Option<Reaction>, RepoError> {
+            Ok(self.existing.then(|| {
+                Reaction::from_persistence(
+                    message_id.clone(),
+                    ConversationId::new(),
+                    user_id.clone(),
+                    emoji.clone(),
+                    chrono::Utc::now(),
+                )
+            }))
+        }
+
+        async fn save(&self, reaction: &Reaction) -> Result<(), RepoError> {
+            self.saved.lock().unwrap().push(reaction.emoji().as_str().to_string());
+            Ok(())
+        }
+
+        async fn delete(&self, _reaction: &Reaction) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    type TestHandler =
+        AddReactionHandler<MockConversationRepository, MockMessageRepository, MockReactionRepository, MockEventPublisher>;
+
+    // a group owned by `member` holding one message from them
+    fn handler(member: &UserId, reactions: MockReactionRepository) -> (TestHandler, MessageId) {
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), member.clone()).unwrap();
+        let (message, _) = Message::new(
+            MessageId::new(),
+            conversation_id,
+            member.clone(),
+            "hello".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let message_id = message.id().clone();
+
+        let handler = AddReactionHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockMessageRepository {
+                message: Mutex::new(Some(message)),
+            },
+            reactions,
+            MockEventPublisher::default(),
+        );
+        (handler, message_id)
+    }
+
+    fn command(message_id: MessageId, user_id: UserId, emoji: &str) -> AddReactionCommand {
+        AddReactionCommand {
+            message_id,
+            user_id,
+            emoji: emoji.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_invalid_emoji() {
+        let member = UserId::new();
+        let (handler, message_id) = handler(&member, MockReactionRepository::default());
+
+        let result = handler.handle(command(message_id, member, "thumbs up")).await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidEmoji));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_non_participant() {
+        let member = UserId::new();
+        let (handler, message_id) = handler(&member, MockReactionRepository::default());
+
+        let result = handler.handle(command(message_id, UserId::new(), "👍")).await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAParticipant));
+        assert!(handler.reactions.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_is_a_no_op_when_already_reacted() {
+        let member = UserId::new();
+        let (handler, message_id) = handler(
+            &member,
+            MockReactionRepository {
+                existing: true,
+                ..Default::default()
+            },
+        );
+
+        handler.handle(command(message_id, member, "👍")).await.unwrap();
+
+        assert!(handler.reactions.saved.lock().unwrap().is_empty());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_reaction_and_publishes_event() {
+        let member = UserId::new();
+        let (handler, message_id) = handler(&member, MockReactionRepository::default());
+
+        handler.handle(command(message_id.clone(), member, "👍")).await.unwrap();
+
+        assert_eq!(handler.reactions.saved.lock().unwrap().as_slice(), &["👍".to_string()]);
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::ReactionAdded {
+                message_id: event_id,
+                emoji,
+                ..
+            }) => {
+                assert_eq!(event_id, &message_id);
+                assert_eq!(emoji, "👍");
+            }
+            _ => panic!("expected ReactionAdded event"),
+        }
+    }
+}
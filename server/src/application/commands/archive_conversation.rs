@@ -0,0 +1,138 @@
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{EventPublisher, ListEntryRepository},
+};
+
+pub struct ArchiveConversationCommand {
+    pub conversation_id: ConversationId,
+    pub user_id: UserId,
+    pub archived: bool,
+}
+
+pub struct ArchiveConversationHandler<L: ListEntryRepository, P: EventPublisher, Clk: Clock> {
+    entries: L,
+    events: P,
+    clock: Clk,
+}
+
+impl<L: ListEntryRepository, P: EventPublisher, Clk: Clock> ArchiveConversationHandler<L, P, Clk> {
+    pub fn new(entries: L, events: P, clock: Clk) -> Self {
+        Self { entries, events, clock }
+    }
+
+    pub async fn handle(&self, command: ArchiveConversationCommand) -> Result<(), DomainError> {
+        let mut entry = self
+            .entries
+            .find(&command.conversation_id, &command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::NotAParticipant)?;
+
+        let Some(event) = entry.set_archived(command.archived, self.clock.now()) else {
+            return Ok(());
+        };
+
+        self.entries.save(&entry).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::list_entry::ListEntry;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    #[derive(Default)]
+    struct MockListEntryRepository {
+        entry: Mutex<Option<ListEntry>>,
+        saved: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl ListEntryRepository for MockListEntryRepository {
+        async fn find(&self, _conversation_id: &ConversationId, _user_id: &UserId) -> Result<Option<ListEntry>, RepoError> {
+            Ok(self.entry.lock().unwrap().take())
+        }
+
+        async fn save(&self, _entry: &ListEntry) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    fn handler(entry: Option<ListEntry>) -> ArchiveConversationHandler<MockListEntryRepository, MockEventPublisher, FixedClock> {
+        ArchiveConversationHandler::new(
+            MockListEntryRepository {
+                entry: Mutex::new(entry),
+                ..Default::default()
+            },
+            MockEventPublisher::default(),
+            FixedClock(Utc::now()),
+        )
+    }
+
+    fn command(archived: bool) -> ArchiveConversationCommand {
+        ArchiveConversationCommand {
+            conversation_id: ConversationId::new(),
+            user_id: UserId::new(),
+            archived,
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_archives_and_publishes() {
+        let handler = handler(Some(ListEntry::from_persistence(ConversationId::new(), UserId::new(), None, None)));
+
+        handler.handle(command(true)).await.unwrap();
+
+        assert!(*handler.entries.saved.lock().unwrap());
+        assert!(matches!(
+            *handler.events.published.lock().unwrap(),
+            Some(DomainEvent::ListEntryChanged { archived: true, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn handle_skips_saving_when_nothing_changes() {
+        let handler = handler(Some(ListEntry::from_persistence(ConversationId::new(), UserId::new(), None, None)));
+
+        handler.handle(command(false)).await.unwrap();
+
+        assert!(!*handler.entries.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_non_participants() {
+        let handler = handler(None);
+
+        let result = handler.handle(command(true)).await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAParticipant));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::domain::{
+    block::Block,
+    errors::DomainError,
+    ids::UserId,
+    repository::{BlockRepository, UserRepository},
+};
+
+pub struct BlockUserCommand {
+    pub blocker_id: UserId,
+    pub blocked_id: UserId,
+}
+
+pub struct BlockUserHandler<U: UserRepository, B: BlockRepository> {
+    users: U,
+    blocks: B,
+}
+
+impl<U: UserRepository, B: BlockRepository> BlockUserHandler<U, B> {
+    pub fn new(users: U, blocks: B) -> Self {
+        Self { users, blocks }
+    }
+
+    // blocking twice is harmless; the original block and its timestamp are kept
+    pub async fn handle(&self, command: BlockUserCommand) -> Result<(), DomainError> {
+        let block = Block::new(command.blocker_id, command.blocked_id)?;
+
+        self.users
+            .find_by_id(block.blocked_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::UserNotFound)?;
+
+        self.blocks.save(&block).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::credentials::Credentials;
+    use crate::domain::repository::RepoError;
+    use crate::domain::user::{DisplayName, User, Username};
+
+    #[derive(Default)]
+    struct MockUserRepository {
+        user: Option<UserId>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(self.user.as_ref().filter(|u| *u == id).map(|u| {
+                User::new(
+                    u.clone(),
+                    Username::new("bob".into()).unwrap(),
+                    DisplayName::new("Bob".into()).unwrap(),
+                )
+            }))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockBlockRepository {
+        saved: Mutex<Option<(UserId, UserId)>>,
+    }
+
+    #[async_trait]
+    impl BlockRepository for MockBlockRepository {
+        async fn find_by_blocker(&self, _blocker_id: &UserId) -> Result<Vec<Block>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn is_blocked_between(&self, _a: &UserId, _b: &UserId) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+
+        async fn save(&self, block: &Block) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = Some((block.blocker_id().clone(), block.blocked_id().clone()));
+            Ok(())
+        }
+
+        async fn delete(&self, _blocker_id: &UserId, _blocked_id: &UserId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_saves_the_block() {
+        let blocker = UserId::new();
+        let blocked = UserId::new();
+        let handler = BlockUserHandler::new(
+            MockUserRepository {
+                user: Some(blocked.clone()),
+            },
+            MockBlockRepository::default(),
+        );
+
+        handler
+            .handle(BlockUserCommand {
+                blocker_id: blocker.clone(),
+                blocked_id: blocked.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(*handler.blocks.saved.lock().unwrap(), Some((blocker, blocked)));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_unknown_users() {
+        let handler = BlockUserHandler::new(MockUserRepository::default(), MockBlockRepository::default());
+
+        let result = handler
+            .handle(BlockUserCommand {
+                blocker_id: UserId::new(),
+                blocked_id: UserId::new(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::UserNotFound));
+        assert!(handler.blocks.saved.lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ScheduledMessageId, UserId},
+    repository::ScheduledMessageRepository,
+};
+
+pub struct CancelScheduledMessageCommand {
+    pub id: ScheduledMessageId,
+    pub user_id: UserId,
+}
+
+pub struct CancelScheduledMessageHandler<S: ScheduledMessageRepository> {
+    scheduled: S,
+}
+
+impl<S: ScheduledMessageRepository> CancelScheduledMessageHandler<S> {
+    pub fn new(scheduled: S) -> Self {
+        Self { scheduled }
+    }
+
+    pub async fn handle(&self, command: CancelScheduledMessageCommand) -> Result<(), DomainError> {
+        let mut scheduled = self
+            .scheduled
+            .find_by_id(&command.id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ScheduledMessageNotFound)?;
+
+        scheduled.cancel(&command.user_id)?;
+
+        let cancelled = self
+            .scheduled
+            .cancel(&scheduled)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        if !cancelled {
+            return Err(DomainError::ScheduledMessageNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{MessageId, UserId},
+    poll::PollVote,
+    repository::{ConversationRepository, EventPublisher, MessageRepository, PollVoteRepository},
+};
+
+pub struct CastVoteCommand {
+    pub message_id: MessageId,
+    pub user_id: UserId,
+    pub choices: Vec<usize>,
+}
+
+pub struct CastVoteHandler<C: ConversationRepository, M: MessageRepository, V: PollVoteRepository, P: EventPublisher, Clk: Clock> {
+    conversations: C,
+    messages: M,
+    votes: V,
+    events: P,
+    clock: Clk,
+}
+
+impl<C: ConversationRepository, M: MessageRepository, V: PollVoteRepository, P: EventPublisher, Clk: Clock>
+    CastVoteHandler<C, M, V, P, Clk>
+{
+    pub fn new(conversations: C, messages: M, votes: V, events: P, clock: Clk) -> Self {
+        Self {
+            conversations,
+            messages,
+            votes,
+            events,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: CastVoteCommand) -> Result<(), DomainError> {
+        let message = self
+            .messages
+            .find_by_id(&command.message_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::MessageNotFound)?;
+
+        let conversation = self
+            .conversations
+            .find_by_id(message.conversation_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+        if !conversation.is_participant(&command.user_id) {
+            return Err(DomainError::NotAParticipant);
+        }
+
+        let (vote, event) = PollVote::cast(&message, command.user_id, command.choices, self.clock.now())?;
+
+        self.votes.save(&vote).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::ids::ConversationId;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{Message, MessageRevision};
+    use crate::domain::poll::Poll;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockMessageRepository {
+        message: Mutex<Option<Message>>,
+    }
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(self.message.lock().unwrap().take())
+        }
+
+        async fn save(&self, _message: &Message) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockPollVoteRepository {
+        saved: Mutex<Vec<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl PollVoteRepository for MockPollVoteRepository {
+        async fn find(&self, _message_id: &MessageId, _user_id: &UserId) -> Result<Option<PollVote>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, vote: &PollVote) -> Result<(), RepoError> {
+            self.saved.lock().unwrap().push(vote.choices().clone());
+            Ok(())
+        }
+
+        async fn delete(&self, _vote: &PollVote) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    type TestHandler =
+        CastVoteHandler<MockConversationRepository, MockMessageRepository, MockPollVoteRepository, MockEventPublisher, FixedClock>;
+
+    // a group owned by `member` holding a single-choice poll from them that closes in an hour
+    fn handler(member: &UserId, now: DateTime<Utc>) -> (TestHandler, MessageId) {
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), member.clone()).unwrap();
+        let poll = Poll::new(vec!["yes".into(), "no".into()], false, Some(Utc::now() + Duration::hours(1))).unwrap();
+        let (message, _) = Message::new_poll(
+            MessageId::new(),
+            conversation_id,
+            member.clone(),
+            "Lunch?".into(),
+            poll,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let message_id = message.id().clone();
+
+        let handler = CastVoteHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockMessageRepository {
+                message: Mutex::new(Some(message)),
+            },
+            MockPollVoteRepository::default(),
+            MockEventPublisher::default(),
+            FixedClock(now),
+        );
+        (handler, message_id)
+    }
+
+    #[tokio::test]
+    async fn records_the_vote_and_publishes_it() {
+        let member = UserId::new();
+        let (handler, message_id) = handler(&member, Utc::now());
+
+        handler
+            .handle(CastVoteCommand {
+                message_id,
+                user_id: member,
+                choices: vec![1],
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(*handler.votes.saved.lock().unwrap(), vec![vec![1]]);
+        assert!(matches!(
+            handler.events.published.lock().unwrap().as_ref(),
+            Some(DomainEvent::PollVoteCast { choices, .. }) if choices == &vec![1]
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_outsiders() {
+        let (handler, message_id) = handler(&UserId::new(), Utc::now());
+
+        let result = handler
+            .handle(CastVoteCommand {
+                message_id,
+                user_id: UserId::new(),
+                choices: vec![0],
+            })
+            .await;
+
+        assert_eq!(result, Err(DomainError::NotAParticipant));
+        assert!(handler.votes.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_votes_after_the_poll_closes() {
+        let member = UserId::new();
+        let (handler, message_id) = handler(&member, Utc::now() + Duration::hours(2));
+
+        let result = handler
+            .handle(CastVoteCommand {
+                message_id,
+                user_id: member,
+                choices: vec![0],
+            })
+            .await;
+
+        assert_eq!(result, Err(DomainError::PollClosed));
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+}
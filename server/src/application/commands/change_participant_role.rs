@@ -0,0 +1,176 @@
+use crate::domain::{
+    conversation::ParticipantRole,
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher},
+};
+
+pub struct ChangeParticipantRoleCommand {
+    pub conversation_id: ConversationId,
+    pub actor_id: UserId,
+    pub user_id: UserId,
+    pub role: ParticipantRole,
+}
+
+pub struct ChangeParticipantRoleHandler<C: ConversationRepository, P: EventPublisher> {
+    conversations: C,
+    events: P,
+}
+
+impl<C: ConversationRepository, P: EventPublisher> ChangeParticipantRoleHandler<C, P> {
+    pub fn new(conversations: C, events: P) -> Self {
+        Self { conversations, events }
+    }
+
+    pub async fn handle(&self, command: ChangeParticipantRoleCommand) -> Result<(), DomainError> {
+        let mut conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let event = conversation.change_role(&command.actor_id, &command.user_id, command.role)?;
+
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+        saved_role: Mutex<Option<ParticipantRole>>,
+        watched: UserId,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, conversation: &Conversation) -> Result<(), RepoError> {
+            *self.saved_role.lock().unwrap() = conversation.role_of(&self.watched);
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    fn handler(owner: &UserId, member: &UserId) -> ChangeParticipantRoleHandler<MockConversationRepository, MockEventPublisher> {
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        conversation.add_participant(owner, member.clone()).unwrap();
+        ChangeParticipantRoleHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                saved_role: Mutex::new(None),
+                watched: member.clone(),
+            },
+            MockEventPublisher::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn handle_returns_conversation_not_found_when_missing() {
+        let handler = ChangeParticipantRoleHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(None),
+                saved_role: Mutex::new(None),
+                watched: UserId::new(),
+            },
+            MockEventPublisher::default(),
+        );
+
+        let result = handler
+            .handle(ChangeParticipantRoleCommand {
+                conversation_id: ConversationId::new(),
+                actor_id: UserId::new(),
+                user_id: UserId::new(),
+                role: ParticipantRole::Admin,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::ConversationNotFound));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_non_owner() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let handler = handler(&owner, &member);
+
+        let result = handler
+            .handle(ChangeParticipantRoleCommand {
+                conversation_id: ConversationId::new(),
+                actor_id: member.clone(),
+                user_id: member,
+                role: ParticipantRole::Admin,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAnAdmin));
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_promotion_and_publishes_event() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let handler = handler(&owner, &member);
+
+        handler
+            .handle(ChangeParticipantRoleCommand {
+                conversation_id: ConversationId::new(),
+                actor_id: owner,
+                user_id: member.clone(),
+                role: ParticipantRole::Admin,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(*handler.conversations.saved_role.lock().unwrap(), Some(ParticipantRole::Admin));
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::ParticipantRoleChanged { user_id, role, .. }) => {
+                assert_eq!(user_id, &member);
+                assert_eq!(role, &ParticipantRole::Admin);
+            }
+            _ => panic!("expected ParticipantRoleChanged event"),
+        }
+    }
+}
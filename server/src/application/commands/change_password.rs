@@ -0,0 +1,176 @@
+use crate::domain::credentials::Password;
+use crate::domain::errors::DomainError;
+use crate::domain::ids::{SessionId, UserId};
+use crate::domain::repository::{CredentialRepository, SessionRepository};
+
+pub struct ChangePasswordCommand {
+    pub user_id: UserId,
+    pub session_id: SessionId,
+    pub current_password: String,
+    pub new_password: String,
+}
+
+pub struct ChangePasswordHandler<K: CredentialRepository, S: SessionRepository> {
+    credentials: K,
+    sessions: S,
+}
+
+impl<K: CredentialRepository, S: SessionRepository> ChangePasswordHandler<K, S> {
+    pub fn new(credentials: K, sessions: S) -> Self {
+        Self { credentials, sessions }
+    }
+
+    pub async fn handle(&self, command: ChangePasswordCommand) -> Result<(), DomainError> {
+        let new_password = Password::new(command.new_password)?;
+        let mut credentials = self
+            .credentials
+            .find_by_user_id(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::InvalidCredentials)?;
+
+        let current_password = command.current_password;
+        let credentials = tokio::task::spawn_blocking(move || {
+            credentials.change(&current_password, &new_password)?;
+            Ok::<_, DomainError>(credentials)
+        })
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))??;
+
+        self.credentials
+            .save(&credentials)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        // sign out every other device, the session making the change stays valid
+        self.sessions
+            .delete_all_for_user_except(&command.user_id, &command.session_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::credentials::Credentials;
+    use crate::domain::repository::RepoError;
+    use crate::domain::session::Session;
+
+    struct MockCredentialRepository {
+        credentials: Mutex<Option<Credentials>>,
+    }
+
+    #[async_trait]
+    impl CredentialRepository for MockCredentialRepository {
+        async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Option<Credentials>, RepoError> {
+            Ok(self.credentials.lock().unwrap().take())
+        }
+
+        async fn save(&self, credentials: &Credentials) -> Result<(), RepoError> {
+            *self.credentials.lock().unwrap() = Some(Credentials::from_persistence(
+                credentials.user_id().clone(),
+                credentials.password_hash().clone(),
+                *credentials.updated_at(),
+            ));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockSessionRepository {
+        kept: Mutex<Option<SessionId>>,
+    }
+
+    #[async_trait]
+    impl SessionRepository for MockSessionRepository {
+        async fn find_by_token_hash(&self, _token_hash: &[u8]) -> Result<Option<Session>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _session: &Session) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: &SessionId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete_all_for_user_except(&self, _user_id: &UserId, keep: &SessionId) -> Result<(), RepoError> {
+            *self.kept.lock().unwrap() = Some(keep.clone());
+            Ok(())
+        }
+    }
+
+    fn handler(user_id: &UserId) -> ChangePasswordHandler<MockCredentialRepository, MockSessionRepository> {
+        let credentials = Credentials::new(user_id.clone(), &Password::new("correct horse".into()).unwrap()).unwrap();
+        ChangePasswordHandler::new(
+            MockCredentialRepository {
+                credentials: Mutex::new(Some(credentials)),
+            },
+            MockSessionRepository::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_wrong_current_password() {
+        let user_id = UserId::new();
+        let handler = handler(&user_id);
+
+        let result = handler
+            .handle(ChangePasswordCommand {
+                user_id,
+                session_id: SessionId::new(),
+                current_password: "battery staple".into(),
+                new_password: "new password".into(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidCredentials));
+        assert!(handler.sessions.kept.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_short_new_password() {
+        let user_id = UserId::new();
+        let handler = handler(&user_id);
+
+        let result = handler
+            .handle(ChangePasswordCommand {
+                user_id,
+                session_id: SessionId::new(),
+                current_password: "correct horse".into(),
+                new_password: "short".into(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::PasswordTooShort));
+    }
+
+    #[tokio::test]
+    async fn handle_saves_new_hash_and_revokes_other_sessions() {
+        let user_id = UserId::new();
+        let session_id = SessionId::new();
+        let handler = handler(&user_id);
+
+        handler
+            .handle(ChangePasswordCommand {
+                user_id,
+                session_id: session_id.clone(),
+                current_password: "correct horse".into(),
+                new_password: "new password".into(),
+            })
+            .await
+            .unwrap();
+
+        let saved = handler.credentials.credentials.lock().unwrap().take().unwrap();
+        assert!(saved.verify("new password"));
+        assert_eq!(handler.sessions.kept.lock().unwrap().as_ref(), Some(&session_id));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::domain::clock::Clock;
+use crate::domain::errors::DomainError;
+use crate::domain::ids::UserId;
+use crate::domain::repository::{TotpRepository, UserRepository};
+
+pub struct ConfirmTotpCommand {
+    pub user_id: UserId,
+    pub code: String,
+}
+
+pub struct ConfirmTotpHandler<U: UserRepository, T: TotpRepository, Clk: Clock> {
+    users: U,
+    totp: T,
+    clock: Clk,
+}
+
+impl<U: UserRepository, T: TotpRepository, Clk: Clock> ConfirmTotpHandler<U, T, Clk> {
+    pub fn new(users: U, totp: T, clock: Clk) -> Self {
+        Self { users, totp, clock }
+    }
+
+    // returns the recovery codes in plaintext, they are not retrievable afterwards
+    pub async fn handle(&self, command: ConfirmTotpCommand) -> Result<Vec<String>, DomainError> {
+        let mut enrollment = self
+            .totp
+            .find_by_user_id(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::TwoFactorNotEnabled)?;
+
+        let recovery_codes = enrollment.confirm(&command.code, self.clock.now())?;
+
+        let mut user = self
+            .users
+            .find_by_id(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::UserNotFound)?;
+        user.enable_two_factor()?;
+
+        self.totp
+            .save(&enrollment)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.users.save(&user).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(recovery_codes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::credentials::Credentials;
+    use crate::domain::repository::RepoError;
+    use crate::domain::two_factor::{RECOVERY_CODE_COUNT, TOTP_STEP_SECONDS, TotpEnrollment, TwoFactorUse};
+    use crate::domain::user::{DisplayName, User, Username};
+
+    #[derive(Default)]
+    struct MockUserRepository {
+        saved_two_factor_enabled: Mutex<Option<bool>>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(Some(User::new(
+                id.clone(),
+                Username::new("alice".into()).unwrap(),
+                DisplayName::new("Alice".into()).unwrap(),
+            )))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, user: &User) -> Result<(), RepoError> {
+            *self.saved_two_factor_enabled.lock().unwrap() = Some(*user.two_factor_enabled());
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    struct MockTotpRepository {
+        enrollment: Mutex<Option<TotpEnrollment>>,
+    }
+
+    #[async_trait]
+    impl TotpRepository for MockTotpRepository {
+        async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Option<TotpEnrollment>, RepoError> {
+            Ok(self.enrollment.lock().unwrap().take())
+        }
+
+        async fn save(&self, enrollment: &TotpEnrollment) -> Result<(), RepoError> {
+            *self.enrollment.lock().unwrap() = Some(TotpEnrollment::from_persistence(
+                enrollment.user_id().clone(),
+                enrollment.secret().clone(),
+                *enrollment.created_at(),
+                *enrollment.confirmed_at(),
+                *enrollment.last_used_step(),
+                enrollment.recovery_codes().clone(),
+            ));
+            Ok(())
+        }
+
+        async fn redeem(&self, _user_id: &UserId, _used: &TwoFactorUse, _now: DateTime<Utc>) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn delete(&self, _user_id: &UserId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap()
+    }
+
+    fn handler(user_id: &UserId) -> (ConfirmTotpHandler<MockUserRepository, MockTotpRepository, FixedClock>, String) {
+        let enrollment = TotpEnrollment::begin(user_id.clone(), now());
+        let code = enrollment.secret().code_at(now().timestamp() / TOTP_STEP_SECONDS);
+        let handler = ConfirmTotpHandler::new(
+            MockUserRepository::default(),
+            MockTotpRepository {
+                enrollment: Mutex::new(Some(enrollment)),
+            },
+            FixedClock(now()),
+        );
+        (handler, code)
+    }
+
+    #[tokio::test]
+    async fn handle_confirms_enrollment_and_enables_two_factor() {
+        let user_id = UserId::new();
+        let (handler, code) = handler(&user_id);
+
+        let recovery_codes = handler.handle(ConfirmTotpCommand { user_id, code }).await.unwrap();
+
+        assert_eq!(recovery_codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(*handler.users.saved_two_factor_enabled.lock().unwrap(), Some(true));
+        let enrollment = handler.totp.enrollment.lock().unwrap();
+        assert!(enrollment.as_ref().unwrap().is_confirmed());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_wrong_code_without_enabling() {
+        let user_id = UserId::new();
+        let (handler, _) = handler(&user_id);
+
+        let result = handler
+            .handle(ConfirmTotpCommand {
+                user_id,
+                code: "not-a-code".into(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidTwoFactorCode));
+        assert!(handler.users.saved_two_factor_enabled.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_when_nothing_is_pending() {
+        let user_id = UserId::new();
+        let (handler, code) = handler(&user_id);
+        *handler.totp.enrollment.lock().unwrap() = None;
+
+        let result = handler.handle(ConfirmTotpCommand { user_id, code }).await;
+
+        assert_eq!(result.err(), Some(DomainError::TwoFactorNotEnabled));
+    }
+}
@@ -2,7 +2,8 @@ use crate::domain::conversation::{Conversation, ConversationKind};
 use crate::domain::errors::DomainError;
 use crate::domain::events::DomainEvent;
 use crate::domain::ids::{ConversationId, UserId};
-use crate::domain::repository::{ConversationRepository, EventPublisher};
+use crate::domain::invitation::Invitation;
+use crate::domain::repository::{BlockRepository, ConversationRepository, EventPublisher, UserRepository};
 
 pub struct CreateConversationCommand {
     pub kind: ConversationKind,
@@ -11,18 +12,27 @@ pub struct CreateConversationCommand {
     pub title: Option<String>,
 }
 
-pub struct CreateConversationHandler<C: ConversationRepository, P: EventPublisher> {
+pub struct CreateConversationHandler<C: ConversationRepository, U: UserRepository, B: BlockRepository, P: EventPublisher> {
     conversations: C,
+    users: U,
+    blocks: B,
     events: P,
 }
 
-impl<C: ConversationRepository, P: EventPublisher> CreateConversationHandler<C, P> {
-    pub fn new(conversations: C, events: P) -> Self {
-        Self { conversations, events }
+impl<C: ConversationRepository, U: UserRepository, B: BlockRepository, P: EventPublisher> CreateConversationHandler<C, U, B, P> {
+    pub fn new(conversations: C, users: U, blocks: B, events: P) -> Self {
+        Self {
+            conversations,
+            users,
+            blocks,
+            events,
+        }
     }
 
     pub async fn handle(&self, command: CreateConversationCommand) -> Result<ConversationId, DomainError> {
         let id = ConversationId::new();
+        let mut invitations: Vec<Invitation> = Vec::new();
+        let mut invited: Vec<DomainEvent> = Vec::new();
 
         let conversation = match command.kind {
             ConversationKind::Direct => {
@@ -31,28 +41,63 @@ impl<C: ConversationRepository, P: EventPublisher> CreateConversationHandler<C,
                     .into_iter()
                     .find(|p| p != &command.creator_id)
                     .ok_or(DomainError::DirectWithSelf)?;
+                if self
+                    .blocks
+                    .is_blocked_between(&command.creator_id, &other)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?
+                {
+                    return Err(DomainError::Blocked);
+                }
+                if let Some(existing) = self.find_direct(&command.creator_id, &other).await? {
+                    return Ok(existing);
+                }
                 Conversation::new_direct(id, command.creator_id, other)?
             }
             ConversationKind::Group => {
                 let title = command.title.ok_or(DomainError::GroupNeedsTitle)?;
-                let mut conversation = Conversation::new_group(id, title, command.creator_id.clone())?;
+                let conversation = Conversation::new_group(id, title, command.creator_id.clone())?;
 
+                let mut invitees: Vec<UserId> = Vec::with_capacity(command.participants.len());
                 for participant in command.participants {
-                    if participant == command.creator_id {
-                        continue;
+                    if participant != command.creator_id && !invitees.contains(&participant) {
+                        invitees.push(participant);
                     }
-                    conversation.add_participant(participant)?;
+                }
+                let found = self
+                    .users
+                    .find_many(&invitees)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?;
+                if found.len() != invitees.len() {
+                    return Err(DomainError::UserNotFound);
+                }
+
+                // everyone else listed is only invited; they join when they accept
+                for invitee in invitees {
+                    let (invitation, event) = Invitation::issue(&conversation, &command.creator_id, invitee)?;
+                    invitations.push(invitation);
+                    invited.push(event);
                 }
 
                 conversation
             }
         };
 
-        self.conversations
-            .save(&conversation)
-            .await
-            .map_err(|e| DomainError::Internal(e.to_string()))?;
-
+        let saved = match conversation.kind() {
+            ConversationKind::Direct => self.conversations.save(&conversation).await,
+            ConversationKind::Group => self.conversations.create_group(&conversation, &invitations).await,
+        };
+        if let Err(e) = saved {
+            // lost a race with the other user opening the same chat: hand back theirs
+            if let [a, b] = conversation.participants().as_slice()
+                && matches!(conversation.kind(), ConversationKind::Direct)
+                && let Some(existing) = self.find_direct(&a.user_id, &b.user_id).await?
+            {
+                return Ok(existing);
+            }
+            return Err(DomainError::Internal(e.to_string()));
+        }
         // notify every participant (including the creator) that this conversation now includes them
         for participant in conversation.participants() {
             self.events
@@ -63,9 +108,21 @@ impl<C: ConversationRepository, P: EventPublisher> CreateConversationHandler<C,
                 .await
                 .ok();
         }
+        for event in invited {
+            self.events.publish(event).await.ok();
+        }
 
         Ok(conversation.id().clone())
     }
+
+    async fn find_direct(&self, a: &UserId, b: &UserId) -> Result<Option<ConversationId>, DomainError> {
+        let existing = self
+            .conversations
+            .find_direct_between(a, b)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        Ok(existing.map(|c| c.id().clone()))
+    }
 }
 
 #[cfg(test)]
@@ -75,9 +132,17 @@ mod tests {
     use async_trait::async_trait;
 
     use super::*;
+    use crate::domain::block::Block;
+    use crate::domain::credentials::Credentials;
     use crate::domain::repository::{PublishError, RepoError};
+    use crate::domain::user::{DisplayName, User, Username};
 
-    struct MockConversationRepository;
+    #[derive(Default)]
+    struct MockConversationRepository {
+        direct: Mutex<Option<Conversation>>,
+        saved: Mutex<bool>,
+        invited: Mutex<Vec<UserId>>,
+    }
 
     #[async_trait]
     impl ConversationRepository for MockConversationRepository {
@@ -85,7 +150,83 @@ mod tests {
             Ok(None)
         }
 
+        async fn find_direct_between(&self, a: &UserId, b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            let direct = self.direct.lock().unwrap();
+            Ok(direct
+                .as_ref()
+                .filter(|c| c.is_participant(a) && c.is_participant(b))
+                .map(|c| Conversation::new_direct(c.id().clone(), a.clone(), b.clone()).unwrap()))
+        }
+
         async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = true;
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, invitations: &[Invitation]) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = true;
+            self.invited
+                .lock()
+                .unwrap()
+                .extend(invitations.iter().map(|invitation| invitation.user_id().clone()));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockUserRepository {
+        known: Vec<UserId>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(self.known.contains(id).then(|| {
+                User::new(
+                    id.clone(),
+                    Username::new("someone".into()).unwrap(),
+                    DisplayName::new("Someone".into()).unwrap(),
+                )
+            }))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockBlockRepository {
+        blocked: bool,
+    }
+
+    #[async_trait]
+    impl BlockRepository for MockBlockRepository {
+        async fn find_by_blocker(&self, _blocker_id: &UserId) -> Result<Vec<Block>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn is_blocked_between(&self, _a: &UserId, _b: &UserId) -> Result<bool, RepoError> {
+            Ok(self.blocked)
+        }
+
+        async fn save(&self, _block: &Block) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, _blocker_id: &UserId, _blocked_id: &UserId) -> Result<(), RepoError> {
             Ok(())
         }
     }
@@ -105,7 +246,12 @@ mod tests {
 
     #[tokio::test]
     async fn handle_publishes_participant_added_for_both_sides_of_a_direct_conversation() {
-        let handler = CreateConversationHandler::new(MockConversationRepository, MockEventPublisher::default());
+        let handler = CreateConversationHandler::new(
+            MockConversationRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockEventPublisher::default(),
+        );
         let creator = UserId::new();
         let other = UserId::new();
 
@@ -133,28 +279,70 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn handle_publishes_participant_added_for_every_group_member() {
-        let handler = CreateConversationHandler::new(MockConversationRepository, MockEventPublisher::default());
+    async fn handle_adds_only_the_creator_to_a_group_and_invites_the_rest() {
         let creator = UserId::new();
         let member = UserId::new();
+        let handler = CreateConversationHandler::new(
+            MockConversationRepository::default(),
+            MockUserRepository {
+                known: vec![member.clone()],
+            },
+            MockBlockRepository::default(),
+            MockEventPublisher::default(),
+        );
 
         handler
             .handle(CreateConversationCommand {
                 kind: ConversationKind::Group,
                 creator_id: creator.clone(),
-                participants: vec![creator.clone(), member.clone()],
+                participants: vec![creator.clone(), member.clone(), member.clone()],
                 title: Some("Group".into()),
             })
             .await
             .unwrap();
 
+        assert_eq!(*handler.conversations.invited.lock().unwrap(), vec![member.clone()]);
         let published = handler.events.published.lock().unwrap();
         assert_eq!(published.len(), 2);
+        assert!(matches!(&published[0], DomainEvent::ParticipantAdded { user_id, .. } if user_id == &creator));
+        assert!(matches!(&published[1], DomainEvent::InvitationReceived { user_id, .. } if user_id == &member));
+    }
+
+    #[tokio::test]
+    async fn handle_creates_nothing_when_an_invitee_does_not_exist() {
+        let creator = UserId::new();
+        let member = UserId::new();
+        let handler = CreateConversationHandler::new(
+            MockConversationRepository::default(),
+            MockUserRepository {
+                known: vec![member.clone()],
+            },
+            MockBlockRepository::default(),
+            MockEventPublisher::default(),
+        );
+
+        let result = handler
+            .handle(CreateConversationCommand {
+                kind: ConversationKind::Group,
+                creator_id: creator,
+                participants: vec![member, UserId::new()],
+                title: Some("Group".into()),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::UserNotFound));
+        assert!(!*handler.conversations.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_empty());
     }
 
     #[tokio::test]
     async fn handle_rejects_direct_without_a_second_participant() {
-        let handler = CreateConversationHandler::new(MockConversationRepository, MockEventPublisher::default());
+        let handler = CreateConversationHandler::new(
+            MockConversationRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockEventPublisher::default(),
+        );
         let creator = UserId::new();
 
         let result = handler
@@ -169,4 +357,59 @@ mod tests {
         assert_eq!(result.err(), Some(DomainError::DirectWithSelf));
         assert!(handler.events.published.lock().unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn handle_returns_existing_direct_conversation_for_the_same_pair() {
+        let creator = UserId::new();
+        let other = UserId::new();
+        let existing = Conversation::new_direct(ConversationId::new(), other.clone(), creator.clone()).unwrap();
+        let existing_id = existing.id().clone();
+        let handler = CreateConversationHandler::new(
+            MockConversationRepository {
+                direct: Mutex::new(Some(existing)),
+                ..Default::default()
+            },
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockEventPublisher::default(),
+        );
+
+        let id = handler
+            .handle(CreateConversationCommand {
+                kind: ConversationKind::Direct,
+                creator_id: creator.clone(),
+                participants: vec![other],
+                title: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(id, existing_id);
+        assert!(!*handler.conversations.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_refuses_a_direct_conversation_between_blocked_users() {
+        let handler = CreateConversationHandler::new(
+            MockConversationRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository { blocked: true },
+            MockEventPublisher::default(),
+        );
+        let creator = UserId::new();
+
+        let result = handler
+            .handle(CreateConversationCommand {
+                kind: ConversationKind::Direct,
+                creator_id: creator.clone(),
+                participants: vec![UserId::new()],
+                title: None,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::Blocked));
+        assert!(!*handler.conversations.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,165 @@
+use chrono::Duration;
+
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    invite::Invite,
+    repository::{ConversationRepository, InviteRepository},
+};
+
+pub struct CreateInviteCommand {
+    pub conversation_id: ConversationId,
+    pub actor_id: UserId,
+    pub expires_in: Option<Duration>,
+    pub max_uses: Option<i32>,
+}
+
+pub struct CreateInviteHandler<C: ConversationRepository, I: InviteRepository, Clk: Clock> {
+    conversations: C,
+    invites: I,
+    clock: Clk,
+}
+
+impl<C: ConversationRepository, I: InviteRepository, Clk: Clock> CreateInviteHandler<C, I, Clk> {
+    pub fn new(conversations: C, invites: I, clock: Clk) -> Self {
+        Self {
+            conversations,
+            invites,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: CreateInviteCommand) -> Result<Invite, DomainError> {
+        let conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let now = self.clock.now();
+        let invite = Invite::create(
+            &conversation,
+            &command.actor_id,
+            command.expires_in.map(|ttl| now + ttl),
+            command.max_uses,
+            now,
+        )?;
+
+        self.invites.save(&invite).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(invite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::ids::InviteId;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::RepoError;
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockInviteRepository {
+        saved: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl InviteRepository for MockInviteRepository {
+        async fn find_by_id(&self, _id: &InviteId) -> Result<Option<Invite>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_by_token(&self, _token: &str) -> Result<Option<Invite>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, invite: &Invite) -> Result<(), RepoError> {
+            self.saved.lock().unwrap().push(invite.token().clone());
+            Ok(())
+        }
+
+        async fn record_use(&self, _invite: &Invite, _conversation: &Conversation, _now: DateTime<Utc>) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    fn handler(owner: &UserId) -> CreateInviteHandler<MockConversationRepository, MockInviteRepository, FixedClock> {
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        CreateInviteHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockInviteRepository::default(),
+            FixedClock(Utc::now()),
+        )
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_non_participant() {
+        let handler = handler(&UserId::new());
+
+        let result = handler
+            .handle(CreateInviteCommand {
+                conversation_id: ConversationId::new(),
+                actor_id: UserId::new(),
+                expires_in: None,
+                max_uses: None,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAParticipant));
+        assert!(handler.invites.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_invite_expiring_relative_to_clock() {
+        let owner = UserId::new();
+        let handler = handler(&owner);
+
+        let invite = handler
+            .handle(CreateInviteCommand {
+                conversation_id: ConversationId::new(),
+                actor_id: owner,
+                expires_in: Some(Duration::hours(24)),
+                max_uses: Some(10),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(*invite.expires_at(), Some(handler.clock.0 + Duration::hours(24)));
+        assert_eq!(*invite.max_uses(), Some(10));
+        assert_eq!(handler.invites.saved.lock().unwrap().as_slice(), &[invite.token().clone()]);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::InvitationRepository,
+};
+
+pub struct DeclineInvitationCommand {
+    pub conversation_id: ConversationId,
+    pub user_id: UserId,
+}
+
+pub struct DeclineInvitationHandler<I: InvitationRepository> {
+    invitations: I,
+}
+
+impl<I: InvitationRepository> DeclineInvitationHandler<I> {
+    pub fn new(invitations: I) -> Self {
+        Self { invitations }
+    }
+
+    pub async fn handle(&self, command: DeclineInvitationCommand) -> Result<(), DomainError> {
+        let invitation = self
+            .invitations
+            .find(&command.conversation_id, &command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::InvitationNotFound)?;
+
+        self.invitations
+            .delete(&invitation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(())
+    }
+}
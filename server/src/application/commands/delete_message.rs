@@ -0,0 +1,245 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{MessageId, UserId},
+    repository::{ConversationRepository, EventPublisher, MessageRepository},
+};
+
+pub struct DeleteMessageCommand {
+    pub message_id: MessageId,
+    pub actor_id: UserId,
+}
+
+pub struct DeleteMessageHandler<C: ConversationRepository, M: MessageRepository, P: EventPublisher> {
+    conversations: C,
+    messages: M,
+    events: P,
+}
+
+impl<C: ConversationRepository, M: MessageRepository, P: EventPublisher> DeleteMessageHandler<C, M, P> {
+    pub fn new(conversations: C, messages: M, events: P) -> Self {
+        Self {
+            conversations,
+            messages,
+            events,
+        }
+    }
+
+    pub async fn handle(&self, command: DeleteMessageCommand) -> Result<(), DomainError> {
+        let mut message = self
+            .messages
+            .find_by_id(&command.message_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::MessageNotFound)?;
+
+        let conversation = self
+            .conversations
+            .find_by_id(message.conversation_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let event = message.delete(&command.actor_id, &conversation)?;
+
+        self.messages
+            .save(&message)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::ids::ConversationId;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{Message, MessageKind, MessageRevision};
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockMessageRepository {
+        message: Mutex<Option<Message>>,
+        saved: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(self.message.lock().unwrap().take())
+        }
+
+        async fn save(&self, message: &Message) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = Some(message.content().clone());
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    type TestHandler = DeleteMessageHandler<MockConversationRepository, MockMessageRepository, MockEventPublisher>;
+
+    // a group owned by `owner` where `sender` has posted one message
+    fn handler(owner: &UserId, sender: &UserId) -> (TestHandler, MessageId) {
+        let conversation_id = ConversationId::new();
+        let mut conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), owner.clone()).unwrap();
+        conversation.add_participant(owner, sender.clone()).unwrap();
+        let (message, _) = Message::new(
+            MessageId::new(),
+            conversation_id,
+            sender.clone(),
+            "hello".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let message_id = message.id().clone();
+
+        let handler = DeleteMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockMessageRepository {
+                message: Mutex::new(Some(message)),
+                saved: Mutex::new(None),
+            },
+            MockEventPublisher::default(),
+        );
+        (handler, message_id)
+    }
+
+    #[tokio::test]
+    async fn handle_returns_not_found_when_message_missing() {
+        let owner = UserId::new();
+        let (handler, _) = handler(&owner, &UserId::new());
+        handler.messages.message.lock().unwrap().take();
+
+        let result = handler
+            .handle(DeleteMessageCommand {
+                message_id: MessageId::new(),
+                actor_id: owner,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::MessageNotFound));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_member_deleting_someone_elses_message() {
+        let owner = UserId::new();
+        let sender = UserId::new();
+        let (handler, message_id) = handler(&owner, &sender);
+        let bystander = UserId::new();
+        handler
+            .conversations
+            .conversation
+            .lock()
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .add_participant(&owner, bystander.clone())
+            .unwrap();
+
+        let result = handler
+            .handle(DeleteMessageCommand {
+                message_id,
+                actor_id: bystander,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::NotYourMessage));
+        assert!(handler.messages.saved.lock().unwrap().is_none());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_lets_admin_delete_and_publishes_event() {
+        let owner = UserId::new();
+        let sender = UserId::new();
+        let (handler, message_id) = handler(&owner, &sender);
+
+        handler
+            .handle(DeleteMessageCommand {
+                message_id: message_id.clone(),
+                actor_id: owner.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(handler.messages.saved.lock().unwrap().as_deref(), Some(""));
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::MessageDeleted {
+                message_id: event_id,
+                deleted_by,
+                ..
+            }) => {
+                assert_eq!(event_id, &message_id);
+                assert_eq!(deleted_by, &owner);
+            }
+            _ => panic!("expected MessageDeleted event"),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::domain::clock::Clock;
+use crate::domain::errors::DomainError;
+use crate::domain::ids::UserId;
+use crate::domain::repository::{CredentialRepository, TotpRepository, UserRepository};
+
+pub struct DisableTotpCommand {
+    pub user_id: UserId,
+    pub password: String,
+    pub code: String,
+}
+
+pub struct DisableTotpHandler<U: UserRepository, K: CredentialRepository, T: TotpRepository, Clk: Clock> {
+    users: U,
+    credentials: K,
+    totp: T,
+    clock: Clk,
+}
+
+impl<U: UserRepository, K: CredentialRepository, T: TotpRepository, Clk: Clock> DisableTotpHandler<U, K, T, Clk> {
+    pub fn new(users: U, credentials: K, totp: T, clock: Clk) -> Self {
+        Self {
+            users,
+            credentials,
+            totp,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: DisableTotpCommand) -> Result<(), DomainError> {
+        let mut user = self
+            .users
+            .find_by_id(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::UserNotFound)?;
+        user.disable_two_factor()?;
+
+        // a hijacked session alone must not be able to strip the second factor, and as codes have no
+        // attempt limit here the password check (and its argon2 cost) is what keeps them from being guessed
+        let credentials = self
+            .credentials
+            .find_by_user_id(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::InvalidCredentials)?;
+        let password = command.password;
+        if !tokio::task::spawn_blocking(move || credentials.verify(&password))
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::InvalidCredentials);
+        }
+
+        let mut enrollment = self
+            .totp
+            .find_by_user_id(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::TwoFactorNotEnabled)?;
+
+        let now = self.clock.now();
+        let used = enrollment.authenticate(&command.code, now)?;
+        if !self
+            .totp
+            .redeem(&command.user_id, &used, now)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::InvalidTwoFactorCode);
+        }
+
+        self.totp
+            .delete(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.users.save(&user).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::credentials::{Credentials, Password};
+    use crate::domain::repository::RepoError;
+    use crate::domain::two_factor::{TOTP_STEP_SECONDS, TotpEnrollment, TwoFactorUse};
+    use crate::domain::user::{DisplayName, User, Username};
+
+    #[derive(Default)]
+    struct MockUserRepository {
+        saved_two_factor_enabled: Mutex<Option<bool>>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(Some(User::from_persistence(
+                id.clone(),
+                Username::new("alice".into()).unwrap(),
+                DisplayName::new("Alice".into()).unwrap(),
+                true,
+                Utc::now(),
+            )))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, user: &User) -> Result<(), RepoError> {
+            *self.saved_two_factor_enabled.lock().unwrap() = Some(*user.two_factor_enabled());
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    struct MockCredentialRepository {
+        credentials: Credentials,
+    }
+
+    #[async_trait]
+    impl CredentialRepository for MockCredentialRepository {
+        async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Option<Credentials>, RepoError> {
+            Ok(Some(Credentials::from_persistence(
+                self.credentials.user_id().clone(),
+                self.credentials.password_hash().clone(),
+                *self.credentials.updated_at(),
+            )))
+        }
+
+        async fn save(&self, _credentials: &Credentials) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockTotpRepository {
+        enrollment: Mutex<Option<TotpEnrollment>>,
+        deleted: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl TotpRepository for MockTotpRepository {
+        async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Option<TotpEnrollment>, RepoError> {
+            Ok(self.enrollment.lock().unwrap().take())
+        }
+
+        async fn save(&self, _enrollment: &TotpEnrollment) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn redeem(&self, _user_id: &UserId, _used: &TwoFactorUse, _now: DateTime<Utc>) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn delete(&self, _user_id: &UserId) -> Result<(), RepoError> {
+            *self.deleted.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap()
+    }
+
+    type TestHandler = DisableTotpHandler<MockUserRepository, MockCredentialRepository, MockTotpRepository, FixedClock>;
+
+    fn handler(user_id: &UserId) -> (TestHandler, String) {
+        let mut enrollment = TotpEnrollment::begin(user_id.clone(), now());
+        let step = now().timestamp() / TOTP_STEP_SECONDS;
+        enrollment
+            .confirm(
+                &enrollment.secret().code_at(step - 2),
+                now() - chrono::Duration::seconds(2 * TOTP_STEP_SECONDS),
+            )
+            .unwrap();
+        let code = enrollment.secret().code_at(step);
+
+        let handler = DisableTotpHandler::new(
+            MockUserRepository::default(),
+            MockCredentialRepository {
+                credentials: Credentials::new(user_id.clone(), &Password::new("correct horse".into()).unwrap()).unwrap(),
+            },
+            MockTotpRepository {
+                enrollment: Mutex::new(Some(enrollment)),
+                deleted: Mutex::new(false),
+            },
+            FixedClock(now()),
+        );
+        (handler, code)
+    }
+
+    #[tokio::test]
+    async fn handle_removes_enrollment_with_valid_code() {
+        let user_id = UserId::new();
+        let (handler, code) = handler(&user_id);
+
+        handler
+            .handle(DisableTotpCommand {
+                user_id,
+                password: "correct horse".into(),
+                code,
+            })
+            .await
+            .unwrap();
+
+        assert!(*handler.totp.deleted.lock().unwrap());
+        assert_eq!(*handler.users.saved_two_factor_enabled.lock().unwrap(), Some(false));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_wrong_code() {
+        let user_id = UserId::new();
+        let (handler, _) = handler(&user_id);
+
+        let result = handler
+            .handle(DisableTotpCommand {
+                user_id,
+                password: "correct horse".into(),
+                code: "wrong-code".into(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidTwoFactorCode));
+        assert!(!*handler.totp.deleted.lock().unwrap());
+        assert!(handler.users.saved_two_factor_enabled.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_wrong_password_even_with_valid_code() {
+        let user_id = UserId::new();
+        let (handler, code) = handler(&user_id);
+
+        let result = handler
+            .handle(DisableTotpCommand {
+                user_id,
+                password: "battery staple".into(),
+                code,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidCredentials));
+        assert!(!*handler.totp.deleted.lock().unwrap());
+        assert!(handler.users.saved_two_factor_enabled.lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,428 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+
+use crate::{
+    application::commands::send_message::{SendMessageCommand, SendMessageHandler},
+    domain::{
+        clock::Clock,
+        errors::DomainError,
+        repository::{
+            BlockRepository, ConversationRepository, EventPublisher, MessageRepository, ScheduledMessageRepository, UploadRepository,
+            UserRepository,
+        },
+        scheduled_message::ScheduledMessage,
+    },
+};
+
+const BATCH_SIZE: i64 = 100;
+// a claim still unrecorded after this long belongs to a dispatcher that died mid-send
+const CLAIM_TIMEOUT: Duration = Duration::minutes(5);
+
+pub struct DispatchScheduledMessagesHandler<
+    C: ConversationRepository,
+    M: MessageRepository,
+    U: UserRepository,
+    B: BlockRepository,
+    F: UploadRepository,
+    P: EventPublisher,
+    S: ScheduledMessageRepository,
+    Clk: Clock,
+> {
+    send_message: Arc<SendMessageHandler<C, M, U, B, F, P>>,
+    scheduled: S,
+    clock: Clk,
+}
+
+impl<
+    C: ConversationRepository,
+    M: MessageRepository,
+    U: UserRepository,
+    B: BlockRepository,
+    F: UploadRepository,
+    P: EventPublisher,
+    S: ScheduledMessageRepository,
+    Clk: Clock,
+> DispatchScheduledMessagesHandler<C, M, U, B, F, P, S, Clk>
+{
+    pub fn new(send_message: Arc<SendMessageHandler<C, M, U, B, F, P>>, scheduled: S, clock: Clk) -> Self {
+        Self {
+            send_message,
+            scheduled,
+            clock,
+        }
+    }
+
+    // sends everything that has come due and returns how many went out. each message goes through the
+    // normal send path, so a sender who has since left or been blocked gets a failed entry instead
+    pub async fn handle(&self) -> Result<usize, DomainError> {
+        let now = self.clock.now();
+        let due = self
+            .scheduled
+            .claim_due(now, now - CLAIM_TIMEOUT, BATCH_SIZE)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        let mut sent = 0;
+        for mut scheduled in due {
+            if scheduled.out_of_attempts() {
+                scheduled.mark_failed("sending was interrupted too many times".into());
+                self.record(&scheduled).await;
+                continue;
+            }
+
+            let command = SendMessageCommand {
+                conversation_id: scheduled.conversation_id().clone(),
+                sender_id: scheduled.sender_id().clone(),
+                content: scheduled.content().clone(),
+                kind: scheduled.kind().clone(),
+                reply_to: scheduled.reply_to().clone(),
+                poll: None,
+            };
+            match self.send_message.handle(command).await {
+                Ok(message_id) => {
+                    scheduled.mark_sent(message_id);
+                    sent += 1;
+                }
+                // back in the queue so the next run retries it
+                Err(DomainError::Internal(e)) => scheduled.retry_later(e),
+                Err(e) => scheduled.mark_failed(e.to_string()),
+            }
+            self.record(&scheduled).await;
+        }
+
+        Ok(sent)
+    }
+
+    // one message that can't be recorded mustn't hold up the rest of the batch; its claim goes stale and
+    // a later run takes it over
+    async fn record(&self, scheduled: &ScheduledMessage) {
+        if let Err(err) = self.scheduled.save(scheduled).await {
+            tracing::warn!("failed to record scheduled message {}: {err}", scheduled.id());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::domain::attachment::Attachment;
+    use crate::domain::block::Block;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::credentials::Credentials;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::ids::{ConversationId, MessageId, ScheduledMessageId, UserId};
+    use crate::domain::invitation::Invitation;
+    use crate::domain::message::{Message, MessageKind, MessageRevision};
+    use crate::domain::repository::{PublishError, RepoError};
+    use crate::domain::scheduled_message::{ScheduledMessage, ScheduledStatus};
+    use crate::domain::user::{User, Username};
+    use crate::domain::voice_note::VoiceNote;
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+        err: bool,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            if self.err {
+                return Err(RepoError::Db(sqlx::Error::PoolTimedOut));
+            }
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockMessageRepository;
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _message: &Message) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    struct MockUserRepository;
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, _id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    struct MockBlockRepository;
+
+    #[async_trait]
+    impl BlockRepository for MockBlockRepository {
+        async fn find_by_blocker(&self, _blocker_id: &UserId) -> Result<Vec<Block>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn is_blocked_between(&self, _a: &UserId, _b: &UserId) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+
+        async fn save(&self, _block: &Block) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, _blocker_id: &UserId, _blocked_id: &UserId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockUploadRepository;
+
+    #[async_trait]
+    impl UploadRepository for MockUploadRepository {
+        async fn save_file(&self, _url: &str, _attachment: &Attachment) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn find_file(&self, _url: &str) -> Result<Option<Attachment>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save_voice_note(&self, _url: &str, _voice_note: &VoiceNote) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn find_voice_note(&self, _url: &str) -> Result<Option<VoiceNote>, RepoError> {
+            Ok(None)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Vec<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            self.published.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockScheduledMessageRepository {
+        due: Mutex<Vec<ScheduledMessage>>,
+        saved: Mutex<Option<ScheduledMessage>>,
+    }
+
+    #[async_trait]
+    impl ScheduledMessageRepository for MockScheduledMessageRepository {
+        async fn find_by_id(&self, _id: &ScheduledMessageId) -> Result<Option<ScheduledMessage>, RepoError> {
+            Ok(None)
+        }
+
+        async fn add(&self, _scheduled: &ScheduledMessage) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn claim_due(
+            &self,
+            now: DateTime<Utc>,
+            _stale_before: DateTime<Utc>,
+            _limit: i64,
+        ) -> Result<Vec<ScheduledMessage>, RepoError> {
+            Ok(self
+                .due
+                .lock()
+                .unwrap()
+                .drain(..)
+                .filter(|s| *s.status() == ScheduledStatus::Pending && *s.send_at() <= now)
+                .map(|s| {
+                    ScheduledMessage::from_persistence(
+                        s.id().clone(),
+                        s.conversation_id().clone(),
+                        s.sender_id().clone(),
+                        s.content().clone(),
+                        s.kind().clone(),
+                        s.reply_to().clone(),
+                        *s.send_at(),
+                        *s.created_at(),
+                        ScheduledStatus::Sending,
+                        None,
+                        None,
+                        s.attempts() + 1,
+                    )
+                })
+                .collect())
+        }
+
+        async fn save(&self, scheduled: &ScheduledMessage) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = Some(ScheduledMessage::from_persistence(
+                scheduled.id().clone(),
+                scheduled.conversation_id().clone(),
+                scheduled.sender_id().clone(),
+                scheduled.content().clone(),
+                scheduled.kind().clone(),
+                scheduled.reply_to().clone(),
+                *scheduled.send_at(),
+                *scheduled.created_at(),
+                *scheduled.status(),
+                scheduled.message_id().clone(),
+                scheduled.failure().clone(),
+                *scheduled.attempts(),
+            ));
+            Ok(())
+        }
+
+        async fn cancel(&self, _scheduled: &ScheduledMessage) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    type Handler = DispatchScheduledMessagesHandler<
+        MockConversationRepository,
+        MockMessageRepository,
+        MockUserRepository,
+        MockBlockRepository,
+        MockUploadRepository,
+        MockEventPublisher,
+        MockScheduledMessageRepository,
+        FixedClock,
+    >;
+
+    // schedules a message an hour ago for delivery a minute ago, then hands the dispatcher the conversation as it is now
+    fn handler(scheduled_in: &Conversation, current: Option<Conversation>, sender: &UserId, now: DateTime<Utc>) -> Handler {
+        let scheduled = ScheduledMessage::schedule(
+            ScheduledMessageId::new(),
+            scheduled_in,
+            sender.clone(),
+            "good morning".into(),
+            MessageKind::Text,
+            None,
+            now - Duration::minutes(1),
+            now - Duration::hours(1),
+        )
+        .unwrap();
+        let send_message = SendMessageHandler::new(
+            MockConversationRepository {
+                err: current.is_none(),
+                conversation: Mutex::new(current),
+            },
+            MockMessageRepository,
+            MockUserRepository,
+            MockBlockRepository,
+            MockUploadRepository,
+            MockEventPublisher::default(),
+        );
+        DispatchScheduledMessagesHandler::new(
+            Arc::new(send_message),
+            MockScheduledMessageRepository {
+                due: Mutex::new(vec![scheduled]),
+                ..Default::default()
+            },
+            FixedClock(now),
+        )
+    }
+
+    #[tokio::test]
+    async fn sends_due_messages_and_marks_them_sent() {
+        let owner = UserId::new();
+        let convo = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let current = Conversation::new_group(convo.id().clone(), "Group".into(), owner.clone()).unwrap();
+        let handler = handler(&convo, Some(current), &owner, Utc::now());
+
+        let sent = handler.handle().await.unwrap();
+
+        assert_eq!(sent, 1);
+        let saved = handler.scheduled.saved.lock().unwrap().take().unwrap();
+        assert_eq!(*saved.status(), ScheduledStatus::Sent);
+        assert!(saved.message_id().is_some());
+    }
+
+    #[tokio::test]
+    async fn marks_the_message_failed_when_the_sender_has_left() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let mut convo = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        convo.add_participant(&owner, member.clone()).unwrap();
+        let current = Conversation::new_group(convo.id().clone(), "Group".into(), owner).unwrap();
+        let handler = handler(&convo, Some(current), &member, Utc::now());
+
+        let sent = handler.handle().await.unwrap();
+
+        assert_eq!(sent, 0);
+        let saved = handler.scheduled.saved.lock().unwrap().take().unwrap();
+        assert_eq!(*saved.status(), ScheduledStatus::Failed);
+        assert_eq!(saved.failure().as_deref(), Some("user is not a participant of this conversation"));
+    }
+
+    #[tokio::test]
+    async fn puts_the_message_back_in_the_queue_when_the_send_fails_for_a_transient_reason() {
+        let owner = UserId::new();
+        let convo = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let handler = handler(&convo, None, &owner, Utc::now());
+
+        let sent = handler.handle().await.unwrap();
+
+        assert_eq!(sent, 0);
+        let saved = handler.scheduled.saved.lock().unwrap().take().unwrap();
+        assert_eq!(*saved.status(), ScheduledStatus::Pending);
+        assert_eq!(*saved.attempts(), 1);
+    }
+}
@@ -1,7 +1,10 @@
-use crate::domain::{
-    errors::DomainError,
-    ids::{MessageId, UserId},
-    repository::{EventPublisher, MessageRepository},
+use crate::{
+    application::commands::send_message::mention_directory,
+    domain::{
+        errors::DomainError,
+        ids::{MessageId, UserId},
+        repository::{ConversationRepository, EventPublisher, MessageRepository, UserRepository},
+    },
 };
 
 pub struct EditMessageCommand {
@@ -10,14 +13,21 @@ pub struct EditMessageCommand {
     pub content: String,
 }
 
-pub struct EditMessageHandler<M: MessageRepository, P: EventPublisher> {
+pub struct EditMessageHandler<C: ConversationRepository, M: MessageRepository, U: UserRepository, P: EventPublisher> {
+    conversations: C,
     messages: M,
+    users: U,
     events: P,
 }
 
-impl<M: MessageRepository, P: EventPublisher> EditMessageHandler<M, P> {
-    pub fn new(messages: M, events: P) -> Self {
-        Self { messages, events }
+impl<C: ConversationRepository, M: MessageRepository, U: UserRepository, P: EventPublisher> EditMessageHandler<C, M, U, P> {
+    pub fn new(conversations: C, messages: M, users: U, events: P) -> Self {
+        Self {
+            conversations,
+            messages,
+            users,
+            events,
+        }
     }
 
     pub async fn handle(&self, command: EditMessageCommand) -> Result<(), DomainError> {
@@ -28,13 +38,31 @@ impl<M: MessageRepository, P: EventPublisher> EditMessageHandler<M, P> {
             .map_err(|e| DomainError::Internal(e.to_string()))?
             .ok_or(DomainError::MessageNotFound)?;
 
-        let event = message.edit(&command.editor_id, command.content)?;
+        let conversation = self
+            .conversations
+            .find_by_id(message.conversation_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let directory = mention_directory(&self.users, &conversation, &command.content).await?;
+        let already_mentioned = message.mentions().clone();
+        let (revision, event) = message.edit(&command.editor_id, command.content, &directory)?;
 
-        self.messages
-            .save(&message)
+        // two edits racing from the same starting point can't both land, or one's revision would be lost
+        if !self
+            .messages
+            .save_edit(&message, &revision)
             .await
-            .map_err(|e| DomainError::Internal(e.to_string()))?;
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::MessageEditConflict);
+        }
         self.events.publish(event).await.ok();
+        // only people newly mentioned by the edit hear about it; everyone else was told the first time
+        for mention in message.mention_events(&already_mentioned) {
+            self.events.publish(mention).await.ok();
+        }
 
         Ok(())
     }
@@ -45,15 +73,46 @@ mod tests {
     use std::sync::Mutex;
 
     use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
 
     use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::credentials::Credentials;
     use crate::domain::events::DomainEvent;
     use crate::domain::ids::ConversationId;
-    use crate::domain::message::{Message, MessageKind};
+    use crate::domain::invitation::Invitation;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{Message, MessageKind, MessageRevision};
     use crate::domain::repository::{PublishError, RepoError};
+    use crate::domain::user::{DisplayName, User, Username};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
 
     struct MockMessageRepository {
         message: Mutex<Option<Message>>,
+        revisions: Mutex<Vec<String>>,
+        conflict: bool,
     }
 
     #[async_trait]
@@ -66,6 +125,31 @@ mod tests {
             *self.message.lock().unwrap() = Some(clone_message(message));
             Ok(())
         }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, message: &Message, revision: &MessageRevision) -> Result<bool, RepoError> {
+            if self.conflict {
+                return Ok(false);
+            }
+            *self.message.lock().unwrap() = Some(clone_message(message));
+            self.revisions.lock().unwrap().push(revision.content().clone());
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
     }
 
     fn clone_message(message: &Message) -> Message {
@@ -75,28 +159,118 @@ mod tests {
             message.sender_id().clone(),
             message.content().clone(),
             message.kind().clone(),
+            message.thread_root_id().clone(),
+            message.mentions().clone(),
             *message.edited(),
             *message.created_at(),
             *message.updated_at(),
+            *message.deleted_at(),
+            *message.expires_at(),
+            message.forwarded_from().clone(),
+            message.poll().clone(),
+            message.attachment().clone(),
+            message.voice_note().clone(),
         )
     }
 
+    #[derive(Default)]
+    struct MockUserRepository {
+        users: Vec<User>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(self.users.iter().find(|u| u.id() == id).map(|u| {
+                User::from_persistence(
+                    u.id().clone(),
+                    u.username().clone(),
+                    u.display_name().clone(),
+                    *u.two_factor_enabled(),
+                    *u.created_at(),
+                )
+            }))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
     #[derive(Default)]
     struct MockEventPublisher {
-        published: Mutex<Option<DomainEvent>>,
+        published: Mutex<Vec<DomainEvent>>,
     }
 
     #[async_trait]
     impl EventPublisher for MockEventPublisher {
         async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
-            *self.published.lock().unwrap() = Some(event);
+            self.published.lock().unwrap().push(event);
             Ok(())
         }
     }
 
+    type TestHandler = EditMessageHandler<MockConversationRepository, MockMessageRepository, MockUserRepository, MockEventPublisher>;
+
+    fn user(username: &str) -> User {
+        User::new(
+            UserId::new(),
+            Username::new(username.into()).unwrap(),
+            DisplayName::new(username.into()).unwrap(),
+        )
+    }
+
+    // a direct conversation between `sender` and `other`, holding one message from `sender`
+    fn handler(sender: User, other: User, content: &str) -> (TestHandler, MessageId) {
+        let conversation = Conversation::new_direct(ConversationId::new(), sender.id().clone(), other.id().clone()).unwrap();
+        let directory = MentionDirectory::new([
+            (sender.username().clone(), sender.id().clone()),
+            (other.username().clone(), other.id().clone()),
+        ]);
+        let (message, _) = Message::new(
+            MessageId::new(),
+            conversation.id().clone(),
+            sender.id().clone(),
+            content.into(),
+            MessageKind::Text,
+            &directory,
+        )
+        .unwrap();
+        let message_id = message.id().clone();
+
+        let handler = EditMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockMessageRepository {
+                message: Mutex::new(Some(message)),
+                revisions: Mutex::new(vec![]),
+                conflict: false,
+            },
+            MockUserRepository {
+                users: vec![sender, other],
+            },
+            MockEventPublisher::default(),
+        );
+        (handler, message_id)
+    }
+
     #[tokio::test]
     async fn handle_returns_message_not_found_when_missing() {
-        let handler = EditMessageHandler::new(MockMessageRepository { message: Mutex::new(None) }, MockEventPublisher::default());
+        let (handler, _) = handler(user("alice"), user("bob"), "hello");
+        handler.messages.message.lock().unwrap().take();
 
         let result = handler
             .handle(EditMessageCommand {
@@ -111,50 +285,32 @@ mod tests {
 
     #[tokio::test]
     async fn handle_rejects_editor_who_is_not_sender() {
-        let sender = UserId::new();
-        let (message, _) = Message::new(MessageId::new(), ConversationId::new(), sender, "hello".into(), MessageKind::Text).unwrap();
-        let message_id = message.id().clone();
-        let handler = EditMessageHandler::new(
-            MockMessageRepository {
-                message: Mutex::new(Some(message)),
-            },
-            MockEventPublisher::default(),
-        );
+        let other = user("bob");
+        let other_id = other.id().clone();
+        let (handler, message_id) = handler(user("alice"), other, "hello");
 
         let result = handler
             .handle(EditMessageCommand {
                 message_id,
-                editor_id: UserId::new(),
+                editor_id: other_id,
                 content: "changed".into(),
             })
             .await;
 
         assert_eq!(result.err(), Some(DomainError::NotYourMessage));
+        assert!(handler.messages.revisions.lock().unwrap().is_empty());
     }
 
     #[tokio::test]
     async fn handle_saves_edit_and_publishes_event_on_success() {
-        let sender = UserId::new();
-        let (message, _) = Message::new(
-            MessageId::new(),
-            ConversationId::new(),
-            sender.clone(),
-            "hello".into(),
-            MessageKind::Text,
-        )
-        .unwrap();
-        let message_id = message.id().clone();
-        let handler = EditMessageHandler::new(
-            MockMessageRepository {
-                message: Mutex::new(Some(message)),
-            },
-            MockEventPublisher::default(),
-        );
+        let sender = user("alice");
+        let sender_id = sender.id().clone();
+        let (handler, message_id) = handler(sender, user("bob"), "hello");
 
         let result = handler
             .handle(EditMessageCommand {
                 message_id: message_id.clone(),
-                editor_id: sender,
+                editor_id: sender_id,
                 content: "updated".into(),
             })
             .await;
@@ -164,17 +320,82 @@ mod tests {
         let saved = handler.messages.message.lock().unwrap().take().unwrap();
         assert_eq!(saved.content(), "updated");
         assert!(*saved.edited());
+        assert_eq!(handler.messages.revisions.lock().unwrap().as_slice(), &["hello".to_string()]);
 
-        match &*handler.events.published.lock().unwrap() {
-            Some(DomainEvent::MessageEdited {
-                message_id: event_id,
-                content,
-                ..
-            }) => {
+        match handler.events.published.lock().unwrap().as_slice() {
+            [
+                DomainEvent::MessageEdited {
+                    message_id: event_id,
+                    content,
+                    ..
+                },
+            ] => {
                 assert_eq!(event_id, &message_id);
                 assert_eq!(content, "updated");
             }
-            _ => panic!("expected MessageEdited event"),
+            _ => panic!("expected only a MessageEdited event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_edit_when_message_changed_since_it_was_read() {
+        let sender = user("alice");
+        let sender_id = sender.id().clone();
+        let (mut handler, message_id) = handler(sender, user("bob"), "hello @bob");
+        handler.messages.conflict = true;
+
+        let result = handler
+            .handle(EditMessageCommand {
+                message_id,
+                editor_id: sender_id,
+                content: "changed".into(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::MessageEditConflict));
+        assert!(handler.messages.revisions.lock().unwrap().is_empty());
+        assert!(handler.events.published.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_does_not_notify_participant_already_mentioned() {
+        let sender = user("alice");
+        let sender_id = sender.id().clone();
+        let (handler, message_id) = handler(sender, user("bob"), "ping @bob");
+
+        handler
+            .handle(EditMessageCommand {
+                message_id,
+                editor_id: sender_id,
+                content: "ping @bob again".into(),
+            })
+            .await
+            .unwrap();
+
+        let published = handler.events.published.lock().unwrap();
+        assert!(matches!(published.as_slice(), [DomainEvent::MessageEdited { .. }]));
+    }
+
+    #[tokio::test]
+    async fn handle_publishes_mention_when_edit_adds_one() {
+        let sender = user("alice");
+        let sender_id = sender.id().clone();
+        let other = user("bob");
+        let other_id = other.id().clone();
+        let (handler, message_id) = handler(sender, other, "hello");
+
+        handler
+            .handle(EditMessageCommand {
+                message_id,
+                editor_id: sender_id,
+                content: "hello @bob".into(),
+            })
+            .await
+            .unwrap();
+
+        match handler.events.published.lock().unwrap().as_slice() {
+            [DomainEvent::MessageEdited { .. }, DomainEvent::UserMentioned { user_id, .. }] => assert_eq!(user_id, &other_id),
+            _ => panic!("expected MessageEdited followed by UserMentioned"),
         }
     }
 }
@@ -0,0 +1,156 @@
+use crate::domain::clock::Clock;
+use crate::domain::errors::DomainError;
+use crate::domain::ids::UserId;
+use crate::domain::repository::{TotpRepository, UserRepository};
+use crate::domain::two_factor::TotpEnrollment;
+
+const TOTP_ISSUER: &str = "Volt";
+
+pub struct EnrollTotpCommand {
+    pub user_id: UserId,
+}
+
+pub struct TotpSetup {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+pub struct EnrollTotpHandler<U: UserRepository, T: TotpRepository, Clk: Clock> {
+    users: U,
+    totp: T,
+    clock: Clk,
+}
+
+impl<U: UserRepository, T: TotpRepository, Clk: Clock> EnrollTotpHandler<U, T, Clk> {
+    pub fn new(users: U, totp: T, clock: Clk) -> Self {
+        Self { users, totp, clock }
+    }
+
+    pub async fn handle(&self, command: EnrollTotpCommand) -> Result<TotpSetup, DomainError> {
+        let user = self
+            .users
+            .find_by_id(&command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::UserNotFound)?;
+
+        if *user.two_factor_enabled() {
+            return Err(DomainError::TwoFactorAlreadyEnabled);
+        }
+
+        // starting over replaces any unconfirmed secret from an abandoned attempt
+        let enrollment = TotpEnrollment::begin(command.user_id, self.clock.now());
+
+        self.totp
+            .save(&enrollment)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(TotpSetup {
+            secret: enrollment.secret().to_base32(),
+            provisioning_uri: enrollment.secret().provisioning_uri(TOTP_ISSUER, user.username().as_str()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::credentials::Credentials;
+    use crate::domain::repository::RepoError;
+    use crate::domain::two_factor::TwoFactorUse;
+    use crate::domain::user::{DisplayName, User, Username};
+
+    struct MockUserRepository {
+        two_factor_enabled: bool,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(Some(User::from_persistence(
+                id.clone(),
+                Username::new("alice".into()).unwrap(),
+                DisplayName::new("Alice".into()).unwrap(),
+                self.two_factor_enabled,
+                Utc::now(),
+            )))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockTotpRepository {
+        saved: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl TotpRepository for MockTotpRepository {
+        async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Option<TotpEnrollment>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, enrollment: &TotpEnrollment) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = Some(enrollment.secret().to_base32());
+            Ok(())
+        }
+
+        async fn redeem(&self, _user_id: &UserId, _used: &TwoFactorUse, _now: DateTime<Utc>) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn delete(&self, _user_id: &UserId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    fn handler(two_factor_enabled: bool) -> EnrollTotpHandler<MockUserRepository, MockTotpRepository, FixedClock> {
+        EnrollTotpHandler::new(
+            MockUserRepository { two_factor_enabled },
+            MockTotpRepository::default(),
+            FixedClock(Utc::now()),
+        )
+    }
+
+    #[tokio::test]
+    async fn handle_saves_unconfirmed_enrollment_and_returns_its_secret() {
+        let handler = handler(false);
+
+        let setup = handler.handle(EnrollTotpCommand { user_id: UserId::new() }).await.unwrap();
+
+        assert_eq!(handler.totp.saved.lock().unwrap().as_deref(), Some(setup.secret.as_str()));
+        assert!(setup.provisioning_uri.starts_with("otpauth://totp/Volt:alice?"));
+        assert!(setup.provisioning_uri.contains(&setup.secret));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_when_two_factor_already_enabled() {
+        let handler = handler(true);
+
+        let result = handler.handle(EnrollTotpCommand { user_id: UserId::new() }).await;
+
+        assert_eq!(result.err(), Some(DomainError::TwoFactorAlreadyEnabled));
+        assert!(handler.totp.saved.lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    events::DomainEvent,
+    message::MessageKind,
+    repository::{EventPublisher, MediaStore, MessageRepository},
+};
+
+const BATCH_SIZE: i64 = 100;
+
+pub struct ExpireMessagesHandler<M: MessageRepository, F: MediaStore, P: EventPublisher, Clk: Clock> {
+    messages: M,
+    media: F,
+    events: P,
+    clock: Clk,
+}
+
+impl<M: MessageRepository, F: MediaStore, P: EventPublisher, Clk: Clock> ExpireMessagesHandler<M, F, P, Clk> {
+    pub fn new(messages: M, media: F, events: P, clock: Clk) -> Self {
+        Self {
+            messages,
+            media,
+            events,
+            clock,
+        }
+    }
+
+    // hard-deletes messages whose timer has run out and returns how many went
+    pub async fn handle(&self) -> Result<usize, DomainError> {
+        let expired = self
+            .messages
+            .find_expired(self.clock.now(), BATCH_SIZE)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        let mut removed = 0;
+        for message in expired {
+            // the upload stays put while a forwarded copy still shows it. one that can't be removed is
+            // logged and left behind: keeping the message for a retry would let a few stuck files fill
+            // every batch and stop the reaper for good
+            if matches!(message.kind(), MessageKind::Image | MessageKind::File | MessageKind::Audio)
+                && !self
+                    .messages
+                    .shares_media(&message)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?
+                && let Err(err) = self.media.delete(message.content()).await
+            {
+                tracing::warn!("leaving orphaned upload {} behind: {err}", message.content());
+            }
+            self.messages
+                .delete(message.id())
+                .await
+                .map_err(|e| DomainError::Internal(e.to_string()))?;
+            self.events
+                .publish(DomainEvent::MessageExpired {
+                    message_id: message.id().clone(),
+                    conversation_id: message.conversation_id().clone(),
+                })
+                .await
+                .ok();
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::ids::{ConversationId, MessageId, UserId};
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{Message, MessageRevision};
+    use crate::domain::repository::{PublishError, RepoError};
+
+    #[derive(Default)]
+    struct MockMessageRepository {
+        expired: Mutex<Vec<Message>>,
+        deleted: Mutex<Vec<MessageId>>,
+        shared: bool,
+    }
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _message: &Message) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(self.expired.lock().unwrap().drain(..).filter(|m| m.is_expired(now)).collect())
+        }
+
+        async fn delete(&self, id: &MessageId) -> Result<(), RepoError> {
+            self.deleted.lock().unwrap().push(id.clone());
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(self.shared)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockMediaStore {
+        fail: bool,
+        deleted: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl MediaStore for MockMediaStore {
+        async fn delete(&self, url: &str) -> Result<(), RepoError> {
+            if self.fail {
+                return Err(RepoError::Io(std::io::Error::other("disk unavailable")));
+            }
+            self.deleted.lock().unwrap().push(url.to_string());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Vec<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            self.published.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    fn message(content: &str, kind: MessageKind, ttl: Duration) -> Message {
+        let (mut message, _) = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            content.to_string(),
+            kind,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        message.expire_after(ttl);
+        message
+    }
+
+    #[tokio::test]
+    async fn deletes_expired_messages_with_their_media() {
+        let text = message("brief", MessageKind::Text, Duration::hours(1));
+        let image = message("http://127.0.0.1:3000/media/a.png", MessageKind::Image, Duration::hours(1));
+        let later = message("not yet", MessageKind::Text, Duration::days(1));
+        let handler = ExpireMessagesHandler::new(
+            MockMessageRepository {
+                expired: Mutex::new(vec![text, image, later]),
+                ..Default::default()
+            },
+            MockMediaStore::default(),
+            MockEventPublisher::default(),
+            FixedClock(Utc::now() + Duration::hours(2)),
+        );
+
+        let removed = handler.handle().await.unwrap();
+
+        assert_eq!(removed, 2);
+        assert_eq!(handler.messages.deleted.lock().unwrap().len(), 2);
+        assert_eq!(*handler.media.deleted.lock().unwrap(), vec!["http://127.0.0.1:3000/media/a.png"]);
+        let published = handler.events.published.lock().unwrap();
+        assert!(published.iter().all(|e| matches!(e, DomainEvent::MessageExpired { .. })));
+        assert_eq!(published.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn removes_the_message_even_when_its_media_cannot_be_removed() {
+        let image = message("http://127.0.0.1:3000/media/a.png", MessageKind::Image, Duration::hours(1));
+        let handler = ExpireMessagesHandler::new(
+            MockMessageRepository {
+                expired: Mutex::new(vec![image]),
+                ..Default::default()
+            },
+            MockMediaStore {
+                fail: true,
+                ..Default::default()
+            },
+            MockEventPublisher::default(),
+            FixedClock(Utc::now() + Duration::hours(2)),
+        );
+
+        let removed = handler.handle().await.unwrap();
+
+        assert_eq!(removed, 1);
+        assert_eq!(handler.messages.deleted.lock().unwrap().len(), 1);
+        assert!(handler.media.deleted.lock().unwrap().is_empty());
+        let published = handler.events.published.lock().unwrap();
+        assert!(matches!(published.as_slice(), [DomainEvent::MessageExpired { .. }]));
+    }
+
+    #[tokio::test]
+    async fn leaves_media_that_a_forwarded_copy_still_shows() {
+        let image = message("http://127.0.0.1:3000/media/a.png", MessageKind::Image, Duration::hours(1));
+        let handler = ExpireMessagesHandler::new(
+            MockMessageRepository {
+                expired: Mutex::new(vec![image]),
+                shared: true,
+                ..Default::default()
+            },
+            MockMediaStore::default(),
+            MockEventPublisher::default(),
+            FixedClock(Utc::now() + Duration::hours(2)),
+        );
+
+        let removed = handler.handle().await.unwrap();
+
+        assert_eq!(removed, 1);
+        assert!(handler.media.deleted.lock().unwrap().is_empty());
+    }
+}
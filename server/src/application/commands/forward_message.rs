@@ -0,0 +1,313 @@
+use std::collections::HashSet;
+
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, MessageId, UserId},
+    message::Message,
+    repository::{BlockRepository, ConversationRepository, EventPublisher, MessageRepository},
+};
+
+pub struct ForwardMessageCommand {
+    pub message_id: MessageId,
+    pub forwarder_id: UserId,
+    pub target_ids: Vec<ConversationId>,
+}
+
+pub struct ForwardMessageHandler<C: ConversationRepository, M: MessageRepository, B: BlockRepository, P: EventPublisher> {
+    conversations: C,
+    messages: M,
+    blocks: B,
+    events: P,
+}
+
+impl<C: ConversationRepository, M: MessageRepository, B: BlockRepository, P: EventPublisher> ForwardMessageHandler<C, M, B, P> {
+    pub fn new(conversations: C, messages: M, blocks: B, events: P) -> Self {
+        Self {
+            conversations,
+            messages,
+            blocks,
+            events,
+        }
+    }
+
+    // every target is checked before anything is sent and the copies are stored together, so one bad
+    // target or a failed write means nothing is forwarded
+    pub async fn handle(&self, command: ForwardMessageCommand) -> Result<Vec<MessageId>, DomainError> {
+        let mut seen = HashSet::new();
+        let mut target_ids = command.target_ids;
+        target_ids.retain(|id| seen.insert(id.clone()));
+        if target_ids.is_empty() {
+            return Err(DomainError::NoForwardTargets);
+        }
+
+        let source = self
+            .messages
+            .find_by_id(&command.message_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::MessageNotFound)?;
+        let source_conversation = self
+            .conversations
+            .find_by_id(source.conversation_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let mut forwards = Vec::with_capacity(target_ids.len());
+        for target_id in &target_ids {
+            let target = self
+                .conversations
+                .find_by_id(target_id)
+                .await
+                .map_err(|e| DomainError::Internal(e.to_string()))?
+                .ok_or(DomainError::ConversationNotFound)?;
+            if let Some(peer) = target.direct_peer(&command.forwarder_id)
+                && self
+                    .blocks
+                    .is_blocked_between(&command.forwarder_id, peer)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?
+            {
+                return Err(DomainError::Blocked);
+            }
+
+            let (mut message, mut event) =
+                Message::forward(MessageId::new(), &source, &source_conversation, &target, &command.forwarder_id)?;
+            if let Some(ttl) = target.message_ttl() {
+                event = message.expire_after(*ttl);
+            }
+            forwards.push((message, event));
+        }
+
+        let (messages, events): (Vec<Message>, Vec<_>) = forwards.into_iter().unzip();
+        self.messages
+            .save_all(&messages)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        for event in events {
+            self.events.publish(event).await.ok();
+        }
+        Ok(messages.iter().map(|m| m.id().clone()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::block::Block;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{MessageKind, MessageRevision};
+    use crate::domain::repository::{PublishError, RepoError};
+
+    #[derive(Default)]
+    struct MockConversationRepository {
+        conversations: Mutex<Vec<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            let mut conversations = self.conversations.lock().unwrap();
+            Ok(conversations.iter().position(|c| c.id() == id).map(|i| conversations.remove(i)))
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockMessageRepository {
+        source: Mutex<Option<Message>>,
+        saved: Mutex<Vec<ConversationId>>,
+        fail_writes: bool,
+    }
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(self.source.lock().unwrap().take())
+        }
+
+        async fn save(&self, message: &Message) -> Result<(), RepoError> {
+            self.saved.lock().unwrap().push(message.conversation_id().clone());
+            Ok(())
+        }
+
+        async fn save_all(&self, messages: &[Message]) -> Result<(), RepoError> {
+            if self.fail_writes {
+                return Err(RepoError::Io(std::io::Error::other("connection lost")));
+            }
+            let mut saved = self.saved.lock().unwrap();
+            saved.extend(messages.iter().map(|m| m.conversation_id().clone()));
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    struct MockBlockRepository;
+
+    #[async_trait]
+    impl BlockRepository for MockBlockRepository {
+        async fn find_by_blocker(&self, _blocker_id: &UserId) -> Result<Vec<Block>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn is_blocked_between(&self, _a: &UserId, _b: &UserId) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+
+        async fn save(&self, _block: &Block) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, _blocker_id: &UserId, _blocked_id: &UserId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Vec<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            self.published.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    fn group(owner: &UserId) -> Conversation {
+        Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap()
+    }
+
+    // a source conversation with one message from `user`, plus the given targets
+    fn handler(
+        user: &UserId,
+        targets: Vec<Conversation>,
+    ) -> (
+        ForwardMessageHandler<MockConversationRepository, MockMessageRepository, MockBlockRepository, MockEventPublisher>,
+        MessageId,
+    ) {
+        let source_conversation = group(user);
+        let (source, _) = Message::new(
+            MessageId::new(),
+            source_conversation.id().clone(),
+            user.clone(),
+            "worth sharing".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let source_id = source.id().clone();
+        let mut conversations = targets;
+        conversations.push(source_conversation);
+        let handler = ForwardMessageHandler::new(
+            MockConversationRepository {
+                conversations: Mutex::new(conversations),
+            },
+            MockMessageRepository {
+                source: Mutex::new(Some(source)),
+                ..Default::default()
+            },
+            MockBlockRepository,
+            MockEventPublisher::default(),
+        );
+        (handler, source_id)
+    }
+
+    #[tokio::test]
+    async fn forwards_into_every_target() {
+        let user = UserId::new();
+        let (a, b) = (group(&user), group(&user));
+        let target_ids = vec![a.id().clone(), b.id().clone()];
+        let (handler, message_id) = handler(&user, vec![a, b]);
+
+        let ids = handler
+            .handle(ForwardMessageCommand {
+                message_id,
+                forwarder_id: user,
+                target_ids: target_ids.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(ids.len(), 2);
+        assert_eq!(*handler.messages.saved.lock().unwrap(), target_ids);
+        assert_eq!(handler.events.published.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn forwards_nothing_when_one_target_is_off_limits() {
+        let user = UserId::new();
+        let mine = group(&user);
+        let theirs = group(&UserId::new());
+        let target_ids = vec![mine.id().clone(), theirs.id().clone()];
+        let (handler, message_id) = handler(&user, vec![mine, theirs]);
+
+        let result = handler
+            .handle(ForwardMessageCommand {
+                message_id,
+                forwarder_id: user,
+                target_ids,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAParticipant));
+        assert!(handler.messages.saved.lock().unwrap().is_empty());
+        assert!(handler.events.published.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn announces_nothing_when_the_forwards_could_not_be_stored() {
+        let user = UserId::new();
+        let (a, b) = (group(&user), group(&user));
+        let target_ids = vec![a.id().clone(), b.id().clone()];
+        let (mut handler, message_id) = handler(&user, vec![a, b]);
+        handler.messages.fail_writes = true;
+
+        let result = handler
+            .handle(ForwardMessageCommand {
+                message_id,
+                forwarder_id: user,
+                target_ids,
+            })
+            .await;
+
+        assert!(matches!(result, Err(DomainError::Internal(_))));
+        assert!(handler.events.published.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher, InviteRepository},
+};
+
+pub struct JoinViaInviteCommand {
+    pub token: String,
+    pub user_id: UserId,
+}
+
+pub struct JoinViaInviteHandler<C: ConversationRepository, I: InviteRepository, P: EventPublisher, Clk: Clock> {
+    conversations: C,
+    invites: I,
+    events: P,
+    clock: Clk,
+}
+
+impl<C: ConversationRepository, I: InviteRepository, P: EventPublisher, Clk: Clock> JoinViaInviteHandler<C, I, P, Clk> {
+    pub fn new(conversations: C, invites: I, events: P, clock: Clk) -> Self {
+        Self {
+            conversations,
+            invites,
+            events,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: JoinViaInviteCommand) -> Result<ConversationId, DomainError> {
+        let invite = self
+            .invites
+            .find_by_token(&command.token)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::InviteNotFound)?;
+
+        let mut conversation = self
+            .conversations
+            .find_by_id(invite.conversation_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::InviteNotFound)?;
+
+        let now = self.clock.now();
+        let event = invite.redeem(&mut conversation, command.user_id, now)?;
+
+        // the new member is saved with the use, so a join never goes uncounted and a use is never
+        // spent on a join that didn't happen
+        let counted = self
+            .invites
+            .record_use(&invite, &conversation, now)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        if !counted {
+            return Err(DomainError::InviteExpired);
+        }
+        // the hub pushes the full conversation view to the new member off this event
+        self.events.publish(event).await.ok();
+
+        Ok(conversation.id().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::ids::InviteId;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::invite::Invite;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockInviteRepository {
+        invite: Mutex<Option<Invite>>,
+        uses_left: Mutex<i32>,
+        saved: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl InviteRepository for MockInviteRepository {
+        async fn find_by_id(&self, _id: &InviteId) -> Result<Option<Invite>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_by_token(&self, _token: &str) -> Result<Option<Invite>, RepoError> {
+            Ok(self.invite.lock().unwrap().take())
+        }
+
+        async fn save(&self, _invite: &Invite) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn record_use(&self, _invite: &Invite, _conversation: &Conversation, _now: DateTime<Utc>) -> Result<bool, RepoError> {
+            let mut uses_left = self.uses_left.lock().unwrap();
+            if *uses_left == 0 {
+                return Ok(false);
+            }
+            *uses_left -= 1;
+            *self.saved.lock().unwrap() = true;
+            Ok(true)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    type TestHandler = JoinViaInviteHandler<MockConversationRepository, MockInviteRepository, MockEventPublisher, FixedClock>;
+
+    // uses_left is what the store has left, which may be less than the loaded invite thinks
+    fn handler(max_uses: Option<i32>, uses_left: i32) -> TestHandler {
+        let owner = UserId::new();
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let now = Utc::now();
+        let invite = Invite::create(&conversation, &owner, None, max_uses, now).unwrap();
+        JoinViaInviteHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockInviteRepository {
+                invite: Mutex::new(Some(invite)),
+                uses_left: Mutex::new(uses_left),
+                saved: Mutex::new(false),
+            },
+            MockEventPublisher::default(),
+            FixedClock(now),
+        )
+    }
+
+    fn command(user_id: UserId) -> JoinViaInviteCommand {
+        JoinViaInviteCommand {
+            token: "token".into(),
+            user_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_returns_invite_not_found_for_unknown_token() {
+        let handler = handler(None, 1);
+        handler.invites.invite.lock().unwrap().take();
+
+        let result = handler.handle(command(UserId::new())).await;
+
+        assert_eq!(result.err(), Some(DomainError::InviteNotFound));
+    }
+
+    #[tokio::test]
+    async fn handle_joins_counts_the_use_and_publishes_event() {
+        let handler = handler(Some(5), 5);
+        let joiner = UserId::new();
+
+        handler.handle(command(joiner.clone())).await.unwrap();
+
+        assert!(*handler.invites.saved.lock().unwrap());
+        assert_eq!(*handler.invites.uses_left.lock().unwrap(), 4);
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::ParticipantAdded { user_id, .. }) => assert_eq!(user_id, &joiner),
+            _ => panic!("expected ParticipantAdded event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_the_join_when_the_last_use_went_to_someone_else() {
+        let handler = handler(Some(1), 0);
+
+        let result = handler.handle(command(UserId::new())).await;
+
+        assert_eq!(result.err(), Some(DomainError::InviteExpired));
+        assert!(!*handler.invites.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+}
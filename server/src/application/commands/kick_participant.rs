@@ -0,0 +1,154 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher},
+};
+
+pub struct KickParticipantCommand {
+    pub conversation_id: ConversationId,
+    pub actor_id: UserId,
+    pub user_id: UserId,
+}
+
+pub struct KickParticipantHandler<C: ConversationRepository, P: EventPublisher> {
+    conversations: C,
+    events: P,
+}
+
+impl<C: ConversationRepository, P: EventPublisher> KickParticipantHandler<C, P> {
+    pub fn new(conversations: C, events: P) -> Self {
+        Self { conversations, events }
+    }
+
+    pub async fn handle(&self, command: KickParticipantCommand) -> Result<(), DomainError> {
+        let mut conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let events = conversation.remove_participant(&command.actor_id, &command.user_id)?;
+
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        for event in events {
+            self.events.publish(event).await.ok();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::conversation::{Conversation, ParticipantRole};
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+        saved: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = true;
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    // a group with an owner, an admin and a plain member
+    fn handler() -> (
+        KickParticipantHandler<MockConversationRepository, MockEventPublisher>,
+        UserId,
+        UserId,
+        UserId,
+    ) {
+        let owner = UserId::new();
+        let admin = UserId::new();
+        let member = UserId::new();
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        conversation.add_participant(&owner, admin.clone()).unwrap();
+        conversation.add_participant(&owner, member.clone()).unwrap();
+        conversation.change_role(&owner, &admin, ParticipantRole::Admin).unwrap();
+
+        let handler = KickParticipantHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                saved: Mutex::new(false),
+            },
+            MockEventPublisher::default(),
+        );
+        (handler, owner, admin, member)
+    }
+
+    fn command(actor_id: UserId, user_id: UserId) -> KickParticipantCommand {
+        KickParticipantCommand {
+            conversation_id: ConversationId::new(),
+            actor_id,
+            user_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_kicking_someone_of_equal_or_higher_rank() {
+        let (handler, owner, admin, _member) = handler();
+
+        let result = handler.handle(command(admin, owner)).await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAnAdmin));
+        assert!(!*handler.conversations.saved.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_and_publishes_kick_with_actor() {
+        let (handler, _owner, admin, member) = handler();
+
+        handler.handle(command(admin.clone(), member.clone())).await.unwrap();
+
+        assert!(*handler.conversations.saved.lock().unwrap());
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::ParticipantKicked { user_id, kicked_by, .. }) => {
+                assert_eq!(user_id, &member);
+                assert_eq!(kicked_by, &admin);
+            }
+            _ => panic!("expected ParticipantKicked event"),
+        }
+    }
+}
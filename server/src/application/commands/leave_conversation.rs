@@ -27,10 +27,15 @@ impl<C: ConversationRepository, P: EventPublisher> LeaveConversationHandler<C, P
             .map_err(|e| DomainError::Internal(e.to_string()))?
             .ok_or(DomainError::ConversationNotFound)?;
 
-        let event = conversation.remove_participant(&command.user_id)?;
+        let events = conversation.remove_participant(&command.user_id, &command.user_id)?;
 
-        self.conversations.save(&conversation).await.map_err(|e| DomainError::Internal(e.to_string()))?;
-        self.events.publish(event).await.ok();
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        for event in events {
+            self.events.publish(event).await.ok();
+        }
 
         Ok(())
     }
@@ -43,8 +48,9 @@ mod tests {
     use async_trait::async_trait;
 
     use super::*;
-    use crate::domain::conversation::Conversation;
+    use crate::domain::conversation::{Conversation, ParticipantRole};
     use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
     use crate::domain::repository::{PublishError, RepoError};
 
     struct MockConversationRepository {
@@ -57,9 +63,17 @@ mod tests {
             Ok(self.conversation.lock().unwrap().take())
         }
 
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
         async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
             Ok(())
         }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
     }
 
     #[derive(Default)]
@@ -78,7 +92,9 @@ mod tests {
     #[tokio::test]
     async fn handle_returns_conversation_not_found_when_missing() {
         let handler = LeaveConversationHandler::new(
-            MockConversationRepository { conversation: Mutex::new(None) },
+            MockConversationRepository {
+                conversation: Mutex::new(None),
+            },
             MockEventPublisher::default(),
         );
 
@@ -98,11 +114,18 @@ mod tests {
         let conversation = Conversation::new_direct(ConversationId::new(), a.clone(), UserId::new()).unwrap();
         let conversation_id = conversation.id().clone();
         let handler = LeaveConversationHandler::new(
-            MockConversationRepository { conversation: Mutex::new(Some(conversation)) },
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
             MockEventPublisher::default(),
         );
 
-        let result = handler.handle(LeaveConversationCommand { conversation_id, user_id: a }).await;
+        let result = handler
+            .handle(LeaveConversationCommand {
+                conversation_id,
+                user_id: a,
+            })
+            .await;
 
         assert_eq!(result.err(), Some(DomainError::CannotLeaveDirect));
     }
@@ -111,11 +134,13 @@ mod tests {
     async fn handle_removes_participant_and_publishes_event_on_success() {
         let creator = UserId::new();
         let member = UserId::new();
-        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), creator).unwrap();
-        conversation.add_participant(member.clone()).unwrap();
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), creator.clone()).unwrap();
+        conversation.add_participant(&creator, member.clone()).unwrap();
         let conversation_id = conversation.id().clone();
         let handler = LeaveConversationHandler::new(
-            MockConversationRepository { conversation: Mutex::new(Some(conversation)) },
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
             MockEventPublisher::default(),
         );
 
@@ -129,11 +154,45 @@ mod tests {
         assert!(result.is_ok());
 
         match &*handler.events.published.lock().unwrap() {
-            Some(DomainEvent::ParticipantRemoved { conversation_id: event_id, user_id }) => {
+            Some(DomainEvent::ParticipantRemoved {
+                conversation_id: event_id,
+                user_id,
+            }) => {
                 assert_eq!(event_id, &conversation_id);
                 assert_eq!(user_id, &member);
             }
             _ => panic!("expected ParticipantRemoved event"),
         }
     }
+
+    #[tokio::test]
+    async fn handle_publishes_ownership_transfer_when_owner_leaves() {
+        let creator = UserId::new();
+        let member = UserId::new();
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), creator.clone()).unwrap();
+        conversation.add_participant(&creator, member.clone()).unwrap();
+        let conversation_id = conversation.id().clone();
+        let handler = LeaveConversationHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockEventPublisher::default(),
+        );
+
+        handler
+            .handle(LeaveConversationCommand {
+                conversation_id,
+                user_id: creator,
+            })
+            .await
+            .unwrap();
+
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::ParticipantRoleChanged { user_id, role, .. }) => {
+                assert_eq!(user_id, &member);
+                assert_eq!(role, &ParticipantRole::Owner);
+            }
+            _ => panic!("expected ParticipantRoleChanged event last"),
+        }
+    }
 }
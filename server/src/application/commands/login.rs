@@ -0,0 +1,316 @@
+use crate::domain::clock::Clock;
+use crate::domain::credentials::verify_decoy;
+use crate::domain::errors::DomainError;
+use crate::domain::repository::{CredentialRepository, LoginChallengeRepository, SessionRepository, UserRepository};
+use crate::domain::session::{LoginChallenge, Session, SessionToken};
+use crate::domain::user::{User, Username};
+
+pub struct LoginCommand {
+    pub username: String,
+    pub password: String,
+}
+
+pub struct LoginResult {
+    pub user: User,
+    pub session: Session,
+    pub token: SessionToken,
+}
+
+pub enum LoginOutcome {
+    Authenticated(LoginResult),
+    TwoFactorRequired { challenge: LoginChallenge, token: SessionToken },
+}
+
+pub struct LoginHandler<U: UserRepository, K: CredentialRepository, S: SessionRepository, L: LoginChallengeRepository, Clk: Clock> {
+    users: U,
+    credentials: K,
+    sessions: S,
+    challenges: L,
+    clock: Clk,
+}
+
+impl<U: UserRepository, K: CredentialRepository, S: SessionRepository, L: LoginChallengeRepository, Clk: Clock>
+    LoginHandler<U, K, S, L, Clk>
+{
+    pub fn new(users: U, credentials: K, sessions: S, challenges: L, clock: Clk) -> Self {
+        Self {
+            users,
+            credentials,
+            sessions,
+            challenges,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: LoginCommand) -> Result<LoginOutcome, DomainError> {
+        // unknown user, missing credentials and a wrong password all look the same to the caller
+        let username = Username::new(command.username).map_err(|_| DomainError::InvalidCredentials)?;
+        let user = self
+            .users
+            .find_by_username(&username)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        let credentials = match &user {
+            Some(user) => self
+                .credentials
+                .find_by_user_id(user.id())
+                .await
+                .map_err(|e| DomainError::Internal(e.to_string()))?,
+            None => None,
+        };
+
+        // ...and take the same time: with no account to check, the password still goes through argon2
+        let password = command.password;
+        let verified = tokio::task::spawn_blocking(move || match credentials {
+            Some(credentials) => credentials.verify(&password),
+            None => verify_decoy(&password),
+        })
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?;
+        let (Some(user), true) = (user, verified) else {
+            return Err(DomainError::InvalidCredentials);
+        };
+
+        // the password alone is not enough, the caller has to come back with a code
+        if *user.two_factor_enabled() {
+            let (challenge, token) = LoginChallenge::issue(user.id().clone(), self.clock.now());
+            self.challenges
+                .save(&challenge)
+                .await
+                .map_err(|e| DomainError::Internal(e.to_string()))?;
+            return Ok(LoginOutcome::TwoFactorRequired { challenge, token });
+        }
+
+        let (session, token) = Session::issue(user.id().clone(), self.clock.now());
+
+        self.sessions
+            .save(&session)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(LoginOutcome::Authenticated(LoginResult { user, session, token }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::credentials::{Credentials, Password};
+    use crate::domain::ids::{LoginChallengeId, SessionId, UserId};
+    use crate::domain::repository::RepoError;
+    use crate::domain::user::DisplayName;
+
+    struct MockUserRepository {
+        user: Option<User>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, _id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_by_username(&self, username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(self.user.as_ref().filter(|u| u.username() == username).map(|u| {
+                User::from_persistence(
+                    u.id().clone(),
+                    u.username().clone(),
+                    u.display_name().clone(),
+                    *u.two_factor_enabled(),
+                    *u.created_at(),
+                )
+            }))
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    struct MockCredentialRepository {
+        credentials: Option<Credentials>,
+    }
+
+    #[async_trait]
+    impl CredentialRepository for MockCredentialRepository {
+        async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Option<Credentials>, RepoError> {
+            Ok(self
+                .credentials
+                .as_ref()
+                .map(|c| Credentials::from_persistence(c.user_id().clone(), c.password_hash().clone(), *c.updated_at())))
+        }
+
+        async fn save(&self, _credentials: &Credentials) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockSessionRepository {
+        saved: Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl SessionRepository for MockSessionRepository {
+        async fn find_by_token_hash(&self, _token_hash: &[u8]) -> Result<Option<Session>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, session: &Session) -> Result<(), RepoError> {
+            self.saved.lock().unwrap().push(session.token_hash().clone());
+            Ok(())
+        }
+
+        async fn delete(&self, _id: &SessionId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete_all_for_user_except(&self, _user_id: &UserId, _keep: &SessionId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockLoginChallengeRepository {
+        saved: Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl LoginChallengeRepository for MockLoginChallengeRepository {
+        async fn find_by_token_hash(&self, _token_hash: &[u8]) -> Result<Option<LoginChallenge>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, challenge: &LoginChallenge) -> Result<(), RepoError> {
+            self.saved.lock().unwrap().push(challenge.token_hash().clone());
+            Ok(())
+        }
+
+        async fn take_attempt(&self, _id: &LoginChallengeId, _now: DateTime<Utc>) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+
+        async fn consume(&self, _id: &LoginChallengeId) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    type TestLoginHandler =
+        LoginHandler<MockUserRepository, MockCredentialRepository, MockSessionRepository, MockLoginChallengeRepository, FixedClock>;
+
+    fn handler_for_alice() -> TestLoginHandler {
+        handler_for(User::new(
+            UserId::new(),
+            Username::new("alice".into()).unwrap(),
+            DisplayName::new("Alice".into()).unwrap(),
+        ))
+    }
+
+    fn handler_for(user: User) -> TestLoginHandler {
+        let credentials = Credentials::new(user.id().clone(), &Password::new("correct horse".into()).unwrap()).unwrap();
+
+        LoginHandler::new(
+            MockUserRepository { user: Some(user) },
+            MockCredentialRepository {
+                credentials: Some(credentials),
+            },
+            MockSessionRepository::default(),
+            MockLoginChallengeRepository::default(),
+            FixedClock(Utc::now()),
+        )
+    }
+
+    fn authenticated(outcome: LoginOutcome) -> LoginResult {
+        match outcome {
+            LoginOutcome::Authenticated(result) => result,
+            LoginOutcome::TwoFactorRequired { .. } => panic!("expected a session, got a two-factor challenge"),
+        }
+    }
+
+    fn command(username: &str, password: &str) -> LoginCommand {
+        LoginCommand {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_unknown_username() {
+        let handler = handler_for_alice();
+
+        let result = handler.handle(command("bob", "correct horse")).await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidCredentials));
+        assert!(handler.sessions.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_wrong_password() {
+        let handler = handler_for_alice();
+
+        let result = handler.handle(command("alice", "battery staple")).await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidCredentials));
+        assert!(handler.sessions.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_user_without_credentials() {
+        let mut handler = handler_for_alice();
+        handler.credentials = MockCredentialRepository { credentials: None };
+
+        let result = handler.handle(command("alice", "correct horse")).await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidCredentials));
+    }
+
+    #[tokio::test]
+    async fn handle_persists_only_the_hash_of_the_issued_token() {
+        let handler = handler_for_alice();
+
+        let result = authenticated(handler.handle(command("alice", "correct horse")).await.unwrap());
+
+        assert_eq!(result.user.username().as_str(), "alice");
+        assert_eq!(result.session.user_id(), result.user.id());
+        assert_eq!(result.session.created_at(), &handler.clock.0);
+        let saved = handler.sessions.saved.lock().unwrap();
+        assert_eq!(saved.as_slice(), &[result.token.hash()]);
+        assert!(handler.challenges.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_issues_challenge_instead_of_session_when_two_factor_enabled() {
+        let mut user = User::new(
+            UserId::new(),
+            Username::new("alice".into()).unwrap(),
+            DisplayName::new("Alice".into()).unwrap(),
+        );
+        user.enable_two_factor().unwrap();
+        let handler = handler_for(user);
+
+        let outcome = handler.handle(command("alice", "correct horse")).await.unwrap();
+
+        let LoginOutcome::TwoFactorRequired { challenge, token } = outcome else {
+            panic!("expected a two-factor challenge");
+        };
+        assert!(challenge.is_usable(handler.clock.0));
+        assert_eq!(handler.challenges.saved.lock().unwrap().as_slice(), &[token.hash()]);
+        assert!(handler.sessions.saved.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::domain::errors::DomainError;
+use crate::domain::ids::SessionId;
+use crate::domain::repository::SessionRepository;
+
+pub struct LogoutCommand {
+    pub session_id: SessionId,
+}
+
+pub struct LogoutHandler<S: SessionRepository> {
+    sessions: S,
+}
+
+impl<S: SessionRepository> LogoutHandler<S> {
+    pub fn new(sessions: S) -> Self {
+        Self { sessions }
+    }
+
+    pub async fn handle(&self, command: LogoutCommand) -> Result<(), DomainError> {
+        self.sessions
+            .delete(&command.session_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::ids::UserId;
+    use crate::domain::repository::RepoError;
+    use crate::domain::session::Session;
+
+    #[derive(Default)]
+    struct MockSessionRepository {
+        deleted: Mutex<Option<SessionId>>,
+    }
+
+    #[async_trait]
+    impl SessionRepository for MockSessionRepository {
+        async fn find_by_token_hash(&self, _token_hash: &[u8]) -> Result<Option<Session>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _session: &Session) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, id: &SessionId) -> Result<(), RepoError> {
+            *self.deleted.lock().unwrap() = Some(id.clone());
+            Ok(())
+        }
+
+        async fn delete_all_for_user_except(&self, _user_id: &UserId, _keep: &SessionId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_deletes_the_session() {
+        let handler = LogoutHandler::new(MockSessionRepository::default());
+        let session_id = SessionId::new();
+
+        handler
+            .handle(LogoutCommand {
+                session_id: session_id.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(handler.sessions.deleted.lock().unwrap().as_ref(), Some(&session_id));
+    }
+}
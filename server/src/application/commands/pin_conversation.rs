@@ -0,0 +1,42 @@
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{EventPublisher, ListEntryRepository},
+};
+
+pub struct PinConversationCommand {
+    pub conversation_id: ConversationId,
+    pub user_id: UserId,
+    pub pinned: bool,
+}
+
+pub struct PinConversationHandler<L: ListEntryRepository, P: EventPublisher, Clk: Clock> {
+    entries: L,
+    events: P,
+    clock: Clk,
+}
+
+impl<L: ListEntryRepository, P: EventPublisher, Clk: Clock> PinConversationHandler<L, P, Clk> {
+    pub fn new(entries: L, events: P, clock: Clk) -> Self {
+        Self { entries, events, clock }
+    }
+
+    pub async fn handle(&self, command: PinConversationCommand) -> Result<(), DomainError> {
+        let mut entry = self
+            .entries
+            .find(&command.conversation_id, &command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::NotAParticipant)?;
+
+        let Some(event) = entry.set_pinned(command.pinned, self.clock.now()) else {
+            return Ok(());
+        };
+
+        self.entries.save(&entry).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, MessageId, UserId},
+    pin::Pin,
+    repository::{ConversationRepository, EventPublisher, MessageRepository, PinRepository},
+};
+
+pub struct PinMessageCommand {
+    pub conversation_id: ConversationId,
+    pub message_id: MessageId,
+    pub actor_id: UserId,
+}
+
+pub struct PinMessageHandler<C: ConversationRepository, M: MessageRepository, Pn: PinRepository, P: EventPublisher> {
+    conversations: C,
+    messages: M,
+    pins: Pn,
+    events: P,
+}
+
+impl<C: ConversationRepository, M: MessageRepository, Pn: PinRepository, P: EventPublisher> PinMessageHandler<C, M, Pn, P> {
+    pub fn new(conversations: C, messages: M, pins: Pn, events: P) -> Self {
+        Self {
+            conversations,
+            messages,
+            pins,
+            events,
+        }
+    }
+
+    pub async fn handle(&self, command: PinMessageCommand) -> Result<(), DomainError> {
+        let conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+        let message = self
+            .messages
+            .find_by_id(&command.message_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::MessageNotFound)?;
+
+        let (pin, event) = Pin::add(&conversation, &message, command.actor_id)?;
+
+        // pinning something already pinned keeps the original pin rather than bumping it, and only
+        // the request that actually pinned it tells anyone
+        if self.pins.save(&pin).await.map_err(|e| DomainError::Internal(e.to_string()))? {
+            self.events.publish(event).await.ok();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{Message, MessageKind, MessageRevision};
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockMessageRepository {
+        message: Mutex<Option<Message>>,
+    }
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(self.message.lock().unwrap().take())
+        }
+
+        async fn save(&self, _message: &Message) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockPinRepository {
+        existing: bool,
+        saved: Mutex<Vec<MessageId>>,
+    }
+
+    #[async_trait]
+    impl PinRepository for MockPinRepository {
+        async fn find(&self, conversation_id: &ConversationId, message_id: &MessageId) -> Result<Option<Pin>, RepoError> {
+            Ok(self
+                .existing
+                .then(|| Pin::from_persistence(conversation_id.clone(), message_id.clone(), UserId::new(), chrono::Utc::now())))
+        }
+
+        async fn save(&self, pin: &Pin) -> Result<bool, RepoError> {
+            if self.existing {
+                return Ok(false);
+            }
+            self.saved.lock().unwrap().push(pin.message_id().clone());
+            Ok(true)
+        }
+
+        async fn delete(&self, _pin: &Pin) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    type TestHandler = PinMessageHandler<MockConversationRepository, MockMessageRepository, MockPinRepository, MockEventPublisher>;
+
+    // a direct conversation between `a` and `b` holding one message from `a`
+    fn handler(a: &UserId, b: &UserId, pins: MockPinRepository) -> (TestHandler, PinMessageCommand) {
+        let conversation = Conversation::new_direct(ConversationId::new(), a.clone(), b.clone()).unwrap();
+        let (message, _) = Message::new(
+            MessageId::new(),
+            conversation.id().clone(),
+            a.clone(),
+            "hello".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let command = PinMessageCommand {
+            conversation_id: conversation.id().clone(),
+            message_id: message.id().clone(),
+            actor_id: b.clone(),
+        };
+
+        let handler = PinMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockMessageRepository {
+                message: Mutex::new(Some(message)),
+            },
+            pins,
+            MockEventPublisher::default(),
+        );
+        (handler, command)
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_non_participant() {
+        let (handler, command) = handler(&UserId::new(), &UserId::new(), MockPinRepository::default());
+
+        let result = handler
+            .handle(PinMessageCommand {
+                actor_id: UserId::new(),
+                ..command
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAParticipant));
+        assert!(handler.pins.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_is_a_no_op_when_already_pinned() {
+        let (handler, command) = handler(
+            &UserId::new(),
+            &UserId::new(),
+            MockPinRepository {
+                existing: true,
+                ..Default::default()
+            },
+        );
+
+        handler.handle(command).await.unwrap();
+
+        assert!(handler.pins.saved.lock().unwrap().is_empty());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_pin_and_publishes_event() {
+        let (handler, command) = handler(&UserId::new(), &UserId::new(), MockPinRepository::default());
+        let message_id = command.message_id.clone();
+
+        handler.handle(command).await.unwrap();
+
+        assert_eq!(handler.pins.saved.lock().unwrap().as_slice(), std::slice::from_ref(&message_id));
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::MessagePinned { message_id: event_id, .. }) => assert_eq!(event_id, &message_id),
+            _ => panic!("expected MessagePinned event"),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::domain::credentials::{Credentials, Password};
+use crate::domain::errors::DomainError;
+use crate::domain::ids::UserId;
+use crate::domain::repository::UserRepository;
+use crate::domain::user::{DisplayName, User, Username};
+
+pub struct RegisterCommand {
+    pub username: String,
+    pub display_name: String,
+    pub password: String,
+}
+
+pub struct RegisterHandler<U: UserRepository> {
+    users: U,
+}
+
+impl<U: UserRepository> RegisterHandler<U> {
+    pub fn new(users: U) -> Self {
+        Self { users }
+    }
+
+    pub async fn handle(&self, command: RegisterCommand) -> Result<User, DomainError> {
+        let username = Username::new(command.username)?;
+        let display_name = DisplayName::new(command.display_name)?;
+        let password = Password::new(command.password)?;
+
+        if self
+            .users
+            .find_by_username(&username)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .is_some()
+        {
+            return Err(DomainError::UsernameTaken);
+        }
+
+        let user = User::new(UserId::new(), username, display_name);
+        let user_id = user.id().clone();
+        let credentials = tokio::task::spawn_blocking(move || Credentials::new(user_id, &password))
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))??;
+
+        // someone else can take the name between the check above and here
+        if !self
+            .users
+            .create(&user, &credentials)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::UsernameTaken);
+        }
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::repository::RepoError;
+
+    #[derive(Default)]
+    struct MockUserRepository {
+        users: Mutex<Vec<User>>,
+        credentials: Mutex<Vec<Credentials>>,
+        // lets a registration through the username check, as if it raced another one
+        skip_lookup: bool,
+    }
+
+    fn clone_user(user: &User) -> User {
+        User::from_persistence(
+            user.id().clone(),
+            user.username().clone(),
+            user.display_name().clone(),
+            *user.two_factor_enabled(),
+            *user.created_at(),
+        )
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(self.users.lock().unwrap().iter().find(|u| u.id() == id).map(clone_user))
+        }
+
+        async fn find_by_username(&self, username: &Username) -> Result<Option<User>, RepoError> {
+            if self.skip_lookup {
+                return Ok(None);
+            }
+            Ok(self.users.lock().unwrap().iter().find(|u| u.username() == username).map(clone_user))
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(self.users.lock().unwrap().iter().map(clone_user).collect())
+        }
+
+        async fn save(&self, user: &User) -> Result<(), RepoError> {
+            self.users.lock().unwrap().push(clone_user(user));
+            Ok(())
+        }
+
+        async fn create(&self, user: &User, credentials: &Credentials) -> Result<bool, RepoError> {
+            let mut users = self.users.lock().unwrap();
+            if users.iter().any(|u| u.username() == user.username()) {
+                return Ok(false);
+            }
+            users.push(clone_user(user));
+            self.credentials.lock().unwrap().push(Credentials::from_persistence(
+                credentials.user_id().clone(),
+                credentials.password_hash().clone(),
+                *credentials.updated_at(),
+            ));
+            Ok(true)
+        }
+    }
+
+    fn command(username: &str, password: &str) -> RegisterCommand {
+        RegisterCommand {
+            username: username.into(),
+            display_name: "Alice".into(),
+            password: password.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_creates_user_with_hashed_credentials() {
+        let handler = RegisterHandler::new(MockUserRepository::default());
+
+        let user = handler.handle(command("alice", "correct horse")).await.unwrap();
+
+        assert_eq!(user.username().as_str(), "alice");
+        let saved = handler.users.credentials.lock().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].user_id(), user.id());
+        assert!(saved[0].verify("correct horse"));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_taken_username() {
+        let handler = RegisterHandler::new(MockUserRepository::default());
+        handler.handle(command("alice", "correct horse")).await.unwrap();
+
+        let result = handler.handle(command("alice", "another password")).await;
+
+        assert_eq!(result.err(), Some(DomainError::UsernameTaken));
+        assert_eq!(handler.users.credentials.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_username_taken_after_the_check() {
+        let handler = RegisterHandler::new(MockUserRepository {
+            skip_lookup: true,
+            ..Default::default()
+        });
+        handler.handle(command("alice", "correct horse")).await.unwrap();
+
+        let result = handler.handle(command("alice", "another password")).await;
+
+        assert_eq!(result.err(), Some(DomainError::UsernameTaken));
+        assert_eq!(handler.users.users.lock().unwrap().len(), 1);
+        assert_eq!(handler.users.credentials.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_empty_username() {
+        let handler = RegisterHandler::new(MockUserRepository::default());
+
+        let result = handler.handle(command("  ", "correct horse")).await;
+
+        assert_eq!(result.err(), Some(DomainError::EmptyUsername));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_short_password() {
+        let handler = RegisterHandler::new(MockUserRepository::default());
+
+        let result = handler.handle(command("alice", "short")).await;
+
+        assert_eq!(result.err(), Some(DomainError::PasswordTooShort));
+        assert!(handler.users.users.lock().unwrap().is_empty());
+    }
+}
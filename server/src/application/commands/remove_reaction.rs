@@ -0,0 +1,139 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{MessageId, UserId},
+    reaction::Emoji,
+    repository::{EventPublisher, ReactionRepository},
+};
+
+pub struct RemoveReactionCommand {
+    pub message_id: MessageId,
+    pub user_id: UserId,
+    pub emoji: String,
+}
+
+pub struct RemoveReactionHandler<R: ReactionRepository, P: EventPublisher> {
+    reactions: R,
+    events: P,
+}
+
+impl<R: ReactionRepository, P: EventPublisher> RemoveReactionHandler<R, P> {
+    pub fn new(reactions: R, events: P) -> Self {
+        Self { reactions, events }
+    }
+
+    // only ever touches the caller's own reaction, so no participant check is needed
+    pub async fn handle(&self, command: RemoveReactionCommand) -> Result<(), DomainError> {
+        let emoji = Emoji::new(command.emoji)?;
+        let Some(reaction) = self
+            .reactions
+            .find(&command.message_id, &command.user_id, &emoji)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        else {
+            return Ok(());
+        };
+
+        let event = reaction.remove();
+
+        self.reactions
+            .delete(&reaction)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::ids::ConversationId;
+    use crate::domain::reaction::Reaction;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    #[derive(Default)]
+    struct MockReactionRepository {
+        existing: bool,
+        deleted: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl ReactionRepository for MockReactionRepository {
+        async fn find(&self, message_id: &MessageId, user_id: &UserId, emoji: &Emoji) -> Result<Option<Reaction>, RepoError> {
+            Ok(self.existing.then(|| {
+                Reaction::from_persistence(
+                    message_id.clone(),
+                    ConversationId::new(),
+                    user_id.clone(),
+                    emoji.clone(),
+                    chrono::Utc::now(),
+                )
+            }))
+        }
+
+        async fn save(&self, _reaction: &Reaction) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, _reaction: &Reaction) -> Result<(), RepoError> {
+            *self.deleted.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    fn command() -> RemoveReactionCommand {
+        RemoveReactionCommand {
+            message_id: MessageId::new(),
+            user_id: UserId::new(),
+            emoji: "👍".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_is_a_no_op_when_not_reacted() {
+        let handler = RemoveReactionHandler::new(MockReactionRepository::default(), MockEventPublisher::default());
+
+        handler.handle(command()).await.unwrap();
+
+        assert!(!*handler.reactions.deleted.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_deletes_reaction_and_publishes_event() {
+        let handler = RemoveReactionHandler::new(
+            MockReactionRepository {
+                existing: true,
+                ..Default::default()
+            },
+            MockEventPublisher::default(),
+        );
+
+        handler.handle(command()).await.unwrap();
+
+        assert!(*handler.reactions.deleted.lock().unwrap());
+        assert!(matches!(
+            *handler.events.published.lock().unwrap(),
+            Some(DomainEvent::ReactionRemoved { .. })
+        ));
+    }
+}
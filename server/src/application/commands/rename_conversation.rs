@@ -0,0 +1,149 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher},
+};
+
+pub struct RenameConversationCommand {
+    pub conversation_id: ConversationId,
+    pub actor_id: UserId,
+    pub title: String,
+}
+
+pub struct RenameConversationHandler<C: ConversationRepository, P: EventPublisher> {
+    conversations: C,
+    events: P,
+}
+
+impl<C: ConversationRepository, P: EventPublisher> RenameConversationHandler<C, P> {
+    pub fn new(conversations: C, events: P) -> Self {
+        Self { conversations, events }
+    }
+
+    pub async fn handle(&self, command: RenameConversationCommand) -> Result<(), DomainError> {
+        let mut conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let event = conversation.rename(&command.actor_id, command.title)?;
+
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+        saved_title: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, conversation: &Conversation) -> Result<(), RepoError> {
+            *self.saved_title.lock().unwrap() = conversation.title().clone();
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    fn handler(conversation: Option<Conversation>) -> RenameConversationHandler<MockConversationRepository, MockEventPublisher> {
+        RenameConversationHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(conversation),
+                saved_title: Mutex::new(None),
+            },
+            MockEventPublisher::default(),
+        )
+    }
+
+    fn command(actor_id: UserId, title: &str) -> RenameConversationCommand {
+        RenameConversationCommand {
+            conversation_id: ConversationId::new(),
+            actor_id,
+            title: title.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_returns_conversation_not_found_when_missing() {
+        let handler = handler(None);
+
+        let result = handler.handle(command(UserId::new(), "Renamed")).await;
+
+        assert_eq!(result.err(), Some(DomainError::ConversationNotFound));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_plain_member() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        conversation.add_participant(&owner, member.clone()).unwrap();
+        let handler = handler(Some(conversation));
+
+        let result = handler.handle(command(member, "Renamed")).await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAnAdmin));
+        assert!(handler.conversations.saved_title.lock().unwrap().is_none());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_new_title_and_publishes_event() {
+        let owner = UserId::new();
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let handler = handler(Some(conversation));
+
+        handler.handle(command(owner, "Renamed")).await.unwrap();
+
+        assert_eq!(handler.conversations.saved_title.lock().unwrap().as_deref(), Some("Renamed"));
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::ConversationRenamed { title, .. }) => assert_eq!(title, "Renamed"),
+            _ => panic!("expected ConversationRenamed event"),
+        }
+    }
+}
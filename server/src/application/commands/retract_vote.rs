@@ -0,0 +1,235 @@
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{MessageId, UserId},
+    repository::{EventPublisher, MessageRepository, PollVoteRepository},
+};
+
+pub struct RetractVoteCommand {
+    pub message_id: MessageId,
+    pub user_id: UserId,
+}
+
+pub struct RetractVoteHandler<M: MessageRepository, V: PollVoteRepository, P: EventPublisher, Clk: Clock> {
+    messages: M,
+    votes: V,
+    events: P,
+    clock: Clk,
+}
+
+impl<M: MessageRepository, V: PollVoteRepository, P: EventPublisher, Clk: Clock> RetractVoteHandler<M, V, P, Clk> {
+    pub fn new(messages: M, votes: V, events: P, clock: Clk) -> Self {
+        Self {
+            messages,
+            votes,
+            events,
+            clock,
+        }
+    }
+
+    // only ever touches the caller's own vote, so no participant check is needed
+    pub async fn handle(&self, command: RetractVoteCommand) -> Result<(), DomainError> {
+        let Some(vote) = self
+            .votes
+            .find(&command.message_id, &command.user_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        let message = self
+            .messages
+            .find_by_id(&command.message_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::MessageNotFound)?;
+
+        let event = vote.retract(&message, self.clock.now())?;
+
+        self.votes.delete(&vote).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::ids::ConversationId;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::{Message, MessageRevision};
+    use crate::domain::poll::{Poll, PollVote};
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockMessageRepository {
+        message: Mutex<Option<Message>>,
+    }
+
+    #[async_trait]
+    impl MessageRepository for MockMessageRepository {
+        async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
+            Ok(self.message.lock().unwrap().take())
+        }
+
+        async fn save(&self, _message: &Message) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockPollVoteRepository {
+        existing: bool,
+        deleted: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl PollVoteRepository for MockPollVoteRepository {
+        async fn find(&self, message_id: &MessageId, user_id: &UserId) -> Result<Option<PollVote>, RepoError> {
+            Ok(self
+                .existing
+                .then(|| PollVote::from_persistence(message_id.clone(), ConversationId::new(), user_id.clone(), vec![0], Utc::now())))
+        }
+
+        async fn save(&self, _vote: &PollVote) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, _vote: &PollVote) -> Result<(), RepoError> {
+            *self.deleted.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    // a poll that closes in an hour, checked at `now`
+    fn handler(
+        votes: MockPollVoteRepository,
+        now: DateTime<Utc>,
+    ) -> (
+        RetractVoteHandler<MockMessageRepository, MockPollVoteRepository, MockEventPublisher, FixedClock>,
+        MessageId,
+    ) {
+        let poll = Poll::new(vec!["yes".into(), "no".into()], false, Some(Utc::now() + Duration::hours(1))).unwrap();
+        let (message, _) = Message::new_poll(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "Lunch?".into(),
+            poll,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let message_id = message.id().clone();
+        let handler = RetractVoteHandler::new(
+            MockMessageRepository {
+                message: Mutex::new(Some(message)),
+            },
+            votes,
+            MockEventPublisher::default(),
+            FixedClock(now),
+        );
+        (handler, message_id)
+    }
+
+    #[tokio::test]
+    async fn removes_the_vote_and_publishes() {
+        let (handler, message_id) = handler(
+            MockPollVoteRepository {
+                existing: true,
+                ..Default::default()
+            },
+            Utc::now(),
+        );
+
+        handler
+            .handle(RetractVoteCommand {
+                message_id,
+                user_id: UserId::new(),
+            })
+            .await
+            .unwrap();
+
+        assert!(*handler.votes.deleted.lock().unwrap());
+        assert!(matches!(
+            handler.events.published.lock().unwrap().as_ref(),
+            Some(DomainEvent::PollVoteRetracted { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_vote_is_a_no_op() {
+        let (handler, message_id) = handler(MockPollVoteRepository::default(), Utc::now());
+
+        handler
+            .handle(RetractVoteCommand {
+                message_id,
+                user_id: UserId::new(),
+            })
+            .await
+            .unwrap();
+
+        assert!(!*handler.votes.deleted.lock().unwrap());
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn keeps_the_vote_once_the_poll_closes() {
+        let (handler, message_id) = handler(
+            MockPollVoteRepository {
+                existing: true,
+                ..Default::default()
+            },
+            Utc::now() + Duration::hours(2),
+        );
+
+        let result = handler
+            .handle(RetractVoteCommand {
+                message_id,
+                user_id: UserId::new(),
+            })
+            .await;
+
+        assert_eq!(result, Err(DomainError::PollClosed));
+        assert!(!*handler.votes.deleted.lock().unwrap());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{ConversationId, InviteId, UserId},
+    repository::{ConversationRepository, InviteRepository},
+};
+
+pub struct RevokeInviteCommand {
+    pub conversation_id: ConversationId,
+    pub invite_id: InviteId,
+    pub actor_id: UserId,
+}
+
+pub struct RevokeInviteHandler<C: ConversationRepository, I: InviteRepository, Clk: Clock> {
+    conversations: C,
+    invites: I,
+    clock: Clk,
+}
+
+impl<C: ConversationRepository, I: InviteRepository, Clk: Clock> RevokeInviteHandler<C, I, Clk> {
+    pub fn new(conversations: C, invites: I, clock: Clk) -> Self {
+        Self {
+            conversations,
+            invites,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: RevokeInviteCommand) -> Result<(), DomainError> {
+        let mut invite = self
+            .invites
+            .find_by_id(&command.invite_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .filter(|invite| invite.conversation_id() == &command.conversation_id)
+            .ok_or(DomainError::InviteNotFound)?;
+
+        let conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        invite.revoke(&conversation, &command.actor_id, self.clock.now())?;
+
+        self.invites.save(&invite).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::invite::Invite;
+    use crate::domain::repository::RepoError;
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockInviteRepository {
+        invite: Mutex<Option<Invite>>,
+        saved_revoked: Mutex<Option<bool>>,
+    }
+
+    #[async_trait]
+    impl InviteRepository for MockInviteRepository {
+        async fn find_by_id(&self, _id: &InviteId) -> Result<Option<Invite>, RepoError> {
+            Ok(self.invite.lock().unwrap().take())
+        }
+
+        async fn find_by_token(&self, _token: &str) -> Result<Option<Invite>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, invite: &Invite) -> Result<(), RepoError> {
+            *self.saved_revoked.lock().unwrap() = Some(invite.revoked_at().is_some());
+            Ok(())
+        }
+
+        async fn record_use(&self, _invite: &Invite, _conversation: &Conversation, _now: DateTime<Utc>) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    // a group owned by `owner` with one open invite; returns the group's id
+    fn handler(
+        owner: &UserId,
+    ) -> (
+        RevokeInviteHandler<MockConversationRepository, MockInviteRepository, FixedClock>,
+        ConversationId,
+    ) {
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let conversation_id = conversation.id().clone();
+        let invite = Invite::create(&conversation, owner, None, None, Utc::now()).unwrap();
+        let handler = RevokeInviteHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockInviteRepository {
+                invite: Mutex::new(Some(invite)),
+                saved_revoked: Mutex::new(None),
+            },
+            FixedClock(Utc::now()),
+        );
+        (handler, conversation_id)
+    }
+
+    #[tokio::test]
+    async fn handle_hides_invites_of_other_conversations() {
+        let owner = UserId::new();
+        let (handler, _) = handler(&owner);
+
+        let result = handler
+            .handle(RevokeInviteCommand {
+                conversation_id: ConversationId::new(),
+                invite_id: InviteId::new(),
+                actor_id: owner,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InviteNotFound));
+    }
+
+    #[tokio::test]
+    async fn handle_saves_revoked_invite() {
+        let owner = UserId::new();
+        let (handler, conversation_id) = handler(&owner);
+
+        handler
+            .handle(RevokeInviteCommand {
+                conversation_id,
+                invite_id: InviteId::new(),
+                actor_id: owner,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(*handler.invites.saved_revoked.lock().unwrap(), Some(true));
+    }
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{ConversationId, MessageId, ScheduledMessageId, UserId},
+    message::MessageKind,
+    repository::{ConversationRepository, ScheduledMessageRepository},
+    scheduled_message::ScheduledMessage,
+};
+
+pub struct ScheduleMessageCommand {
+    pub conversation_id: ConversationId,
+    pub sender_id: UserId,
+    pub content: String,
+    pub kind: MessageKind,
+    pub reply_to: Option<MessageId>,
+    pub send_at: DateTime<Utc>,
+}
+
+pub struct ScheduleMessageHandler<C: ConversationRepository, S: ScheduledMessageRepository, Clk: Clock> {
+    conversations: C,
+    scheduled: S,
+    clock: Clk,
+}
+
+impl<C: ConversationRepository, S: ScheduledMessageRepository, Clk: Clock> ScheduleMessageHandler<C, S, Clk> {
+    pub fn new(conversations: C, scheduled: S, clock: Clk) -> Self {
+        Self {
+            conversations,
+            scheduled,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: ScheduleMessageCommand) -> Result<ScheduledMessage, DomainError> {
+        let conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let scheduled = ScheduledMessage::schedule(
+            ScheduledMessageId::new(),
+            &conversation,
+            command.sender_id,
+            command.content,
+            command.kind,
+            command.reply_to,
+            command.send_at,
+            self.clock.now(),
+        )?;
+
+        self.scheduled
+            .add(&scheduled)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(scheduled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::RepoError;
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockScheduledMessageRepository {
+        saved: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl ScheduledMessageRepository for MockScheduledMessageRepository {
+        async fn find_by_id(&self, _id: &ScheduledMessageId) -> Result<Option<ScheduledMessage>, RepoError> {
+            Ok(None)
+        }
+
+        async fn add(&self, _scheduled: &ScheduledMessage) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = true;
+            Ok(())
+        }
+
+        async fn claim_due(
+            &self,
+            _now: DateTime<Utc>,
+            _stale_before: DateTime<Utc>,
+            _limit: i64,
+        ) -> Result<Vec<ScheduledMessage>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _scheduled: &ScheduledMessage) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn cancel(&self, _scheduled: &ScheduledMessage) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    fn command(conversation_id: ConversationId, sender_id: UserId, send_at: DateTime<Utc>) -> ScheduleMessageCommand {
+        ScheduleMessageCommand {
+            conversation_id,
+            sender_id,
+            content: "see you tomorrow".into(),
+            kind: MessageKind::Text,
+            reply_to: None,
+            send_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn stores_a_pending_message() {
+        let owner = UserId::new();
+        let convo = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let convo_id = convo.id().clone();
+        let now = Utc::now();
+        let scheduled = MockScheduledMessageRepository::default();
+        let handler = ScheduleMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(convo)),
+            },
+            scheduled,
+            FixedClock(now),
+        );
+
+        let result = handler
+            .handle(command(convo_id, owner.clone(), now + Duration::hours(8)))
+            .await
+            .unwrap();
+
+        assert_eq!(result.sender_id(), &owner);
+        assert_eq!(*result.send_at(), now + Duration::hours(8));
+        assert!(*handler.scheduled.saved.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_a_time_that_has_passed() {
+        let owner = UserId::new();
+        let convo = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let convo_id = convo.id().clone();
+        let now = Utc::now();
+        let handler = ScheduleMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(convo)),
+            },
+            MockScheduledMessageRepository::default(),
+            FixedClock(now),
+        );
+
+        let result = handler.handle(command(convo_id, owner, now - Duration::minutes(1))).await;
+
+        assert_eq!(result.err(), Some(DomainError::ScheduleInPast));
+        assert!(!*handler.scheduled.saved.lock().unwrap());
+    }
+}
@@ -1,8 +1,11 @@
 use crate::domain::{
+    conversation::Conversation,
     errors::DomainError,
     ids::{ConversationId, MessageId, UserId},
+    mention::MentionDirectory,
     message::{Message, MessageKind},
-    repository::{ConversationRepository, EventPublisher, MessageRepository},
+    poll::Poll,
+    repository::{BlockRepository, ConversationRepository, EventPublisher, MessageRepository, UploadRepository, UserRepository},
 };
 
 pub struct SendMessageCommand {
@@ -10,45 +13,167 @@ pub struct SendMessageCommand {
     pub sender_id: UserId,
     pub content: String,
     pub kind: MessageKind,
+    pub reply_to: Option<MessageId>,
+    pub poll: Option<Poll>,
 }
 
-pub struct SendMessageHandler<C: ConversationRepository, M: MessageRepository, P: EventPublisher> {
+// usernames are only looked up when the text could mention someone
+pub(crate) async fn mention_directory<U: UserRepository>(
+    users: &U,
+    conversation: &Conversation,
+    content: &str,
+) -> Result<MentionDirectory, DomainError> {
+    if !content.contains('@') {
+        return Ok(MentionDirectory::default());
+    }
+    let ids: Vec<UserId> = conversation.participants().iter().map(|p| p.user_id.clone()).collect();
+    let participants = users.find_many(&ids).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+    Ok(MentionDirectory::from_users(&participants))
+}
+
+pub struct SendMessageHandler<
+    C: ConversationRepository,
+    M: MessageRepository,
+    U: UserRepository,
+    B: BlockRepository,
+    F: UploadRepository,
+    P: EventPublisher,
+> {
     conversations: C,
     messages: M,
+    users: U,
+    blocks: B,
+    uploads: F,
     events: P,
 }
 
-impl<C: ConversationRepository, M: MessageRepository, P: EventPublisher> SendMessageHandler<C, M, P> {
-    pub fn new(conversations: C, messages: M, events: P) -> Self {
+impl<C: ConversationRepository, M: MessageRepository, U: UserRepository, B: BlockRepository, F: UploadRepository, P: EventPublisher>
+    SendMessageHandler<C, M, U, B, F, P>
+{
+    pub fn new(conversations: C, messages: M, users: U, blocks: B, uploads: F, events: P) -> Self {
         Self {
             conversations,
             messages,
+            users,
+            blocks,
+            uploads,
             events,
         }
     }
 
     pub async fn handle(&self, command: SendMessageCommand) -> Result<MessageId, DomainError> {
+        if command.reply_to.is_some() && command.poll.is_some() {
+            return Err(DomainError::PollInThread);
+        }
         let conversation = self
             .conversations
             .find_by_id(&command.conversation_id)
             .await
-            .map_err(|_| DomainError::ConversationNotFound)?
+            .map_err(|e| DomainError::Internal(e.to_string()))?
             .ok_or(DomainError::ConversationNotFound)?;
 
         if !conversation.is_participant(&command.sender_id) {
             return Err(DomainError::NotAParticipant);
         }
+        if let Some(peer) = conversation.direct_peer(&command.sender_id)
+            && self
+                .blocks
+                .is_blocked_between(&command.sender_id, peer)
+                .await
+                .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::Blocked);
+        }
 
-        let (message, event) = Message::new(
-            MessageId::new(),
-            command.conversation_id,
-            command.sender_id,
-            command.content,
-            command.kind,
-        )?;
+        // files and voice notes go out with what their upload measured, so they have to be one of ours
+        let attachment = match command.kind {
+            MessageKind::File => Some(
+                self.uploads
+                    .find_file(&command.content)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?
+                    .ok_or(DomainError::FileNeedsUrl)?,
+            ),
+            _ => None,
+        };
+        let voice_note = match command.kind {
+            MessageKind::Audio => Some(
+                self.uploads
+                    .find_voice_note(&command.content)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?
+                    .ok_or(DomainError::AudioNeedsUrl)?,
+            ),
+            _ => None,
+        };
+
+        let directory = mention_directory(&self.users, &conversation, &command.content).await?;
+        let (mut message, mut event) = match (command.reply_to, command.poll, attachment, voice_note) {
+            (Some(parent_id), _, attachment, voice_note) => {
+                let parent = self
+                    .messages
+                    .find_by_id(&parent_id)
+                    .await
+                    .map_err(|e| DomainError::Internal(e.to_string()))?
+                    .ok_or(DomainError::MessageNotFound)?;
+                Message::reply(
+                    MessageId::new(),
+                    &parent,
+                    command.conversation_id,
+                    command.sender_id,
+                    command.content,
+                    command.kind,
+                    attachment,
+                    voice_note,
+                    &directory,
+                )?
+            }
+            (None, Some(poll), _, _) => Message::new_poll(
+                MessageId::new(),
+                command.conversation_id,
+                command.sender_id,
+                command.content,
+                poll,
+                &directory,
+            )?,
+            (None, None, Some(attachment), _) => Message::new_file(
+                MessageId::new(),
+                command.conversation_id,
+                command.sender_id,
+                command.content,
+                attachment,
+                &directory,
+            )?,
+            (None, None, None, Some(voice_note)) => Message::new_voice_note(
+                MessageId::new(),
+                command.conversation_id,
+                command.sender_id,
+                command.content,
+                voice_note,
+                &directory,
+            )?,
+            (None, None, None, None) => Message::new(
+                MessageId::new(),
+                command.conversation_id,
+                command.sender_id,
+                command.content,
+                command.kind,
+                &directory,
+            )?,
+        };
+
+        if let Some(ttl) = conversation.message_ttl() {
+            event = message.expire_after(*ttl);
+        }
 
-        self.messages.save(&message).await.map_err(|_| DomainError::ConversationNotFound)?;
+        self.messages
+            .save(&message)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
         self.events.publish(event).await.ok();
+        for mention in message.mention_events(&[]) {
+            self.events.publish(mention).await.ok();
+        }
         Ok(message.id().clone())
     }
 }
@@ -58,11 +183,18 @@ mod tests {
     use std::sync::Mutex;
 
     use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
 
     use super::*;
-    use crate::domain::conversation::Conversation;
+    use crate::domain::attachment::Attachment;
+    use crate::domain::block::Block;
+    use crate::domain::credentials::Credentials;
     use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::message::MessageRevision;
     use crate::domain::repository::{PublishError, RepoError};
+    use crate::domain::user::{DisplayName, User, Username};
+    use crate::domain::voice_note::VoiceNote;
 
     struct MockConversationRepository {
         conversation: Mutex<Option<Conversation>>,
@@ -78,37 +210,156 @@ mod tests {
             Ok(self.conversation.lock().unwrap().take())
         }
 
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
         async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
             Ok(())
         }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
     }
 
     #[derive(Default)]
     struct MockMessageRepository {
+        parent: Mutex<Option<Message>>,
         saved_id: Mutex<Option<MessageId>>,
+        saved_thread_root: Mutex<Option<MessageId>>,
     }
 
     #[async_trait]
     impl MessageRepository for MockMessageRepository {
         async fn find_by_id(&self, _id: &MessageId) -> Result<Option<Message>, RepoError> {
-            Ok(None)
+            Ok(self.parent.lock().unwrap().take())
         }
 
         async fn save(&self, message: &Message) -> Result<(), RepoError> {
             *self.saved_id.lock().unwrap() = Some(message.id().clone());
+            *self.saved_thread_root.lock().unwrap() = message.thread_root_id().clone();
+            Ok(())
+        }
+
+        async fn save_all(&self, _messages: &[Message]) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn save_edit(&self, _message: &Message, _revision: &MessageRevision) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn find_expired(&self, _now: DateTime<Utc>, _limit: i64) -> Result<Vec<Message>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn delete(&self, _id: &MessageId) -> Result<(), RepoError> {
             Ok(())
         }
+
+        async fn shares_media(&self, _message: &Message) -> Result<bool, RepoError> {
+            Ok(false)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockUserRepository {
+        users: Vec<User>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(self.users.iter().find(|u| u.id() == id).map(|u| {
+                User::from_persistence(
+                    u.id().clone(),
+                    u.username().clone(),
+                    u.display_name().clone(),
+                    *u.two_factor_enabled(),
+                    *u.created_at(),
+                )
+            }))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    #[derive(Default)]
+    struct MockBlockRepository {
+        blocked: bool,
+    }
+
+    #[async_trait]
+    impl BlockRepository for MockBlockRepository {
+        async fn find_by_blocker(&self, _blocker_id: &UserId) -> Result<Vec<Block>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn is_blocked_between(&self, _a: &UserId, _b: &UserId) -> Result<bool, RepoError> {
+            Ok(self.blocked)
+        }
+
+        async fn save(&self, _block: &Block) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete(&self, _blocker_id: &UserId, _blocked_id: &UserId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockUploadRepository {
+        files: Vec<(String, Attachment)>,
+    }
+
+    #[async_trait]
+    impl UploadRepository for MockUploadRepository {
+        async fn save_file(&self, _url: &str, _attachment: &Attachment) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn find_file(&self, url: &str) -> Result<Option<Attachment>, RepoError> {
+            Ok(self
+                .files
+                .iter()
+                .find(|(stored, _)| stored == url)
+                .map(|(_, attachment)| attachment.clone()))
+        }
+
+        async fn save_voice_note(&self, _url: &str, _voice_note: &VoiceNote) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn find_voice_note(&self, _url: &str) -> Result<Option<VoiceNote>, RepoError> {
+            Ok(None)
+        }
     }
 
     #[derive(Default)]
     struct MockEventPublisher {
-        published: Mutex<Option<DomainEvent>>,
+        published: Mutex<Vec<DomainEvent>>,
     }
 
     #[async_trait]
     impl EventPublisher for MockEventPublisher {
         async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
-            *self.published.lock().unwrap() = Some(event);
+            self.published.lock().unwrap().push(event);
             Ok(())
         }
     }
@@ -119,6 +370,8 @@ mod tests {
             sender_id,
             content: content.to_string(),
             kind: MessageKind::Text,
+            reply_to: None,
+            poll: None,
         }
     }
 
@@ -130,6 +383,9 @@ mod tests {
                 err: false,
             },
             MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
             MockEventPublisher::default(),
         );
 
@@ -139,19 +395,22 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn handle_returns_conversation_not_found_when_repo_errors() {
+    async fn handle_returns_internal_when_repo_errors() {
         let handler = SendMessageHandler::new(
             MockConversationRepository {
                 conversation: Mutex::new(None),
                 err: true,
             },
             MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
             MockEventPublisher::default(),
         );
 
         let result = handler.handle(command(ConversationId::new(), UserId::new(), "hello")).await;
 
-        assert_eq!(result.err(), Some(DomainError::ConversationNotFound));
+        assert!(matches!(result.err(), Some(DomainError::Internal(_))));
     }
 
     #[tokio::test]
@@ -163,6 +422,9 @@ mod tests {
                 err: false,
             },
             MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
             MockEventPublisher::default(),
         );
 
@@ -171,6 +433,30 @@ mod tests {
         assert_eq!(result.err(), Some(DomainError::NotAParticipant));
     }
 
+    #[tokio::test]
+    async fn handle_rejects_direct_messages_between_blocked_users() {
+        let sender = UserId::new();
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_direct(conversation_id.clone(), sender.clone(), UserId::new()).unwrap();
+        let handler = SendMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                err: false,
+            },
+            MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository { blocked: true },
+            MockUploadRepository::default(),
+            MockEventPublisher::default(),
+        );
+
+        let result = handler.handle(command(conversation_id, sender, "hello")).await;
+
+        assert_eq!(result.err(), Some(DomainError::Blocked));
+        assert!(handler.messages.saved_id.lock().unwrap().is_none());
+        assert!(handler.events.published.lock().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn handle_propagates_domain_error_from_message_validation() {
         let sender = UserId::new();
@@ -181,6 +467,9 @@ mod tests {
                 err: false,
             },
             MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
             MockEventPublisher::default(),
         );
 
@@ -200,6 +489,9 @@ mod tests {
                 err: false,
             },
             MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
             MockEventPublisher::default(),
         );
 
@@ -208,7 +500,7 @@ mod tests {
         let message_id = result.unwrap();
         assert_eq!(handler.messages.saved_id.lock().unwrap().as_ref(), Some(&message_id));
 
-        match &*handler.events.published.lock().unwrap() {
+        match handler.events.published.lock().unwrap().first() {
             Some(DomainEvent::MessageSent {
                 message_id: event_id,
                 conversation_id: event_conversation_id,
@@ -222,4 +514,264 @@ mod tests {
             _ => panic!("expected MessageSent event"),
         }
     }
+
+    #[tokio::test]
+    async fn handle_stamps_expiry_from_the_conversation_timer() {
+        let sender = UserId::new();
+        let conversation_id = ConversationId::new();
+        let mut conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), sender.clone()).unwrap();
+        conversation.set_message_timer(&sender, Some(Duration::hours(1))).unwrap();
+        let handler = SendMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                err: false,
+            },
+            MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
+            MockEventPublisher::default(),
+        );
+
+        handler.handle(command(conversation_id, sender, "gone in an hour")).await.unwrap();
+
+        match handler.events.published.lock().unwrap().first() {
+            Some(DomainEvent::MessageSent {
+                created_at, expires_at, ..
+            }) => assert_eq!(*expires_at, Some(*created_at + Duration::hours(1))),
+            _ => panic!("expected MessageSent event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_takes_file_details_from_the_upload() {
+        let sender = UserId::new();
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), sender.clone()).unwrap();
+        let url = "http://127.0.0.1:3000/files/abc.pdf";
+        let attachment = Attachment::new("report.pdf".into(), 2048, "application/pdf".into()).unwrap();
+        let handler = SendMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                err: false,
+            },
+            MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository {
+                files: vec![(url.to_string(), attachment.clone())],
+            },
+            MockEventPublisher::default(),
+        );
+
+        handler
+            .handle(SendMessageCommand {
+                kind: MessageKind::File,
+                ..command(conversation_id, sender, url)
+            })
+            .await
+            .unwrap();
+
+        match handler.events.published.lock().unwrap().first() {
+            Some(DomainEvent::MessageSent { attachment: sent, .. }) => assert_eq!(sent.as_ref(), Some(&attachment)),
+            _ => panic!("expected MessageSent event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_files_and_voice_notes_that_were_not_uploaded_here() {
+        let sender = UserId::new();
+        let conversation_id = ConversationId::new();
+        let handler = |conversation: Conversation| {
+            SendMessageHandler::new(
+                MockConversationRepository {
+                    conversation: Mutex::new(Some(conversation)),
+                    err: false,
+                },
+                MockMessageRepository::default(),
+                MockUserRepository::default(),
+                MockBlockRepository::default(),
+                MockUploadRepository::default(),
+                MockEventPublisher::default(),
+            )
+        };
+        let group = || Conversation::new_group(conversation_id.clone(), "Group".into(), sender.clone()).unwrap();
+
+        let file = handler(group())
+            .handle(SendMessageCommand {
+                kind: MessageKind::File,
+                ..command(conversation_id.clone(), sender.clone(), "https://example.com/files/abc.pdf")
+            })
+            .await;
+        let voice_note = handler(group())
+            .handle(SendMessageCommand {
+                kind: MessageKind::Audio,
+                ..command(conversation_id.clone(), sender.clone(), "https://example.com/media/abc.ogg")
+            })
+            .await;
+
+        assert_eq!(file.err(), Some(DomainError::FileNeedsUrl));
+        assert_eq!(voice_note.err(), Some(DomainError::AudioNeedsUrl));
+    }
+
+    #[tokio::test]
+    async fn handle_returns_message_not_found_when_reply_parent_missing() {
+        let sender = UserId::new();
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), sender.clone()).unwrap();
+        let handler = SendMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                err: false,
+            },
+            MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
+            MockEventPublisher::default(),
+        );
+
+        let result = handler
+            .handle(SendMessageCommand {
+                reply_to: Some(MessageId::new()),
+                ..command(conversation_id, sender, "hello")
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::MessageNotFound));
+    }
+
+    #[tokio::test]
+    async fn handle_saves_reply_under_parent_thread() {
+        let sender = UserId::new();
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), sender.clone()).unwrap();
+        let (parent, _) = Message::new(
+            MessageId::new(),
+            conversation_id.clone(),
+            sender.clone(),
+            "root".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let parent_id = parent.id().clone();
+        let handler = SendMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                err: false,
+            },
+            MockMessageRepository {
+                parent: Mutex::new(Some(parent)),
+                ..Default::default()
+            },
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
+            MockEventPublisher::default(),
+        );
+
+        handler
+            .handle(SendMessageCommand {
+                reply_to: Some(parent_id.clone()),
+                ..command(conversation_id, sender, "hello")
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(handler.messages.saved_thread_root.lock().unwrap().as_ref(), Some(&parent_id));
+        match handler.events.published.lock().unwrap().first() {
+            Some(DomainEvent::MessageSent { thread_root_id, .. }) => assert_eq!(thread_root_id.as_ref(), Some(&parent_id)),
+            _ => panic!("expected MessageSent event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_sends_polls_but_not_into_threads() {
+        let sender = UserId::new();
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_group(conversation_id.clone(), "Group".into(), sender.clone()).unwrap();
+        let handler = SendMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                err: false,
+            },
+            MockMessageRepository::default(),
+            MockUserRepository::default(),
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
+            MockEventPublisher::default(),
+        );
+        let poll = Poll::new(vec!["yes".into(), "no".into()], false, None).unwrap();
+
+        let result = handler
+            .handle(SendMessageCommand {
+                kind: MessageKind::Poll,
+                reply_to: Some(MessageId::new()),
+                poll: Some(poll.clone()),
+                ..command(conversation_id.clone(), sender.clone(), "Lunch?")
+            })
+            .await;
+        assert_eq!(result.err(), Some(DomainError::PollInThread));
+
+        handler
+            .handle(SendMessageCommand {
+                kind: MessageKind::Poll,
+                poll: Some(poll.clone()),
+                ..command(conversation_id, sender, "Lunch?")
+            })
+            .await
+            .unwrap();
+
+        match handler.events.published.lock().unwrap().first() {
+            Some(DomainEvent::MessageSent { kind, poll: sent, .. }) => {
+                assert_eq!(kind, &MessageKind::Poll);
+                assert_eq!(sent.as_ref(), Some(&poll));
+            }
+            _ => panic!("expected MessageSent event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_publishes_mention_for_each_mentioned_participant() {
+        let alice = User::new(
+            UserId::new(),
+            Username::new("alice".into()).unwrap(),
+            DisplayName::new("Alice".into()).unwrap(),
+        );
+        let bob = User::new(
+            UserId::new(),
+            Username::new("bob".into()).unwrap(),
+            DisplayName::new("Bob".into()).unwrap(),
+        );
+        let conversation_id = ConversationId::new();
+        let conversation = Conversation::new_direct(conversation_id.clone(), alice.id().clone(), bob.id().clone()).unwrap();
+        let (alice_id, bob_id) = (alice.id().clone(), bob.id().clone());
+        let handler = SendMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                err: false,
+            },
+            MockMessageRepository::default(),
+            MockUserRepository { users: vec![alice, bob] },
+            MockBlockRepository::default(),
+            MockUploadRepository::default(),
+            MockEventPublisher::default(),
+        );
+
+        handler
+            .handle(command(conversation_id, alice_id.clone(), "@bob look, and @alice too"))
+            .await
+            .unwrap();
+
+        let published = handler.events.published.lock().unwrap();
+        assert_eq!(published.len(), 2);
+        match &published[1] {
+            DomainEvent::UserMentioned { user_id, mentioned_by, .. } => {
+                assert_eq!(user_id, &bob_id);
+                assert_eq!(mentioned_by, &alice_id);
+            }
+            _ => panic!("expected UserMentioned event"),
+        }
+    }
 }
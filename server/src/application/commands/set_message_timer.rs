@@ -0,0 +1,44 @@
+use chrono::Duration;
+
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher},
+};
+
+pub struct SetMessageTimerCommand {
+    pub conversation_id: ConversationId,
+    pub actor_id: UserId,
+    pub ttl: Option<Duration>,
+}
+
+pub struct SetMessageTimerHandler<C: ConversationRepository, P: EventPublisher> {
+    conversations: C,
+    events: P,
+}
+
+impl<C: ConversationRepository, P: EventPublisher> SetMessageTimerHandler<C, P> {
+    pub fn new(conversations: C, events: P) -> Self {
+        Self { conversations, events }
+    }
+
+    // only messages sent from now on pick up the new timer
+    pub async fn handle(&self, command: SetMessageTimerCommand) -> Result<(), DomainError> {
+        let mut conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let event = conversation.set_message_timer(&command.actor_id, command.ttl)?;
+
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
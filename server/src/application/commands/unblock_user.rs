@@ -0,0 +1,25 @@
+use crate::domain::{errors::DomainError, ids::UserId, repository::BlockRepository};
+
+pub struct UnblockUserCommand {
+    pub blocker_id: UserId,
+    pub blocked_id: UserId,
+}
+
+pub struct UnblockUserHandler<B: BlockRepository> {
+    blocks: B,
+}
+
+impl<B: BlockRepository> UnblockUserHandler<B> {
+    pub fn new(blocks: B) -> Self {
+        Self { blocks }
+    }
+
+    pub async fn handle(&self, command: UnblockUserCommand) -> Result<(), DomainError> {
+        self.blocks
+            .delete(&command.blocker_id, &command.blocked_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(())
+    }
+}
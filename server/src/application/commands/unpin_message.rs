@@ -0,0 +1,251 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, MessageId, UserId},
+    repository::{ConversationRepository, EventPublisher, PinRepository},
+};
+
+pub struct UnpinMessageCommand {
+    pub conversation_id: ConversationId,
+    pub message_id: MessageId,
+    pub actor_id: UserId,
+}
+
+pub struct UnpinMessageHandler<C: ConversationRepository, Pn: PinRepository, P: EventPublisher> {
+    conversations: C,
+    pins: Pn,
+    events: P,
+}
+
+impl<C: ConversationRepository, Pn: PinRepository, P: EventPublisher> UnpinMessageHandler<C, Pn, P> {
+    pub fn new(conversations: C, pins: Pn, events: P) -> Self {
+        Self {
+            conversations,
+            pins,
+            events,
+        }
+    }
+
+    pub async fn handle(&self, command: UnpinMessageCommand) -> Result<(), DomainError> {
+        let conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let Some(pin) = self
+            .pins
+            .find(&command.conversation_id, &command.message_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        else {
+            return Ok(());
+        };
+
+        let event = pin.remove(&conversation, &command.actor_id)?;
+
+        self.pins.delete(&pin).await.map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::pin::Pin;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockPinRepository {
+        existing: bool,
+        deleted: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl PinRepository for MockPinRepository {
+        async fn find(&self, conversation_id: &ConversationId, message_id: &MessageId) -> Result<Option<Pin>, RepoError> {
+            Ok(self
+                .existing
+                .then(|| Pin::from_persistence(conversation_id.clone(), message_id.clone(), UserId::new(), chrono::Utc::now())))
+        }
+
+        async fn save(&self, _pin: &Pin) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+
+        async fn delete(&self, _pin: &Pin) -> Result<(), RepoError> {
+            *self.deleted.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    // a group owned by `owner` with `member` as a plain member
+    fn handler(
+        owner: &UserId,
+        member: &UserId,
+        pins: MockPinRepository,
+    ) -> (
+        UnpinMessageHandler<MockConversationRepository, MockPinRepository, MockEventPublisher>,
+        ConversationId,
+    ) {
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        conversation.add_participant(owner, member.clone()).unwrap();
+        let conversation_id = conversation.id().clone();
+
+        let handler = UnpinMessageHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            pins,
+            MockEventPublisher::default(),
+        );
+        (handler, conversation_id)
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_non_participant() {
+        let owner = UserId::new();
+        let (handler, conversation_id) = handler(
+            &owner,
+            &UserId::new(),
+            MockPinRepository {
+                existing: true,
+                ..Default::default()
+            },
+        );
+
+        let result = handler
+            .handle(UnpinMessageCommand {
+                conversation_id,
+                message_id: MessageId::new(),
+                actor_id: UserId::new(),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::NotAParticipant));
+        assert!(!*handler.pins.deleted.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn handle_lets_plain_group_member_unpin() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let (handler, conversation_id) = handler(
+            &owner,
+            &member,
+            MockPinRepository {
+                existing: true,
+                ..Default::default()
+            },
+        );
+
+        handler
+            .handle(UnpinMessageCommand {
+                conversation_id,
+                message_id: MessageId::new(),
+                actor_id: member,
+            })
+            .await
+            .unwrap();
+
+        assert!(*handler.pins.deleted.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn handle_is_a_no_op_when_not_pinned() {
+        let owner = UserId::new();
+        let (handler, conversation_id) = handler(&owner, &UserId::new(), MockPinRepository::default());
+
+        handler
+            .handle(UnpinMessageCommand {
+                conversation_id,
+                message_id: MessageId::new(),
+                actor_id: owner,
+            })
+            .await
+            .unwrap();
+
+        assert!(handler.events.published.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_deletes_pin_and_publishes_event() {
+        let owner = UserId::new();
+        let (handler, conversation_id) = handler(
+            &owner,
+            &UserId::new(),
+            MockPinRepository {
+                existing: true,
+                ..Default::default()
+            },
+        );
+        let message_id = MessageId::new();
+
+        handler
+            .handle(UnpinMessageCommand {
+                conversation_id,
+                message_id: message_id.clone(),
+                actor_id: owner.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert!(*handler.pins.deleted.lock().unwrap());
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::MessageUnpinned {
+                message_id: event_id,
+                unpinned_by,
+                ..
+            }) => {
+                assert_eq!(event_id, &message_id);
+                assert_eq!(unpinned_by, &owner);
+            }
+            _ => panic!("expected MessageUnpinned event"),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::domain::{
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    repository::{ConversationRepository, EventPublisher},
+};
+
+pub struct UpdateConversationDetailsCommand {
+    pub conversation_id: ConversationId,
+    pub actor_id: UserId,
+    pub description: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+pub struct UpdateConversationDetailsHandler<C: ConversationRepository, P: EventPublisher> {
+    conversations: C,
+    events: P,
+}
+
+impl<C: ConversationRepository, P: EventPublisher> UpdateConversationDetailsHandler<C, P> {
+    pub fn new(conversations: C, events: P) -> Self {
+        Self { conversations, events }
+    }
+
+    pub async fn handle(&self, command: UpdateConversationDetailsCommand) -> Result<(), DomainError> {
+        let mut conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let event = conversation.update_details(&command.actor_id, command.description, command.avatar_url)?;
+
+        self.conversations
+            .save(&conversation)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+        saved: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = true;
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    fn handler(owner: &UserId) -> UpdateConversationDetailsHandler<MockConversationRepository, MockEventPublisher> {
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        UpdateConversationDetailsHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+                saved: Mutex::new(false),
+            },
+            MockEventPublisher::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_invalid_avatar_without_saving() {
+        let owner = UserId::new();
+        let handler = handler(&owner);
+
+        let result = handler
+            .handle(UpdateConversationDetailsCommand {
+                conversation_id: ConversationId::new(),
+                actor_id: owner,
+                description: None,
+                avatar_url: Some("not a url".into()),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::AvatarNeedsUrl));
+        assert!(!*handler.conversations.saved.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_details_and_publishes_event() {
+        let owner = UserId::new();
+        let handler = handler(&owner);
+
+        handler
+            .handle(UpdateConversationDetailsCommand {
+                conversation_id: ConversationId::new(),
+                actor_id: owner,
+                description: Some("Weekend plans".into()),
+                avatar_url: Some("https://example.com/a.png".into()),
+            })
+            .await
+            .unwrap();
+
+        assert!(*handler.conversations.saved.lock().unwrap());
+        match &*handler.events.published.lock().unwrap() {
+            Some(DomainEvent::ConversationDetailsChanged {
+                description, avatar_url, ..
+            }) => {
+                assert_eq!(description.as_deref(), Some("Weekend plans"));
+                assert_eq!(avatar_url.as_deref(), Some("https://example.com/a.png"));
+            }
+            _ => panic!("expected ConversationDetailsChanged event"),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    clock::Clock,
+    errors::DomainError,
+    ids::{ConversationId, UserId},
+    notification::{NotificationSettings, NotifyLevel},
+    repository::{ConversationRepository, EventPublisher, NotificationSettingsRepository},
+};
+
+pub struct UpdateNotificationSettingsCommand {
+    pub conversation_id: ConversationId,
+    pub user_id: UserId,
+    pub level: NotifyLevel,
+    pub muted_until: Option<DateTime<Utc>>,
+}
+
+pub struct UpdateNotificationSettingsHandler<
+    C: ConversationRepository,
+    N: NotificationSettingsRepository,
+    P: EventPublisher,
+    Clk: Clock,
+> {
+    conversations: C,
+    settings: N,
+    events: P,
+    clock: Clk,
+}
+
+impl<C: ConversationRepository, N: NotificationSettingsRepository, P: EventPublisher, Clk: Clock>
+    UpdateNotificationSettingsHandler<C, N, P, Clk>
+{
+    pub fn new(conversations: C, settings: N, events: P, clock: Clk) -> Self {
+        Self {
+            conversations,
+            settings,
+            events,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: UpdateNotificationSettingsCommand) -> Result<(), DomainError> {
+        let conversation = self
+            .conversations
+            .find_by_id(&command.conversation_id)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::ConversationNotFound)?;
+
+        let (settings, event) =
+            NotificationSettings::change(&conversation, command.user_id, command.level, command.muted_until, self.clock.now())?;
+
+        self.settings
+            .save(&settings)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.events.publish(event).await.ok();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::conversation::Conversation;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::invitation::Invitation;
+    use crate::domain::repository::{PublishError, RepoError};
+
+    struct MockConversationRepository {
+        conversation: Mutex<Option<Conversation>>,
+    }
+
+    #[async_trait]
+    impl ConversationRepository for MockConversationRepository {
+        async fn find_by_id(&self, _id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
+            Ok(self.conversation.lock().unwrap().take())
+        }
+
+        async fn find_direct_between(&self, _a: &UserId, _b: &UserId) -> Result<Option<Conversation>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, _conversation: &Conversation) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create_group(&self, _conversation: &Conversation, _invitations: &[Invitation]) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockNotificationSettingsRepository {
+        saved: Mutex<Option<(NotifyLevel, Option<DateTime<Utc>>)>>,
+    }
+
+    #[async_trait]
+    impl NotificationSettingsRepository for MockNotificationSettingsRepository {
+        async fn save(&self, settings: &NotificationSettings) -> Result<(), RepoError> {
+            *self.saved.lock().unwrap() = Some((*settings.level(), *settings.muted_until()));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        published: Mutex<Option<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, event: DomainEvent) -> Result<(), PublishError> {
+            *self.published.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    fn handler(
+        member: &UserId,
+        now: DateTime<Utc>,
+    ) -> UpdateNotificationSettingsHandler<
+        MockConversationRepository,
+        MockNotificationSettingsRepository,
+        MockEventPublisher,
+        FixedClock,
+    > {
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), member.clone()).unwrap();
+        UpdateNotificationSettingsHandler::new(
+            MockConversationRepository {
+                conversation: Mutex::new(Some(conversation)),
+            },
+            MockNotificationSettingsRepository::default(),
+            MockEventPublisher::default(),
+            FixedClock(now),
+        )
+    }
+
+    #[tokio::test]
+    async fn handle_saves_and_publishes_the_new_settings() {
+        let member = UserId::new();
+        let now = Utc::now();
+        let until = now + Duration::hours(8);
+        let handler = handler(&member, now);
+
+        handler
+            .handle(UpdateNotificationSettingsCommand {
+                conversation_id: ConversationId::new(),
+                user_id: member,
+                level: NotifyLevel::Mentions,
+                muted_until: Some(until),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(*handler.settings.saved.lock().unwrap(), Some((NotifyLevel::Mentions, Some(until))));
+        assert!(matches!(
+            *handler.events.published.lock().unwrap(),
+            Some(DomainEvent::NotificationSettingsChanged { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_a_mute_that_already_ended() {
+        let member = UserId::new();
+        let now = Utc::now();
+        let handler = handler(&member, now);
+
+        let result = handler
+            .handle(UpdateNotificationSettingsCommand {
+                conversation_id: ConversationId::new(),
+                user_id: member,
+                level: NotifyLevel::All,
+                muted_until: Some(now - Duration::minutes(5)),
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::MuteInPast));
+        assert!(handler.settings.saved.lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,450 @@
+use crate::application::commands::login::LoginResult;
+use crate::domain::clock::Clock;
+use crate::domain::errors::DomainError;
+use crate::domain::repository::{LoginChallengeRepository, SessionRepository, TotpRepository, UserRepository};
+use crate::domain::session::{Session, SessionToken};
+
+pub struct VerifyLoginCommand {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+pub struct VerifyLoginHandler<U: UserRepository, T: TotpRepository, S: SessionRepository, L: LoginChallengeRepository, Clk: Clock> {
+    users: U,
+    totp: T,
+    sessions: S,
+    challenges: L,
+    clock: Clk,
+}
+
+impl<U: UserRepository, T: TotpRepository, S: SessionRepository, L: LoginChallengeRepository, Clk: Clock>
+    VerifyLoginHandler<U, T, S, L, Clk>
+{
+    pub fn new(users: U, totp: T, sessions: S, challenges: L, clock: Clk) -> Self {
+        Self {
+            users,
+            totp,
+            sessions,
+            challenges,
+            clock,
+        }
+    }
+
+    pub async fn handle(&self, command: VerifyLoginCommand) -> Result<LoginResult, DomainError> {
+        let now = self.clock.now();
+        let token = SessionToken::from_client(command.challenge_token);
+        let challenge = self
+            .challenges
+            .find_by_token_hash(&token.hash())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .filter(|challenge| challenge.is_usable(now))
+            .ok_or(DomainError::InvalidLoginChallenge)?;
+
+        // the attempt is counted before the code is checked so a stolen password can't brute-force it
+        // with parallel guesses; only a success, which uses the challenge up, makes it not count
+        if !self
+            .challenges
+            .take_attempt(challenge.id(), now)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::InvalidLoginChallenge);
+        }
+
+        // two-factor was turned off between the password step and this one
+        let mut enrollment = self
+            .totp
+            .find_by_user_id(challenge.user_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::InvalidLoginChallenge)?;
+
+        let used = enrollment.authenticate(&command.code, now)?;
+
+        // a parallel login with the same code gets here too; only the one that spends it goes on
+        if !self
+            .totp
+            .redeem(challenge.user_id(), &used, now)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::InvalidTwoFactorCode);
+        }
+        if !self
+            .challenges
+            .consume(challenge.id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+        {
+            return Err(DomainError::InvalidLoginChallenge);
+        }
+
+        let user = self
+            .users
+            .find_by_id(challenge.user_id())
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?
+            .ok_or(DomainError::UserNotFound)?;
+
+        let (session, token) = Session::issue(user.id().clone(), now);
+
+        self.sessions
+            .save(&session)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        Ok(LoginResult { user, session, token })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use crate::domain::credentials::Credentials;
+    use crate::domain::ids::{LoginChallengeId, SessionId, UserId};
+    use crate::domain::repository::RepoError;
+    use crate::domain::session::LoginChallenge;
+    use crate::domain::two_factor::{TOTP_STEP_SECONDS, TotpEnrollment, TwoFactorUse};
+    use crate::domain::user::{DisplayName, User, Username};
+
+    struct MockUserRepository {
+        user: User,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
+            Ok(Some(&self.user).filter(|u| u.id() == id).map(|u| {
+                User::from_persistence(
+                    u.id().clone(),
+                    u.username().clone(),
+                    u.display_name().clone(),
+                    *u.two_factor_enabled(),
+                    *u.created_at(),
+                )
+            }))
+        }
+
+        async fn find_by_username(&self, _username: &Username) -> Result<Option<User>, RepoError> {
+            Ok(None)
+        }
+
+        async fn find_all(&self) -> Result<Vec<User>, RepoError> {
+            Ok(vec![])
+        }
+
+        async fn save(&self, _user: &User) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn create(&self, _user: &User, _credentials: &Credentials) -> Result<bool, RepoError> {
+            Ok(true)
+        }
+    }
+
+    fn clone_enrollment(enrollment: &TotpEnrollment) -> TotpEnrollment {
+        TotpEnrollment::from_persistence(
+            enrollment.user_id().clone(),
+            enrollment.secret().clone(),
+            *enrollment.created_at(),
+            *enrollment.confirmed_at(),
+            *enrollment.last_used_step(),
+            enrollment.recovery_codes().clone(),
+        )
+    }
+
+    struct MockTotpRepository {
+        enrollment: Mutex<Option<TotpEnrollment>>,
+        redeemed: Mutex<Vec<TwoFactorUse>>,
+    }
+
+    #[async_trait]
+    impl TotpRepository for MockTotpRepository {
+        async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Option<TotpEnrollment>, RepoError> {
+            Ok(self.enrollment.lock().unwrap().as_ref().map(clone_enrollment))
+        }
+
+        async fn save(&self, enrollment: &TotpEnrollment) -> Result<(), RepoError> {
+            *self.enrollment.lock().unwrap() = Some(clone_enrollment(enrollment));
+            Ok(())
+        }
+
+        async fn redeem(&self, _user_id: &UserId, used: &TwoFactorUse, now: DateTime<Utc>) -> Result<bool, RepoError> {
+            let mut redeemed = self.redeemed.lock().unwrap();
+            if redeemed.contains(used) {
+                return Ok(false);
+            }
+            redeemed.push(used.clone());
+
+            let mut enrollment = self.enrollment.lock().unwrap();
+            let stored = enrollment.as_ref().unwrap();
+            let mut last_used_step = *stored.last_used_step();
+            let mut recovery_codes = stored.recovery_codes().clone();
+            match used {
+                TwoFactorUse::TotpStep(step) => last_used_step = Some(*step),
+                TwoFactorUse::RecoveryCode(hash) => {
+                    recovery_codes
+                        .iter_mut()
+                        .filter(|c| &c.code_hash == hash)
+                        .for_each(|c| c.used_at = Some(now));
+                }
+            }
+            *enrollment = Some(TotpEnrollment::from_persistence(
+                stored.user_id().clone(),
+                stored.secret().clone(),
+                *stored.created_at(),
+                *stored.confirmed_at(),
+                last_used_step,
+                recovery_codes,
+            ));
+            Ok(true)
+        }
+
+        async fn delete(&self, _user_id: &UserId) -> Result<(), RepoError> {
+            *self.enrollment.lock().unwrap() = None;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockSessionRepository {
+        saved: Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl SessionRepository for MockSessionRepository {
+        async fn find_by_token_hash(&self, _token_hash: &[u8]) -> Result<Option<Session>, RepoError> {
+            Ok(None)
+        }
+
+        async fn save(&self, session: &Session) -> Result<(), RepoError> {
+            self.saved.lock().unwrap().push(session.token_hash().clone());
+            Ok(())
+        }
+
+        async fn delete(&self, _id: &SessionId) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn delete_all_for_user_except(&self, _user_id: &UserId, _keep: &SessionId) -> Result<(), RepoError> {
+            Ok(())
+        }
+    }
+
+    struct MockLoginChallengeRepository {
+        challenge: Mutex<Option<LoginChallenge>>,
+    }
+
+    #[async_trait]
+    impl LoginChallengeRepository for MockLoginChallengeRepository {
+        async fn find_by_token_hash(&self, token_hash: &[u8]) -> Result<Option<LoginChallenge>, RepoError> {
+            Ok(self
+                .challenge
+                .lock()
+                .unwrap()
+                .as_ref()
+                .filter(|c| c.token_hash().as_slice() == token_hash)
+                .map(|c| {
+                    LoginChallenge::from_persistence(
+                        c.id().clone(),
+                        c.user_id().clone(),
+                        c.token_hash().clone(),
+                        *c.expires_at(),
+                        *c.failed_attempts(),
+                    )
+                }))
+        }
+
+        async fn save(&self, _challenge: &LoginChallenge) -> Result<(), RepoError> {
+            Ok(())
+        }
+
+        async fn take_attempt(&self, _id: &LoginChallengeId, now: DateTime<Utc>) -> Result<bool, RepoError> {
+            let mut challenge = self.challenge.lock().unwrap();
+            let Some(c) = challenge.as_ref().filter(|c| c.is_usable(now)) else {
+                return Ok(false);
+            };
+            *challenge = Some(LoginChallenge::from_persistence(
+                c.id().clone(),
+                c.user_id().clone(),
+                c.token_hash().clone(),
+                *c.expires_at(),
+                c.failed_attempts() + 1,
+            ));
+            Ok(true)
+        }
+
+        async fn consume(&self, _id: &LoginChallengeId) -> Result<bool, RepoError> {
+            Ok(self.challenge.lock().unwrap().take().is_some())
+        }
+    }
+
+    type TestHandler =
+        VerifyLoginHandler<MockUserRepository, MockTotpRepository, MockSessionRepository, MockLoginChallengeRepository, FixedClock>;
+
+    fn now() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap()
+    }
+
+    // returns the handler, the challenge token and the user's recovery codes
+    fn setup() -> (TestHandler, SessionToken, Vec<String>) {
+        let user = User::new(
+            UserId::new(),
+            Username::new("alice".into()).unwrap(),
+            DisplayName::new("Alice".into()).unwrap(),
+        );
+        let mut enrollment = TotpEnrollment::begin(user.id().clone(), now());
+        let code = enrollment.secret().code_at(now().timestamp() / TOTP_STEP_SECONDS - 2);
+        let recovery_codes = enrollment
+            .confirm(&code, now() - chrono::Duration::seconds(2 * TOTP_STEP_SECONDS))
+            .unwrap();
+        let (challenge, token) = LoginChallenge::issue(user.id().clone(), now());
+
+        let handler = VerifyLoginHandler::new(
+            MockUserRepository { user },
+            MockTotpRepository {
+                enrollment: Mutex::new(Some(enrollment)),
+                redeemed: Mutex::new(vec![]),
+            },
+            MockSessionRepository::default(),
+            MockLoginChallengeRepository {
+                challenge: Mutex::new(Some(challenge)),
+            },
+            FixedClock(now()),
+        );
+        (handler, token, recovery_codes)
+    }
+
+    fn current_code(handler: &TestHandler) -> String {
+        let enrollment = handler.totp.enrollment.lock().unwrap();
+        enrollment.as_ref().unwrap().secret().code_at(now().timestamp() / TOTP_STEP_SECONDS)
+    }
+
+    #[tokio::test]
+    async fn handle_issues_session_for_valid_code_and_consumes_challenge() {
+        let (handler, token, _) = setup();
+        let code = current_code(&handler);
+
+        let result = handler
+            .handle(VerifyLoginCommand {
+                challenge_token: token.as_str().to_string(),
+                code,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result.user.username().as_str(), "alice");
+        assert_eq!(handler.sessions.saved.lock().unwrap().as_slice(), &[result.token.hash()]);
+        assert!(handler.challenges.challenge.lock().unwrap().is_none());
+        let enrollment = handler.totp.enrollment.lock().unwrap();
+        assert_eq!(
+            *enrollment.as_ref().unwrap().last_used_step(),
+            Some(now().timestamp() / TOTP_STEP_SECONDS)
+        );
+    }
+
+    #[tokio::test]
+    async fn handle_accepts_recovery_code_once() {
+        let (handler, token, recovery_codes) = setup();
+
+        handler
+            .handle(VerifyLoginCommand {
+                challenge_token: token.as_str().to_string(),
+                code: recovery_codes[0].clone(),
+            })
+            .await
+            .unwrap();
+
+        let enrollment = handler.totp.enrollment.lock().unwrap();
+        assert_eq!(
+            enrollment
+                .as_ref()
+                .unwrap()
+                .recovery_codes()
+                .iter()
+                .filter(|c| c.used_at.is_some())
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_code_spent_by_a_concurrent_login() {
+        let (handler, token, _) = setup();
+        let code = current_code(&handler);
+        // another login checked the same code and spent it after this one read the enrollment
+        handler
+            .totp
+            .redeemed
+            .lock()
+            .unwrap()
+            .push(TwoFactorUse::TotpStep(now().timestamp() / TOTP_STEP_SECONDS));
+
+        let result = handler
+            .handle(VerifyLoginCommand {
+                challenge_token: token.as_str().to_string(),
+                code,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidTwoFactorCode));
+        assert!(handler.sessions.saved.lock().unwrap().is_empty());
+        assert!(handler.challenges.challenge.lock().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn handle_counts_failed_attempts_and_locks_challenge() {
+        let (handler, token, _) = setup();
+
+        for _ in 0..crate::domain::session::LOGIN_CHALLENGE_MAX_ATTEMPTS {
+            let result = handler
+                .handle(VerifyLoginCommand {
+                    challenge_token: token.as_str().to_string(),
+                    code: "wrong-code".into(),
+                })
+                .await;
+            assert_eq!(result.err(), Some(DomainError::InvalidTwoFactorCode));
+        }
+
+        let code = current_code(&handler);
+        let result = handler
+            .handle(VerifyLoginCommand {
+                challenge_token: token.as_str().to_string(),
+                code,
+            })
+            .await;
+
+        assert_eq!(result.err(), Some(DomainError::InvalidLoginChallenge));
+        assert!(handler.sessions.saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_unknown_or_expired_challenge() {
+        let (mut handler, token, _) = setup();
+        let code = current_code(&handler);
+
+        let unknown = handler
+            .handle(VerifyLoginCommand {
+                challenge_token: SessionToken::generate().as_str().to_string(),
+                code: code.clone(),
+            })
+            .await;
+        handler.clock = FixedClock(now() + chrono::Duration::hours(1));
+        let expired = handler
+            .handle(VerifyLoginCommand {
+                challenge_token: token.as_str().to_string(),
+                code,
+            })
+            .await;
+
+        assert_eq!(unknown.err(), Some(DomainError::InvalidLoginChallenge));
+        assert_eq!(expired.err(), Some(DomainError::InvalidLoginChallenge));
+    }
+}
@@ -9,6 +9,7 @@ pub struct ParticipantView {
     pub user_id: String,
     pub username: String,
     pub display_name: String,
+    pub role: String,
     pub joined_at: DateTime<Utc>,
     pub last_read_at: Option<DateTime<Utc>>,
 }
@@ -18,11 +19,51 @@ pub struct ConversationView {
     pub id: String,
     pub conversation_type: String,
     pub name: Option<String>,
+    pub description: Option<String>,
+    pub avatar_url: Option<String>,
     pub participants: Vec<ParticipantView>,
+    // the viewer's own settings for this conversation
+    pub notify_level: String,
+    pub muted_until: Option<DateTime<Utc>>,
+    pub archived: bool,
+    pub pinned: bool,
+    pub message_ttl_seconds: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ConversationFilter {
+    #[default]
+    Active,
+    Archived,
+}
+
+pub struct ConversationListQuery {
+    pub user_id: UserId,
+    pub filter: ConversationFilter,
+}
+
+#[derive(Serialize)]
+pub struct InviteView {
+    pub id: String,
+    pub conversation_id: String,
+    pub token: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+}
+
+#[derive(Serialize)]
+pub struct InvitationView {
+    pub conversation_id: String,
+    pub name: Option<String>,
+    pub invited_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum QueryError {
     #[error(transparent)]
@@ -31,6 +72,8 @@ pub enum QueryError {
 
 #[async_trait]
 pub trait ConversationViewQueries: Send + Sync {
-    async fn for_user(&self, user_id: &UserId) -> Result<Vec<ConversationView>, QueryError>;
-    async fn by_id(&self, id: &ConversationId) -> Result<Option<ConversationView>, QueryError>;
+    async fn for_user(&self, query: ConversationListQuery) -> Result<Vec<ConversationView>, QueryError>;
+    async fn by_id(&self, id: &ConversationId, viewer: &UserId) -> Result<Option<ConversationView>, QueryError>;
+    async fn invites(&self, conversation_id: &ConversationId) -> Result<Vec<InviteView>, QueryError>;
+    async fn invitations(&self, user_id: &UserId) -> Result<Vec<InvitationView>, QueryError>;
 }
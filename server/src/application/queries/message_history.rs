@@ -2,7 +2,50 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 
-use crate::domain::ids::ConversationId;
+use crate::domain::ids::{ConversationId, MessageId, UserId};
+
+#[derive(Serialize)]
+pub struct ReactionCountView {
+    pub emoji: String,
+    pub count: i64,
+    pub user_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ForwardedFromView {
+    pub message_id: String,
+    pub conversation_id: String,
+}
+
+#[derive(Serialize)]
+pub struct PollOptionView {
+    pub text: String,
+    pub votes: i64,
+    pub user_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PollView {
+    pub options: Vec<PollOptionView>,
+    pub multiple_choice: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub closed: bool,
+    // people rather than votes, which differ in multiple-choice polls
+    pub voter_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct AttachmentView {
+    pub filename: String,
+    pub size_bytes: i64,
+    pub mime_type: String,
+}
+
+#[derive(Serialize)]
+pub struct VoiceNoteView {
+    pub duration_ms: i32,
+    pub peaks: Vec<u8>,
+}
 
 #[derive(Serialize)]
 pub struct MessageView {
@@ -11,9 +54,41 @@ pub struct MessageView {
     pub sender_id: String,
     pub content: String,
     pub kind: String,
+    pub thread_root_id: Option<String>,
+    pub reply_count: i64,
+    pub last_reply_at: Option<DateTime<Utc>>,
     pub edited: bool,
+    pub revision_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub forwarded_from: Option<ForwardedFromView>,
+    pub poll: Option<PollView>,
+    pub attachment: Option<AttachmentView>,
+    pub voice_note: Option<VoiceNoteView>,
+    pub mentions: Vec<String>,
+    pub reactions: Vec<ReactionCountView>,
+}
+
+#[derive(Serialize)]
+pub struct MessageRevisionView {
+    pub content: String,
+    pub written_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct ScheduledMessageView {
+    pub id: String,
+    pub conversation_id: String,
+    pub content: String,
+    pub kind: String,
+    pub reply_to: Option<String>,
+    pub send_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub status: String,
+    pub failure: Option<String>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -28,7 +103,25 @@ pub struct MessageHistoryQuery {
     pub limit: Option<i64>,
 }
 
+pub struct ThreadHistoryQuery {
+    pub thread_root_id: MessageId,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+pub struct MentionsQuery {
+    pub user_id: UserId,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
 #[async_trait]
 pub trait MessageHistoryQueries: Send + Sync {
     async fn for_conversation(&self, query: MessageHistoryQuery) -> Result<Vec<MessageView>, QueryError>;
+    async fn for_thread(&self, query: ThreadHistoryQuery) -> Result<Vec<MessageView>, QueryError>;
+    async fn pinned(&self, conversation_id: &ConversationId) -> Result<Vec<MessageView>, QueryError>;
+    async fn revisions(&self, message_id: &MessageId) -> Result<Vec<MessageRevisionView>, QueryError>;
+    async fn mentions(&self, query: MentionsQuery) -> Result<Vec<MessageView>, QueryError>;
+    // the sender's own queue: still waiting or failed to go out
+    async fn scheduled(&self, user_id: &UserId) -> Result<Vec<ScheduledMessageView>, QueryError>;
 }
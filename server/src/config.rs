@@ -2,12 +2,30 @@ use dotenvy::dotenv;
 use secrecy::SecretString;
 use thiserror::Error;
 
+const DEFAULT_FILE_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/zip",
+    "application/gzip",
+    "application/x-7z-compressed",
+    "application/x-tar",
+    "text/plain",
+    "text/csv",
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+];
+
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub database_url: SecretString,
     pub port: u16,
     pub upload_dir: String,
+    pub file_dir: String,
+    // content types POST /upload/file accepts
+    pub allowed_file_types: Vec<String>,
     pub public_url: String,
+    pub totp_encryption_key: SecretString,
 }
 
 impl AppConfig {
@@ -17,13 +35,29 @@ impl AppConfig {
         let database_url = std::env::var("DATABASE_URL").map_err(|e| ConfigError::MissingEnv(e.to_string()))?;
         let port = std::env::var("PORT").ok().and_then(|v| v.parse::<u16>().ok()).unwrap_or(3000);
         let upload_dir = non_empty_env("UPLOAD_DIR").unwrap_or_else(|| "uploads".to_string());
+        let file_dir = non_empty_env("FILE_DIR").unwrap_or_else(|| "files".to_string());
+        let allowed_file_types = non_empty_env("ALLOWED_FILE_TYPES").map_or_else(
+            || DEFAULT_FILE_TYPES.iter().map(|t| t.to_string()).collect(),
+            |types| {
+                types
+                    .split(',')
+                    .map(|t| t.trim().to_ascii_lowercase())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            },
+        );
         let public_url = non_empty_env("PUBLIC_URL").unwrap_or_else(|| format!("http://127.0.0.1:{port}"));
+        let totp_encryption_key =
+            std::env::var("TOTP_ENCRYPTION_KEY").map_err(|e| ConfigError::MissingEnv(format!("TOTP_ENCRYPTION_KEY: {e}")))?;
 
         Ok(AppConfig {
             database_url: database_url.into(),
             port,
             upload_dir,
+            file_dir,
+            allowed_file_types,
             public_url,
+            totp_encryption_key: totp_encryption_key.into(),
         })
     }
 }
@@ -1,7 +1,23 @@
+pub mod attachment;
+pub mod block;
+pub mod clock;
 pub mod conversation;
+pub mod credentials;
 pub mod errors;
 pub mod events;
 pub mod ids;
+pub mod invitation;
+pub mod invite;
+pub mod list_entry;
+pub mod mention;
 pub mod message;
+pub mod notification;
+pub mod pin;
+pub mod poll;
+pub mod reaction;
 pub mod repository;
+pub mod scheduled_message;
+pub mod session;
+pub mod two_factor;
 pub mod user;
+pub mod voice_note;
@@ -0,0 +1,74 @@
+use getset::Getters;
+
+use crate::domain::errors::DomainError;
+
+pub const MAX_FILENAME_LENGTH: usize = 255;
+
+// what a file message's URL points at, as reported by the upload. only ever shown to people, the
+// file itself is always served as a download whatever its type claims to be
+#[derive(Debug, Clone, Getters, PartialEq)]
+pub struct Attachment {
+    #[getset(get = "pub")]
+    filename: String,
+    #[getset(get = "pub")]
+    size_bytes: i64,
+    #[getset(get = "pub")]
+    mime_type: String,
+}
+
+impl Attachment {
+    // clients can send a full path as the name, only the last part of it is kept
+    pub fn new(filename: String, size_bytes: i64, mime_type: String) -> Result<Self, DomainError> {
+        let filename = filename.rsplit(['/', '\\']).next().unwrap_or_default().trim().to_string();
+        if filename.is_empty() || filename.chars().count() > MAX_FILENAME_LENGTH || filename.chars().any(char::is_control) {
+            return Err(DomainError::InvalidAttachment);
+        }
+        if size_bytes <= 0 || !mime_type.contains('/') {
+            return Err(DomainError::InvalidAttachment);
+        }
+        Ok(Self {
+            filename,
+            size_bytes,
+            mime_type,
+        })
+    }
+
+    pub(crate) fn from_persistence(filename: String, size_bytes: i64, mime_type: String) -> Self {
+        Self {
+            filename,
+            size_bytes,
+            mime_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_keeps_only_the_last_path_segment() {
+        let attachment = Attachment::new("C:\\Users\\me\\report.pdf".into(), 1024, "application/pdf".into()).unwrap();
+        assert_eq!(attachment.filename(), "report.pdf");
+
+        let attachment = Attachment::new("../../etc/passwd".into(), 1, "text/plain".into()).unwrap();
+        assert_eq!(attachment.filename(), "passwd");
+    }
+
+    #[test]
+    fn new_rejects_missing_or_bogus_metadata() {
+        for (filename, size, mime) in [
+            ("", 10, "application/pdf"),
+            ("dir/", 10, "application/pdf"),
+            ("bad\nname.pdf", 10, "application/pdf"),
+            ("report.pdf", 0, "application/pdf"),
+            ("report.pdf", 10, "pdf"),
+        ] {
+            assert_eq!(
+                Attachment::new(filename.into(), size, mime.into()).err(),
+                Some(DomainError::InvalidAttachment),
+                "{filename:?} {size} {mime}"
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{errors::DomainError, ids::UserId};
+
+#[derive(Debug, Getters, PartialEq)]
+pub struct Block {
+    #[getset(get = "pub")]
+    blocker_id: UserId,
+    #[getset(get = "pub")]
+    blocked_id: UserId,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+}
+
+impl Block {
+    pub fn new(blocker_id: UserId, blocked_id: UserId) -> Result<Self, DomainError> {
+        if blocker_id == blocked_id {
+            return Err(DomainError::CannotBlockSelf);
+        }
+
+        Ok(Self {
+            blocker_id,
+            blocked_id,
+            created_at: Utc::now(),
+        })
+    }
+
+    pub(crate) fn from_persistence(blocker_id: UserId, blocked_id: UserId, created_at: DateTime<Utc>) -> Self {
+        Self {
+            blocker_id,
+            blocked_id,
+            created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_rejects_blocking_yourself() {
+        let user = UserId::new();
+
+        assert_eq!(Block::new(user.clone(), user).err(), Some(DomainError::CannotBlockSelf));
+    }
+
+    #[test]
+    fn new_records_who_blocked_whom() {
+        let blocker = UserId::new();
+        let blocked = UserId::new();
+
+        let block = Block::new(blocker.clone(), blocked.clone()).unwrap();
+
+        assert_eq!(block.blocker_id(), &blocker);
+        assert_eq!(block.blocked_id(), &blocked);
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use getset::Getters;
 
 use crate::domain::{
@@ -7,6 +7,10 @@ use crate::domain::{
     ids::{ConversationId, UserId},
 };
 
+pub const MAX_DESCRIPTION_LENGTH: usize = 500;
+// disappearing-message timers on offer: an hour, a day and a week
+pub const MESSAGE_TIMERS_SECONDS: [i64; 3] = [60 * 60, 24 * 60 * 60, 7 * 24 * 60 * 60];
+
 #[derive(Debug, PartialEq, Clone, sqlx::Type)]
 #[sqlx(type_name = "conversation_kind", rename_all = "lowercase")]
 pub enum ConversationKind {
@@ -14,12 +18,36 @@ pub enum ConversationKind {
     Group,
 }
 
+#[derive(Debug, PartialEq, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "participant_role", rename_all = "lowercase")]
+pub enum ParticipantRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl ParticipantRole {
+    pub fn is_admin(&self) -> bool {
+        matches!(self, ParticipantRole::Owner | ParticipantRole::Admin)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Participant {
     pub user_id: UserId,
+    pub role: ParticipantRole,
     pub joined_at: DateTime<Utc>,
 }
 
+// what saving a conversation has to write for its participants, so that people another request added
+// or removed since it was loaded are left as they are
+#[derive(Debug, Default, PartialEq)]
+pub struct ParticipantChanges<'a> {
+    pub joined: Vec<&'a Participant>,
+    pub role_changed: Vec<&'a Participant>,
+    pub left: Vec<&'a UserId>,
+}
+
 #[derive(Debug, Getters, PartialEq)]
 pub struct Conversation {
     #[getset(get = "pub")]
@@ -29,9 +57,17 @@ pub struct Conversation {
     #[getset(get = "pub")]
     title: Option<String>,
     #[getset(get = "pub")]
+    description: Option<String>,
+    #[getset(get = "pub")]
+    avatar_url: Option<String>,
+    #[getset(get = "pub")]
     participants: Vec<Participant>,
     #[getset(get = "pub")]
+    message_ttl: Option<Duration>,
+    #[getset(get = "pub")]
     created_at: DateTime<Utc>,
+    // participants and roles as they were loaded; empty for a conversation that was never saved
+    loaded_roles: Vec<(UserId, ParticipantRole)>,
 }
 
 impl Conversation {
@@ -45,17 +81,23 @@ impl Conversation {
             id,
             kind: ConversationKind::Direct,
             title: None,
+            description: None,
+            avatar_url: None,
             participants: vec![
                 Participant {
                     user_id: a,
+                    role: ParticipantRole::Member,
                     joined_at: now,
                 },
                 Participant {
                     user_id: b,
+                    role: ParticipantRole::Member,
                     joined_at: now,
                 },
             ],
+            message_ttl: None,
             created_at: now,
+            loaded_roles: Vec::new(),
         })
     }
 
@@ -69,11 +111,16 @@ impl Conversation {
             id,
             kind: ConversationKind::Group,
             title: Some(title),
+            description: None,
+            avatar_url: None,
             participants: vec![Participant {
                 user_id: creator,
+                role: ParticipantRole::Owner,
                 joined_at: now,
             }],
+            message_ttl: None,
             created_at: now,
+            loaded_roles: Vec::new(),
         })
     }
 
@@ -81,10 +128,31 @@ impl Conversation {
         self.participants.iter().any(|p| &p.user_id == user_id)
     }
 
-    pub fn add_participant(&mut self, user_id: UserId) -> Result<DomainEvent, DomainError> {
+    // the other side of a direct conversation; groups have no single peer
+    pub fn direct_peer(&self, user_id: &UserId) -> Option<&UserId> {
+        if !matches!(self.kind, ConversationKind::Direct) || !self.is_participant(user_id) {
+            return None;
+        }
+        self.participants.iter().map(|p| &p.user_id).find(|id| *id != user_id)
+    }
+
+    pub fn role_of(&self, user_id: &UserId) -> Option<ParticipantRole> {
+        self.participants.iter().find(|p| &p.user_id == user_id).map(|p| p.role)
+    }
+
+    pub fn require_admin(&self, actor: &UserId) -> Result<ParticipantRole, DomainError> {
+        match self.role_of(actor) {
+            None => Err(DomainError::NotAParticipant),
+            Some(role) if !role.is_admin() => Err(DomainError::NotAnAdmin),
+            Some(role) => Ok(role),
+        }
+    }
+
+    pub fn add_participant(&mut self, actor: &UserId, user_id: UserId) -> Result<DomainEvent, DomainError> {
         if matches!(self.kind, ConversationKind::Direct) {
             return Err(DomainError::CannotAddToDirect);
         }
+        self.require_admin(actor)?;
 
         if self.is_participant(&user_id) {
             return Err(DomainError::AlreadyParticipant);
@@ -92,6 +160,7 @@ impl Conversation {
 
         self.participants.push(Participant {
             user_id: user_id.clone(),
+            role: ParticipantRole::Member,
             joined_at: Utc::now(),
         });
         Ok(DomainEvent::ParticipantAdded {
@@ -100,45 +169,227 @@ impl Conversation {
         })
     }
 
-    pub fn remove_participant(&mut self, user_id: &UserId) -> Result<DomainEvent, DomainError> {
+    // anyone may remove themselves; removing someone else is a kick and needs a role above theirs.
+    // when the owner leaves, ownership passes on so the group is never left without one
+    pub fn remove_participant(&mut self, actor: &UserId, user_id: &UserId) -> Result<Vec<DomainEvent>, DomainError> {
         if matches!(self.kind, ConversationKind::Direct) {
             return Err(DomainError::CannotLeaveDirect);
         }
 
-        if !self.is_participant(user_id) {
+        let Some(target_role) = self.role_of(user_id) else {
             return Err(DomainError::NotAParticipant);
+        };
+        if actor != user_id {
+            let actor_role = self.require_admin(actor)?;
+            if !outranks(actor_role, target_role) {
+                return Err(DomainError::NotAnAdmin);
+            }
         }
 
         self.participants.retain(|p| &p.user_id != user_id);
-        Ok(DomainEvent::ParticipantRemoved {
+        let removed = if actor == user_id {
+            DomainEvent::ParticipantRemoved {
+                conversation_id: self.id.clone(),
+                user_id: user_id.clone(),
+            }
+        } else {
+            DomainEvent::ParticipantKicked {
+                conversation_id: self.id.clone(),
+                user_id: user_id.clone(),
+                kicked_by: actor.clone(),
+            }
+        };
+        let mut events = vec![removed];
+
+        if target_role == ParticipantRole::Owner
+            && let Some(successor) = successor(&mut self.participants)
+        {
+            successor.role = ParticipantRole::Owner;
+            events.push(DomainEvent::ParticipantRoleChanged {
+                conversation_id: self.id.clone(),
+                user_id: successor.user_id.clone(),
+                role: ParticipantRole::Owner,
+            });
+        }
+
+        Ok(events)
+    }
+
+    pub fn rename(&mut self, actor: &UserId, title: String) -> Result<DomainEvent, DomainError> {
+        if matches!(self.kind, ConversationKind::Direct) {
+            return Err(DomainError::NotAGroup);
+        }
+        self.require_admin(actor)?;
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return Err(DomainError::GroupNeedsTitle);
+        }
+
+        self.title = Some(title.clone());
+        Ok(DomainEvent::ConversationRenamed {
+            conversation_id: self.id.clone(),
+            title,
+            renamed_by: actor.clone(),
+        })
+    }
+
+    // replaces both fields at once; blank values clear them
+    pub fn update_details(
+        &mut self,
+        actor: &UserId,
+        description: Option<String>,
+        avatar_url: Option<String>,
+    ) -> Result<DomainEvent, DomainError> {
+        if matches!(self.kind, ConversationKind::Direct) {
+            return Err(DomainError::NotAGroup);
+        }
+        self.require_admin(actor)?;
+
+        let description = non_blank(description);
+        let avatar_url = non_blank(avatar_url);
+        if description.as_ref().is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LENGTH) {
+            return Err(DomainError::DescriptionTooLong);
+        }
+        if avatar_url
+            .as_ref()
+            .is_some_and(|url| !url.starts_with("http://") && !url.starts_with("https://"))
+        {
+            return Err(DomainError::AvatarNeedsUrl);
+        }
+
+        self.description = description.clone();
+        self.avatar_url = avatar_url.clone();
+        Ok(DomainEvent::ConversationDetailsChanged {
+            conversation_id: self.id.clone(),
+            description,
+            avatar_url,
+            changed_by: actor.clone(),
+        })
+    }
+
+    // shared settings of the conversation as a whole: either side of a direct chat may change them,
+    // only admins in a group
+    pub fn require_settings_rights(&self, actor: &UserId) -> Result<(), DomainError> {
+        match self.kind {
+            ConversationKind::Direct if self.is_participant(actor) => Ok(()),
+            ConversationKind::Direct => Err(DomainError::NotAParticipant),
+            ConversationKind::Group => self.require_admin(actor).map(|_| ()),
+        }
+    }
+
+    // only the owner hands out or takes away admin; ownership itself is never granted this way
+    pub fn change_role(&mut self, actor: &UserId, user_id: &UserId, role: ParticipantRole) -> Result<DomainEvent, DomainError> {
+        if matches!(self.kind, ConversationKind::Direct) {
+            return Err(DomainError::NotAGroup);
+        }
+        match self.role_of(actor) {
+            None => return Err(DomainError::NotAParticipant),
+            Some(ParticipantRole::Owner) => {}
+            Some(_) => return Err(DomainError::NotAnAdmin),
+        }
+        if role == ParticipantRole::Owner {
+            return Err(DomainError::CannotChangeOwnerRole);
+        }
+
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|p| &p.user_id == user_id)
+            .ok_or(DomainError::NotAParticipant)?;
+        if participant.role == ParticipantRole::Owner {
+            return Err(DomainError::CannotChangeOwnerRole);
+        }
+
+        participant.role = role;
+        Ok(DomainEvent::ParticipantRoleChanged {
             conversation_id: self.id.clone(),
             user_id: user_id.clone(),
+            role,
         })
     }
 
+    // None turns the timer off
+    pub fn set_message_timer(&mut self, actor: &UserId, ttl: Option<Duration>) -> Result<DomainEvent, DomainError> {
+        self.require_settings_rights(actor)?;
+        if ttl.is_some_and(|ttl| !MESSAGE_TIMERS_SECONDS.contains(&ttl.num_seconds())) {
+            return Err(DomainError::InvalidMessageTimer);
+        }
+
+        self.message_ttl = ttl;
+        Ok(DomainEvent::MessageTimerChanged {
+            conversation_id: self.id.clone(),
+            ttl_seconds: ttl.map(|ttl| ttl.num_seconds()),
+            changed_by: actor.clone(),
+        })
+    }
+
+    pub(crate) fn participant_changes(&self) -> ParticipantChanges<'_> {
+        let mut changes = ParticipantChanges::default();
+        for participant in &self.participants {
+            match self.loaded_roles.iter().find(|(user_id, _)| user_id == &participant.user_id) {
+                None => changes.joined.push(participant),
+                Some((_, role)) if *role != participant.role => changes.role_changed.push(participant),
+                Some(_) => {}
+            }
+        }
+        changes.left = self
+            .loaded_roles
+            .iter()
+            .map(|(user_id, _)| user_id)
+            .filter(|user_id| !self.is_participant(user_id))
+            .collect();
+        changes
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_persistence(
         id: ConversationId,
         kind: ConversationKind,
         title: Option<String>,
+        description: Option<String>,
+        avatar_url: Option<String>,
         participants: Vec<Participant>,
+        message_ttl: Option<Duration>,
         created_at: DateTime<Utc>,
     ) -> Self {
+        let loaded_roles = participants.iter().map(|p| (p.user_id.clone(), p.role)).collect();
         Self {
             id,
             kind,
             title,
+            description,
+            avatar_url,
             participants,
+            message_ttl,
             created_at,
+            loaded_roles,
         }
     }
 }
 
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+// longest-serving admin, falling back to the longest-serving member
+fn successor(participants: &mut [Participant]) -> Option<&mut Participant> {
+    participants.iter_mut().min_by_key(|p| (!p.role.is_admin(), p.joined_at))
+}
+
+fn outranks(actor: ParticipantRole, target: ParticipantRole) -> bool {
+    match actor {
+        ParticipantRole::Owner => target != ParticipantRole::Owner,
+        ParticipantRole::Admin => target == ParticipantRole::Member,
+        ParticipantRole::Member => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use chrono::Utc;
+    use chrono::{Duration, Utc};
 
     use crate::domain::{
-        conversation::{Conversation, ConversationKind, Participant},
+        conversation::{Conversation, ConversationKind, MAX_DESCRIPTION_LENGTH, Participant, ParticipantChanges, ParticipantRole},
         errors::DomainError,
         events::DomainEvent,
         ids::{ConversationId, UserId},
@@ -155,6 +406,19 @@ mod tests {
         assert_eq!(result, Err(DomainError::DirectWithSelf));
     }
 
+    #[test]
+    fn direct_peer_is_the_other_participant_of_a_direct_conversation() {
+        let a = UserId::new();
+        let b = UserId::new();
+        let direct = Conversation::new_direct(ConversationId::new(), a.clone(), b.clone()).unwrap();
+        let group = Conversation::new_group(ConversationId::new(), "Group".into(), a.clone()).unwrap();
+
+        assert_eq!(direct.direct_peer(&a), Some(&b));
+        assert_eq!(direct.direct_peer(&b), Some(&a));
+        assert_eq!(direct.direct_peer(&UserId::new()), None);
+        assert_eq!(group.direct_peer(&a), None);
+    }
+
     #[test]
     fn new_direct_builds_conversation_with_both_participants() {
         let id = ConversationId::new();
@@ -197,6 +461,7 @@ mod tests {
         assert_eq!(convo.title(), &Some("The Group Chat".to_string()));
         assert_eq!(convo.participants.len(), 1);
         assert_eq!(convo.participants[0].user_id, creator);
+        assert_eq!(convo.role_of(&creator), Some(ParticipantRole::Owner));
     }
 
     #[test]
@@ -214,9 +479,9 @@ mod tests {
         let creator = UserId::new();
         let new_member = UserId::new();
         let conversation_id = ConversationId::new();
-        let mut convo = Conversation::new_group(conversation_id.clone(), "The Group Chat".into(), creator).unwrap();
+        let mut convo = Conversation::new_group(conversation_id.clone(), "The Group Chat".into(), creator.clone()).unwrap();
 
-        let event = convo.add_participant(new_member.clone()).unwrap();
+        let event = convo.add_participant(&creator, new_member.clone()).unwrap();
 
         assert!(convo.is_participant(&new_member));
         assert_eq!(convo.participants.len(), 2);
@@ -234,9 +499,10 @@ mod tests {
 
     #[test]
     fn add_participant_rejects_direct_conversation() {
-        let mut convo = Conversation::new_direct(ConversationId::new(), UserId::new(), UserId::new()).unwrap();
+        let a = UserId::new();
+        let mut convo = Conversation::new_direct(ConversationId::new(), a.clone(), UserId::new()).unwrap();
 
-        let result = convo.add_participant(UserId::new());
+        let result = convo.add_participant(&a, UserId::new());
 
         assert_eq!(result.err(), Some(DomainError::CannotAddToDirect));
     }
@@ -246,7 +512,7 @@ mod tests {
         let creator = UserId::new();
         let mut convo = Conversation::new_group(ConversationId::new(), "The Group Chat".into(), creator.clone()).unwrap();
 
-        let result = convo.add_participant(creator);
+        let result = convo.add_participant(&creator, creator.clone());
 
         assert_eq!(result.err(), Some(DomainError::AlreadyParticipant));
     }
@@ -256,19 +522,20 @@ mod tests {
         let creator = UserId::new();
         let member = UserId::new();
         let conversation_id = ConversationId::new();
-        let mut convo = Conversation::new_group(conversation_id.clone(), "The Group Chat".into(), creator).unwrap();
-        convo.add_participant(member.clone()).unwrap();
+        let mut convo = Conversation::new_group(conversation_id.clone(), "The Group Chat".into(), creator.clone()).unwrap();
+        convo.add_participant(&creator, member.clone()).unwrap();
 
-        let event = convo.remove_participant(&member).unwrap();
+        let events = convo.remove_participant(&member, &member).unwrap();
 
         assert!(!convo.is_participant(&member));
-        match event {
+        assert_eq!(events.len(), 1);
+        match &events[0] {
             DomainEvent::ParticipantRemoved {
                 conversation_id: event_conversation_id,
                 user_id,
             } => {
-                assert_eq!(event_conversation_id, conversation_id);
-                assert_eq!(user_id, member);
+                assert_eq!(event_conversation_id, &conversation_id);
+                assert_eq!(user_id, &member);
             }
             _ => panic!("expected ParticipantRemoved event"),
         }
@@ -279,7 +546,7 @@ mod tests {
         let a = UserId::new();
         let mut convo = Conversation::new_direct(ConversationId::new(), a.clone(), UserId::new()).unwrap();
 
-        let result = convo.remove_participant(&a);
+        let result = convo.remove_participant(&a, &a);
 
         assert_eq!(result.err(), Some(DomainError::CannotLeaveDirect));
     }
@@ -288,12 +555,264 @@ mod tests {
     fn remove_participant_rejects_non_participant() {
         let creator = UserId::new();
         let mut convo = Conversation::new_group(ConversationId::new(), "The Group Chat".into(), creator).unwrap();
+        let stranger = UserId::new();
 
-        let result = convo.remove_participant(&UserId::new());
+        let result = convo.remove_participant(&stranger, &stranger);
 
         assert_eq!(result.err(), Some(DomainError::NotAParticipant));
     }
 
+    // owner, then an admin, then a plain member, each joining after the last
+    fn group_with_roles() -> (Conversation, UserId, UserId, UserId) {
+        let owner = UserId::new();
+        let admin = UserId::new();
+        let member = UserId::new();
+        let mut convo = Conversation::new_group(ConversationId::new(), "The Group Chat".into(), owner.clone()).unwrap();
+        convo.add_participant(&owner, admin.clone()).unwrap();
+        convo.add_participant(&owner, member.clone()).unwrap();
+        convo.change_role(&owner, &admin, ParticipantRole::Admin).unwrap();
+        for (offset, participant) in convo.participants.iter_mut().enumerate() {
+            participant.joined_at = Utc::now() + chrono::Duration::seconds(offset as i64);
+        }
+        (convo, owner, admin, member)
+    }
+
+    #[test]
+    fn add_participant_requires_admin() {
+        let (mut convo, _owner, admin, member) = group_with_roles();
+
+        assert_eq!(convo.add_participant(&member, UserId::new()).err(), Some(DomainError::NotAnAdmin));
+        assert_eq!(
+            convo.add_participant(&UserId::new(), UserId::new()).err(),
+            Some(DomainError::NotAParticipant)
+        );
+        assert!(convo.add_participant(&admin, UserId::new()).is_ok());
+    }
+
+    #[test]
+    fn added_participants_start_as_members() {
+        let (convo, _owner, _admin, member) = group_with_roles();
+
+        assert_eq!(convo.role_of(&member), Some(ParticipantRole::Member));
+    }
+
+    #[test]
+    fn remove_participant_requires_outranking_the_target() {
+        let (mut convo, owner, admin, member) = group_with_roles();
+
+        assert_eq!(convo.remove_participant(&member, &admin).err(), Some(DomainError::NotAnAdmin));
+        assert_eq!(convo.remove_participant(&admin, &owner).err(), Some(DomainError::NotAnAdmin));
+        assert!(convo.remove_participant(&admin, &member).is_ok());
+        assert!(convo.remove_participant(&owner, &admin).is_ok());
+    }
+
+    #[test]
+    fn removing_someone_else_emits_kick_with_actor() {
+        let (mut convo, _owner, admin, member) = group_with_roles();
+
+        let events = convo.remove_participant(&admin, &member).unwrap();
+
+        assert!(!convo.is_participant(&member));
+        match events.as_slice() {
+            [DomainEvent::ParticipantKicked { user_id, kicked_by, .. }] => {
+                assert_eq!(user_id, &member);
+                assert_eq!(kicked_by, &admin);
+            }
+            _ => panic!("expected a single ParticipantKicked event"),
+        }
+    }
+
+    #[test]
+    fn owner_leaving_promotes_longest_serving_admin() {
+        let (mut convo, owner, admin, _member) = group_with_roles();
+
+        let events = convo.remove_participant(&owner, &owner).unwrap();
+
+        assert_eq!(convo.role_of(&admin), Some(ParticipantRole::Owner));
+        match events.as_slice() {
+            [
+                DomainEvent::ParticipantRemoved { .. },
+                DomainEvent::ParticipantRoleChanged { user_id, role, .. },
+            ] => {
+                assert_eq!(user_id, &admin);
+                assert_eq!(role, &ParticipantRole::Owner);
+            }
+            _ => panic!("expected ParticipantRemoved then ParticipantRoleChanged"),
+        }
+    }
+
+    #[test]
+    fn owner_leaving_without_admins_promotes_longest_serving_member() {
+        let (mut convo, owner, admin, member) = group_with_roles();
+        convo.remove_participant(&owner, &admin).unwrap();
+
+        convo.remove_participant(&owner, &owner).unwrap();
+
+        assert_eq!(convo.role_of(&member), Some(ParticipantRole::Owner));
+    }
+
+    #[test]
+    fn change_role_is_owner_only() {
+        let (mut convo, owner, admin, member) = group_with_roles();
+
+        assert_eq!(
+            convo.change_role(&admin, &member, ParticipantRole::Admin).err(),
+            Some(DomainError::NotAnAdmin)
+        );
+
+        let event = convo.change_role(&owner, &admin, ParticipantRole::Member).unwrap();
+
+        assert_eq!(convo.role_of(&admin), Some(ParticipantRole::Member));
+        assert!(matches!(
+            event,
+            DomainEvent::ParticipantRoleChanged {
+                role: ParticipantRole::Member,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn change_role_never_grants_or_removes_ownership() {
+        let (mut convo, owner, admin, _member) = group_with_roles();
+
+        assert_eq!(
+            convo.change_role(&owner, &admin, ParticipantRole::Owner).err(),
+            Some(DomainError::CannotChangeOwnerRole)
+        );
+        assert_eq!(
+            convo.change_role(&owner, &owner, ParticipantRole::Member).err(),
+            Some(DomainError::CannotChangeOwnerRole)
+        );
+    }
+
+    #[test]
+    fn change_role_rejects_direct_conversation() {
+        let a = UserId::new();
+        let b = UserId::new();
+        let mut convo = Conversation::new_direct(ConversationId::new(), a.clone(), b.clone()).unwrap();
+
+        assert_eq!(
+            convo.change_role(&a, &b, ParticipantRole::Admin).err(),
+            Some(DomainError::NotAGroup)
+        );
+    }
+
+    #[test]
+    fn require_settings_rights_allows_direct_participants_and_group_admins() {
+        let a = UserId::new();
+        let b = UserId::new();
+        let direct = Conversation::new_direct(ConversationId::new(), a.clone(), b.clone()).unwrap();
+        assert_eq!(direct.require_settings_rights(&b), Ok(()));
+        assert_eq!(direct.require_settings_rights(&UserId::new()), Err(DomainError::NotAParticipant));
+
+        let mut group = Conversation::new_group(ConversationId::new(), "Group".into(), a.clone()).unwrap();
+        group.add_participant(&a, b.clone()).unwrap();
+        assert_eq!(group.require_settings_rights(&a), Ok(()));
+        assert_eq!(group.require_settings_rights(&b), Err(DomainError::NotAnAdmin));
+    }
+
+    #[test]
+    fn rename_is_admin_only_and_emits_event() {
+        let (mut convo, _owner, admin, member) = group_with_roles();
+
+        assert_eq!(convo.rename(&member, "Renamed".into()).err(), Some(DomainError::NotAnAdmin));
+        assert_eq!(convo.rename(&admin, "   ".into()).err(), Some(DomainError::GroupNeedsTitle));
+
+        let event = convo.rename(&admin, " Renamed ".into()).unwrap();
+
+        assert_eq!(convo.title().as_deref(), Some("Renamed"));
+        match event {
+            DomainEvent::ConversationRenamed { title, renamed_by, .. } => {
+                assert_eq!(title, "Renamed");
+                assert_eq!(renamed_by, admin);
+            }
+            _ => panic!("expected ConversationRenamed event"),
+        }
+    }
+
+    #[test]
+    fn rename_and_update_details_reject_direct_conversation() {
+        let a = UserId::new();
+        let b = UserId::new();
+        let mut convo = Conversation::new_direct(ConversationId::new(), a.clone(), b).unwrap();
+
+        assert_eq!(convo.rename(&a, "Us".into()).err(), Some(DomainError::NotAGroup));
+        assert_eq!(
+            convo.update_details(&a, Some("hi".into()), None).err(),
+            Some(DomainError::NotAGroup)
+        );
+    }
+
+    #[test]
+    fn update_details_validates_and_clears_blank_values() {
+        let (mut convo, owner, _admin, member) = group_with_roles();
+
+        assert_eq!(convo.update_details(&member, None, None).err(), Some(DomainError::NotAnAdmin));
+        assert_eq!(
+            convo
+                .update_details(&owner, Some("x".repeat(MAX_DESCRIPTION_LENGTH + 1)), None)
+                .err(),
+            Some(DomainError::DescriptionTooLong)
+        );
+        assert_eq!(
+            convo.update_details(&owner, None, Some("avatar.png".into())).err(),
+            Some(DomainError::AvatarNeedsUrl)
+        );
+
+        convo
+            .update_details(&owner, Some("Weekend plans".into()), Some("https://example.com/a.png".into()))
+            .unwrap();
+        assert_eq!(convo.description().as_deref(), Some("Weekend plans"));
+        assert_eq!(convo.avatar_url().as_deref(), Some("https://example.com/a.png"));
+
+        convo.update_details(&owner, Some("  ".into()), None).unwrap();
+        assert_eq!(convo.description(), &None);
+        assert_eq!(convo.avatar_url(), &None);
+    }
+
+    #[test]
+    fn set_message_timer_needs_settings_rights_and_an_offered_timer() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let mut convo = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        convo.add_participant(&owner, member.clone()).unwrap();
+
+        assert_eq!(
+            convo.set_message_timer(&member, Some(Duration::hours(1))).err(),
+            Some(DomainError::NotAnAdmin)
+        );
+        assert_eq!(
+            convo.set_message_timer(&owner, Some(Duration::minutes(5))).err(),
+            Some(DomainError::InvalidMessageTimer)
+        );
+
+        let event = convo.set_message_timer(&owner, Some(Duration::weeks(1))).unwrap();
+        assert_eq!(convo.message_ttl(), &Some(Duration::weeks(1)));
+        assert!(matches!(
+            event,
+            DomainEvent::MessageTimerChanged {
+                ttl_seconds: Some(604800),
+                ..
+            }
+        ));
+
+        convo.set_message_timer(&owner, None).unwrap();
+        assert_eq!(convo.message_ttl(), &None);
+    }
+
+    #[test]
+    fn either_side_of_a_direct_chat_can_set_the_message_timer() {
+        let a = UserId::new();
+        let mut convo = Conversation::new_direct(ConversationId::new(), a.clone(), UserId::new()).unwrap();
+
+        assert!(convo.set_message_timer(&a, Some(Duration::hours(1))).is_ok());
+        assert_eq!(
+            convo.set_message_timer(&UserId::new(), None).err(),
+            Some(DomainError::NotAParticipant)
+        );
+    }
+
     #[test]
     fn from_persistence_reconstructs_conversation_fields() {
         let id = ConversationId::new();
@@ -301,15 +820,64 @@ mod tests {
         let created_at = Utc::now();
         let participants = vec![Participant {
             user_id: UserId::new(),
+            role: ParticipantRole::Owner,
             joined_at: created_at,
         }];
 
-        let convo = Conversation::from_persistence(id.clone(), ConversationKind::Group, title.clone(), participants, created_at);
+        let convo = Conversation::from_persistence(
+            id.clone(),
+            ConversationKind::Group,
+            title.clone(),
+            Some("About us".to_string()),
+            None,
+            participants,
+            Some(Duration::days(1)),
+            created_at,
+        );
 
         assert_eq!(convo.id(), &id);
         assert_eq!(convo.kind(), &ConversationKind::Group);
         assert_eq!(convo.title(), &title);
+        assert_eq!(convo.description().as_deref(), Some("About us"));
+        assert_eq!(convo.avatar_url(), &None);
+        assert_eq!(convo.message_ttl(), &Some(Duration::days(1)));
         assert_eq!(convo.created_at(), &created_at);
         assert_eq!(convo.participants.len(), 1);
     }
+
+    #[test]
+    fn participant_changes_only_cover_what_happened_since_loading() {
+        let (convo, owner, admin, member) = group_with_roles();
+        let participants = convo
+            .participants
+            .iter()
+            .map(|p| Participant {
+                user_id: p.user_id.clone(),
+                role: p.role,
+                joined_at: p.joined_at,
+            })
+            .collect();
+        let mut loaded = Conversation::from_persistence(
+            convo.id.clone(),
+            ConversationKind::Group,
+            convo.title.clone(),
+            None,
+            None,
+            participants,
+            None,
+            convo.created_at,
+        );
+        assert_eq!(loaded.participant_changes(), ParticipantChanges::default());
+        assert_eq!(convo.participant_changes().joined.len(), 3);
+
+        let joiner = UserId::new();
+        loaded.add_participant(&owner, joiner.clone()).unwrap();
+        loaded.remove_participant(&owner, &member).unwrap();
+        loaded.change_role(&owner, &admin, ParticipantRole::Member).unwrap();
+        let changes = loaded.participant_changes();
+
+        assert_eq!(changes.joined.iter().map(|p| &p.user_id).collect::<Vec<_>>(), vec![&joiner]);
+        assert_eq!(changes.role_changed.iter().map(|p| &p.user_id).collect::<Vec<_>>(), vec![&admin]);
+        assert_eq!(changes.left, vec![&member]);
+    }
 }
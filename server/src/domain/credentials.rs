@@ -0,0 +1,175 @@
+use std::sync::LazyLock;
+
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::SaltString};
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{errors::DomainError, ids::UserId};
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Password(String);
+
+impl Password {
+    pub fn new(value: String) -> Result<Self, DomainError> {
+        if value.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(DomainError::PasswordTooShort);
+        }
+        Ok(Self(value))
+    }
+}
+
+// never let a plaintext password end up in logs
+impl std::fmt::Debug for Password {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Password(..)")
+    }
+}
+
+#[derive(Debug, Getters, PartialEq)]
+pub struct Credentials {
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    password_hash: String,
+    #[getset(get = "pub")]
+    updated_at: DateTime<Utc>,
+}
+
+impl Credentials {
+    pub fn new(user_id: UserId, password: &Password) -> Result<Self, DomainError> {
+        Ok(Self {
+            user_id,
+            password_hash: hash_password(password)?,
+            updated_at: Utc::now(),
+        })
+    }
+
+    pub fn verify(&self, candidate: &str) -> bool {
+        verify_hash(&self.password_hash, candidate)
+    }
+
+    pub fn change(&mut self, current: &str, new_password: &Password) -> Result<(), DomainError> {
+        if !self.verify(current) {
+            return Err(DomainError::InvalidCredentials);
+        }
+        self.password_hash = hash_password(new_password)?;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub(crate) fn from_persistence(user_id: UserId, password_hash: String, updated_at: DateTime<Utc>) -> Self {
+        Self {
+            user_id,
+            password_hash,
+            updated_at,
+        }
+    }
+}
+
+// the hash of a random password nobody ever sees
+static DECOY_HASH: LazyLock<String> = LazyLock::new(|| {
+    let secret: [u8; 32] = rand::random();
+    let secret = Password(secret.iter().map(|b| format!("{b:02x}")).collect());
+    hash_password(&secret).expect("argon2 with default parameters hashes any password")
+});
+
+// stands in for verify when there is no account to check against, so turning away a username
+// that doesn't exist costs as much argon2 work as a wrong password and the timing gives nothing away
+pub fn verify_decoy(candidate: &str) -> bool {
+    verify_hash(&DECOY_HASH, candidate)
+}
+
+// argon2 is deliberately slow: async callers run these on the blocking pool
+fn verify_hash(hash: &str, candidate: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return false;
+    };
+    Argon2::default().verify_password(candidate.as_bytes(), &parsed).is_ok()
+}
+
+fn hash_password(password: &Password) -> Result<String, DomainError> {
+    let salt_bytes: [u8; 16] = rand::random();
+    let salt = SaltString::encode_b64(&salt_bytes).map_err(|e| DomainError::Internal(e.to_string()))?;
+    Argon2::default()
+        .hash_password(password.0.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| DomainError::Internal(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password(value: &str) -> Password {
+        Password::new(value.into()).unwrap()
+    }
+
+    #[test]
+    fn password_rejects_short_value() {
+        let result = Password::new("short".into());
+
+        assert_eq!(result.err(), Some(DomainError::PasswordTooShort));
+    }
+
+    #[test]
+    fn password_debug_does_not_leak_plaintext() {
+        assert_eq!(format!("{:?}", password("hunter2hunter2")), "Password(..)");
+    }
+
+    #[test]
+    fn new_stores_argon2_hash_not_plaintext() {
+        let credentials = Credentials::new(UserId::new(), &password("correct horse")).unwrap();
+
+        assert!(credentials.password_hash().starts_with("$argon2id$"));
+        assert!(!credentials.password_hash().contains("correct horse"));
+    }
+
+    #[test]
+    fn verify_accepts_only_the_original_password() {
+        let credentials = Credentials::new(UserId::new(), &password("correct horse")).unwrap();
+
+        assert!(credentials.verify("correct horse"));
+        assert!(!credentials.verify("battery staple"));
+    }
+
+    #[test]
+    fn verify_decoy_turns_every_password_away() {
+        assert!(!verify_decoy("correct horse"));
+        assert!(!verify_decoy(""));
+    }
+
+    #[test]
+    fn change_rejects_wrong_current_password() {
+        let mut credentials = Credentials::new(UserId::new(), &password("correct horse")).unwrap();
+
+        let result = credentials.change("battery staple", &password("new password"));
+
+        assert_eq!(result, Err(DomainError::InvalidCredentials));
+        assert!(credentials.verify("correct horse"));
+    }
+
+    #[test]
+    fn change_replaces_hash_when_current_password_matches() {
+        let mut credentials = Credentials::new(UserId::new(), &password("correct horse")).unwrap();
+
+        credentials.change("correct horse", &password("new password")).unwrap();
+
+        assert!(credentials.verify("new password"));
+        assert!(!credentials.verify("correct horse"));
+    }
+
+    #[test]
+    fn from_persistence_reconstructs_credential_fields() {
+        let user_id = UserId::new();
+        let updated_at = Utc::now();
+
+        let credentials = Credentials::from_persistence(user_id.clone(), "$argon2id$stub".into(), updated_at);
+
+        assert_eq!(credentials.user_id(), &user_id);
+        assert_eq!(credentials.password_hash(), "$argon2id$stub");
+        assert_eq!(credentials.updated_at(), &updated_at);
+        assert!(!credentials.verify("anything"));
+    }
+}
@@ -1,5 +1,13 @@
 use thiserror::Error;
 
+use crate::domain::{
+    attachment::MAX_FILENAME_LENGTH,
+    conversation::MAX_DESCRIPTION_LENGTH,
+    credentials::MIN_PASSWORD_LENGTH,
+    poll::{MAX_POLL_OPTION_LENGTH, MAX_POLL_OPTIONS, MIN_POLL_OPTIONS},
+    voice_note::{MAX_VOICE_NOTE_MINUTES, WAVEFORM_PEAKS},
+};
+
 #[derive(Debug, Error, PartialEq)]
 pub enum DomainError {
     #[error("direct conversation cannot have same user on both sides")]
@@ -16,18 +24,108 @@ pub enum DomainError {
     EmptyMessage,
     #[error("image message content must be a URL")]
     ImageNeedsUrl,
-    #[error("only the sender can edit this message")]
+    #[error("only the sender can change this message")]
     NotYourMessage,
     #[error("conversation not found")]
     ConversationNotFound,
     #[error("message not found")]
     MessageNotFound,
+    #[error("message has been deleted")]
+    MessageDeleted,
+    #[error("message changed while you were editing it, reload and try again")]
+    MessageEditConflict,
+    #[error("file message content must be the URL of a file uploaded to /upload/file")]
+    FileNeedsUrl,
+    #[error("a file message needs its attachment details, and only file messages can have them")]
+    FileNeedsAttachment,
+    #[error("attachments need a filename of at most {MAX_FILENAME_LENGTH} characters, a size and a content type")]
+    InvalidAttachment,
+    #[error("file messages can't be edited, send the new file instead")]
+    FileNotEditable,
+    #[error("voice note content must be the URL of a recording uploaded to /upload/audio")]
+    AudioNeedsUrl,
+    #[error("a voice note needs its duration and waveform, and only voice notes can have them")]
+    VoiceNoteNeedsMetadata,
+    #[error("voice notes need a duration of at most {MAX_VOICE_NOTE_MINUTES} minutes and {WAVEFORM_PEAKS} waveform peaks")]
+    InvalidVoiceNote,
+    #[error("voice notes can't be edited, record a new one instead")]
+    VoiceNoteNotEditable,
+    #[error("a poll needs its options, and only poll messages can have them")]
+    PollNeedsOptions,
+    #[error("polls need {MIN_POLL_OPTIONS} to {MAX_POLL_OPTIONS} distinct options of at most {MAX_POLL_OPTION_LENGTH} characters")]
+    InvalidPollOptions,
+    #[error("a poll can only close in the future")]
+    PollClosesInPast,
+    #[error("polls can't be posted in a thread")]
+    PollInThread,
+    #[error("message is not a poll")]
+    NotAPoll,
+    #[error("poll is closed")]
+    PollClosed,
+    #[error("choose one valid option, or several if the poll allows it")]
+    InvalidPollChoice,
+    #[error("replies must stay in the same conversation as their thread")]
+    ThreadParentElsewhere,
+    #[error("pick at least one conversation to forward to")]
+    NoForwardTargets,
+    #[error("message does not belong to this conversation")]
+    MessageNotInConversation,
     #[error("user is not a participant of this conversation")]
     NotAParticipant,
+    #[error("only group admins can do that")]
+    NotAnAdmin,
+    #[error("the owner role cannot be granted or taken away")]
+    CannotChangeOwnerRole,
+    #[error("only group conversations support this")]
+    NotAGroup,
+    #[error("invite limits must allow at least one use and expire in the future")]
+    InvalidInviteLimits,
+    #[error("invite not found")]
+    InviteNotFound,
+    #[error("invite link has expired, been revoked or used up")]
+    InviteExpired,
+    #[error("invitation not found")]
+    InvitationNotFound,
+    #[error("scheduled message not found")]
+    ScheduledMessageNotFound,
+    #[error("a message can only be scheduled for the future")]
+    ScheduleInPast,
+    #[error("a mute must end in the future")]
+    MuteInPast,
+    #[error("group description must be at most {MAX_DESCRIPTION_LENGTH} characters")]
+    DescriptionTooLong,
+    #[error("message timer must be one hour, one day or one week")]
+    InvalidMessageTimer,
+    #[error("group avatar must be a URL")]
+    AvatarNeedsUrl,
+    #[error("you cannot block yourself")]
+    CannotBlockSelf,
+    #[error("messages between you and this user are blocked")]
+    Blocked,
     #[error("username cannot be empty")]
     EmptyUsername,
     #[error("display name cannot be empty")]
     EmptyDisplayName,
+    #[error("user not found")]
+    UserNotFound,
+    #[error("invalid or expired session")]
+    InvalidSession,
+    #[error("username is already taken")]
+    UsernameTaken,
+    #[error("password must be at least {MIN_PASSWORD_LENGTH} characters")]
+    PasswordTooShort,
+    #[error("invalid username or password")]
+    InvalidCredentials,
+    #[error("two-factor authentication is already enabled")]
+    TwoFactorAlreadyEnabled,
+    #[error("two-factor authentication is not enabled")]
+    TwoFactorNotEnabled,
+    #[error("invalid two-factor code")]
+    InvalidTwoFactorCode,
+    #[error("login challenge is invalid or has expired")]
+    InvalidLoginChallenge,
+    #[error("reaction must be a single emoji")]
+    InvalidEmoji,
     #[error("internal error: {0}")]
     Internal(String),
 }
@@ -1,7 +1,12 @@
 use chrono::{DateTime, Utc};
 
+use crate::domain::attachment::Attachment;
+use crate::domain::conversation::ParticipantRole;
 use crate::domain::ids::{ConversationId, MessageId, UserId};
-use crate::domain::message::MessageKind;
+use crate::domain::message::{ForwardedFrom, MessageKind};
+use crate::domain::notification::NotifyLevel;
+use crate::domain::poll::Poll;
+use crate::domain::voice_note::VoiceNote;
 
 #[derive(Debug, Clone)]
 pub enum DomainEvent {
@@ -11,7 +16,13 @@ pub enum DomainEvent {
         sender_id: UserId,
         content: String,
         kind: MessageKind,
+        thread_root_id: Option<MessageId>,
         created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        forwarded_from: Option<ForwardedFrom>,
+        poll: Option<Poll>,
+        attachment: Option<Attachment>,
+        voice_note: Option<VoiceNote>,
     },
     MessageEdited {
         message_id: MessageId,
@@ -19,14 +30,110 @@ pub enum DomainEvent {
         content: String,
         updated_at: DateTime<Utc>,
     },
+    MessageDeleted {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        deleted_by: UserId,
+        deleted_at: DateTime<Utc>,
+    },
+    // the message's timer ran out and it is gone for good, not tombstoned like an unsend
+    MessageExpired {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+    },
+    MessagePinned {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        pinned_by: UserId,
+        pinned_at: DateTime<Utc>,
+    },
+    MessageUnpinned {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        unpinned_by: UserId,
+    },
+    UserMentioned {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        user_id: UserId,
+        mentioned_by: UserId,
+    },
+    ReactionAdded {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        user_id: UserId,
+        emoji: String,
+        created_at: DateTime<Utc>,
+    },
+    ReactionRemoved {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        user_id: UserId,
+        emoji: String,
+    },
+    PollVoteCast {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        user_id: UserId,
+        choices: Vec<usize>,
+    },
+    PollVoteRetracted {
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        user_id: UserId,
+    },
     ParticipantAdded {
         conversation_id: ConversationId,
         user_id: UserId,
     },
+    InvitationReceived {
+        conversation_id: ConversationId,
+        title: String,
+        user_id: UserId,
+        invited_by: UserId,
+    },
     ParticipantRemoved {
         conversation_id: ConversationId,
         user_id: UserId,
     },
+    ParticipantKicked {
+        conversation_id: ConversationId,
+        user_id: UserId,
+        kicked_by: UserId,
+    },
+    ParticipantRoleChanged {
+        conversation_id: ConversationId,
+        user_id: UserId,
+        role: ParticipantRole,
+    },
+    ConversationRenamed {
+        conversation_id: ConversationId,
+        title: String,
+        renamed_by: UserId,
+    },
+    ConversationDetailsChanged {
+        conversation_id: ConversationId,
+        description: Option<String>,
+        avatar_url: Option<String>,
+        changed_by: UserId,
+    },
+    MessageTimerChanged {
+        conversation_id: ConversationId,
+        ttl_seconds: Option<i64>,
+        changed_by: UserId,
+    },
+    NotificationSettingsChanged {
+        conversation_id: ConversationId,
+        user_id: UserId,
+        level: NotifyLevel,
+        muted_until: Option<DateTime<Utc>>,
+    },
+    ListEntryChanged {
+        conversation_id: ConversationId,
+        user_id: UserId,
+        archived: bool,
+        pinned: bool,
+    },
     ConversationRead {
         conversation_id: ConversationId,
         user_id: UserId,
@@ -38,3 +38,7 @@ macro_rules! id_type {
 id_type!(ConversationId);
 id_type!(UserId);
 id_type!(MessageId);
+id_type!(SessionId);
+id_type!(LoginChallengeId);
+id_type!(InviteId);
+id_type!(ScheduledMessageId);
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    conversation::{Conversation, ConversationKind},
+    errors::DomainError,
+    events::DomainEvent,
+    ids::{ConversationId, UserId},
+};
+
+// a standing offer to join a group; the invitee only becomes a participant by accepting it
+#[derive(Debug, Getters, PartialEq)]
+pub struct Invitation {
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    invited_by: UserId,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+}
+
+impl Invitation {
+    pub fn issue(conversation: &Conversation, actor: &UserId, user_id: UserId) -> Result<(Self, DomainEvent), DomainError> {
+        if matches!(conversation.kind(), ConversationKind::Direct) {
+            return Err(DomainError::NotAGroup);
+        }
+        conversation.require_admin(actor)?;
+        if conversation.is_participant(&user_id) {
+            return Err(DomainError::AlreadyParticipant);
+        }
+
+        let invitation = Self {
+            conversation_id: conversation.id().clone(),
+            user_id,
+            invited_by: actor.clone(),
+            created_at: Utc::now(),
+        };
+        let event = DomainEvent::InvitationReceived {
+            conversation_id: invitation.conversation_id.clone(),
+            title: conversation.title().clone().unwrap_or_default(),
+            user_id: invitation.user_id.clone(),
+            invited_by: invitation.invited_by.clone(),
+        };
+        Ok((invitation, event))
+    }
+
+    // the offer stands only while the inviter could still add the invitee themselves
+    pub fn accept(&self, conversation: &mut Conversation) -> Result<DomainEvent, DomainError> {
+        conversation
+            .add_participant(&self.invited_by, self.user_id.clone())
+            .map_err(|e| match e {
+                DomainError::NotAParticipant | DomainError::NotAnAdmin => DomainError::InvitationNotFound,
+                other => other,
+            })
+    }
+
+    pub(crate) fn from_persistence(
+        conversation_id: ConversationId,
+        user_id: UserId,
+        invited_by: UserId,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            conversation_id,
+            user_id,
+            invited_by,
+            created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(owner: &UserId) -> Conversation {
+        Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap()
+    }
+
+    #[test]
+    fn issue_requires_admin_and_a_newcomer() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let mut convo = group(&owner);
+        convo.add_participant(&owner, member.clone()).unwrap();
+
+        assert_eq!(
+            Invitation::issue(&convo, &member, UserId::new()).err(),
+            Some(DomainError::NotAnAdmin)
+        );
+        assert_eq!(
+            Invitation::issue(&convo, &owner, member).err(),
+            Some(DomainError::AlreadyParticipant)
+        );
+    }
+
+    #[test]
+    fn issue_does_not_add_the_invitee_yet() {
+        let owner = UserId::new();
+        let invitee = UserId::new();
+        let convo = group(&owner);
+
+        let (_invitation, event) = Invitation::issue(&convo, &owner, invitee.clone()).unwrap();
+
+        assert!(!convo.is_participant(&invitee));
+        match event {
+            DomainEvent::InvitationReceived {
+                user_id,
+                title,
+                invited_by,
+                ..
+            } => {
+                assert_eq!(user_id, invitee);
+                assert_eq!(title, "Group");
+                assert_eq!(invited_by, owner);
+            }
+            _ => panic!("expected InvitationReceived event"),
+        }
+    }
+
+    #[test]
+    fn accept_adds_the_invitee_as_member() {
+        let owner = UserId::new();
+        let invitee = UserId::new();
+        let mut convo = group(&owner);
+        let (invitation, _) = Invitation::issue(&convo, &owner, invitee.clone()).unwrap();
+
+        let event = invitation.accept(&mut convo).unwrap();
+
+        assert!(convo.is_participant(&invitee));
+        assert!(matches!(event, DomainEvent::ParticipantAdded { user_id, .. } if user_id == invitee));
+    }
+
+    #[test]
+    fn accept_fails_once_the_inviter_has_left() {
+        let owner = UserId::new();
+        let other = UserId::new();
+        let mut convo = group(&owner);
+        convo.add_participant(&owner, other).unwrap();
+        let (invitation, _) = Invitation::issue(&convo, &owner, UserId::new()).unwrap();
+        convo.remove_participant(&owner, &owner).unwrap();
+
+        assert_eq!(invitation.accept(&mut convo).err(), Some(DomainError::InvitationNotFound));
+    }
+}
@@ -0,0 +1,239 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    conversation::{Conversation, ConversationKind},
+    errors::DomainError,
+    events::DomainEvent,
+    ids::{ConversationId, InviteId, UserId},
+};
+
+// shareable links are meant to be pasted around and shown again to admins, so unlike session
+// tokens they are stored as-is
+#[derive(Debug, Getters, PartialEq)]
+pub struct Invite {
+    #[getset(get = "pub")]
+    id: InviteId,
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    token: String,
+    #[getset(get = "pub")]
+    created_by: UserId,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    expires_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    max_uses: Option<i32>,
+    #[getset(get = "pub")]
+    uses: i32,
+    #[getset(get = "pub")]
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Invite {
+    pub fn create(
+        conversation: &Conversation,
+        actor: &UserId,
+        expires_at: Option<DateTime<Utc>>,
+        max_uses: Option<i32>,
+        now: DateTime<Utc>,
+    ) -> Result<Self, DomainError> {
+        if matches!(conversation.kind(), ConversationKind::Direct) {
+            return Err(DomainError::NotAGroup);
+        }
+        conversation.require_admin(actor)?;
+        if max_uses.is_some_and(|n| n < 1) || expires_at.is_some_and(|at| at <= now) {
+            return Err(DomainError::InvalidInviteLimits);
+        }
+
+        let bytes: [u8; 16] = rand::random();
+        Ok(Self {
+            id: InviteId::new(),
+            conversation_id: conversation.id().clone(),
+            token: URL_SAFE_NO_PAD.encode(bytes),
+            created_by: actor.clone(),
+            created_at: now,
+            expires_at,
+            max_uses,
+            uses: 0,
+            revoked_at: None,
+        })
+    }
+
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|at| now < at) && self.max_uses.is_none_or(|max| self.uses < max)
+    }
+
+    // joining acts on behalf of whoever made the link, so it stops working if they lose admin or leave.
+    // the use itself is counted by the repository, against the stored count rather than this copy
+    pub fn redeem(&self, conversation: &mut Conversation, user_id: UserId, now: DateTime<Utc>) -> Result<DomainEvent, DomainError> {
+        if !self.is_usable(now) {
+            return Err(DomainError::InviteExpired);
+        }
+
+        let event = conversation.add_participant(&self.created_by, user_id).map_err(|e| match e {
+            DomainError::NotAParticipant | DomainError::NotAnAdmin => DomainError::InviteExpired,
+            other => other,
+        })?;
+        Ok(event)
+    }
+
+    pub fn revoke(&mut self, conversation: &Conversation, actor: &UserId, now: DateTime<Utc>) -> Result<(), DomainError> {
+        conversation.require_admin(actor)?;
+        if self.revoked_at.is_none() {
+            self.revoked_at = Some(now);
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_persistence(
+        id: InviteId,
+        conversation_id: ConversationId,
+        token: String,
+        created_by: UserId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        max_uses: Option<i32>,
+        uses: i32,
+        revoked_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            conversation_id,
+            token,
+            created_by,
+            created_at,
+            expires_at,
+            max_uses,
+            uses,
+            revoked_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::conversation::ParticipantRole;
+
+    fn group(owner: &UserId) -> Conversation {
+        Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap()
+    }
+
+    #[test]
+    fn create_requires_admin_of_a_group() {
+        let owner = UserId::new();
+        let mut convo = group(&owner);
+        let member = UserId::new();
+        convo.add_participant(&owner, member.clone()).unwrap();
+        let now = Utc::now();
+
+        assert_eq!(
+            Invite::create(&convo, &member, None, None, now).err(),
+            Some(DomainError::NotAnAdmin)
+        );
+
+        let direct = Conversation::new_direct(ConversationId::new(), owner.clone(), member).unwrap();
+        assert_eq!(Invite::create(&direct, &owner, None, None, now).err(), Some(DomainError::NotAGroup));
+    }
+
+    #[test]
+    fn create_rejects_nonsensical_limits() {
+        let owner = UserId::new();
+        let convo = group(&owner);
+        let now = Utc::now();
+
+        assert_eq!(
+            Invite::create(&convo, &owner, None, Some(0), now).err(),
+            Some(DomainError::InvalidInviteLimits)
+        );
+        assert_eq!(
+            Invite::create(&convo, &owner, Some(now - Duration::minutes(1)), None, now).err(),
+            Some(DomainError::InvalidInviteLimits)
+        );
+    }
+
+    #[test]
+    fn redeem_adds_participant_until_the_uses_run_out() {
+        let owner = UserId::new();
+        let mut convo = group(&owner);
+        let now = Utc::now();
+        let invite = Invite::create(&convo, &owner, None, Some(1), now).unwrap();
+        let joiner = UserId::new();
+
+        let event = invite.redeem(&mut convo, joiner.clone(), now).unwrap();
+
+        assert!(convo.is_participant(&joiner));
+        assert!(matches!(event, DomainEvent::ParticipantAdded { user_id, .. } if user_id == joiner));
+
+        let used_up = Invite::from_persistence(
+            invite.id.clone(),
+            invite.conversation_id.clone(),
+            invite.token.clone(),
+            owner,
+            now,
+            None,
+            Some(1),
+            1,
+            None,
+        );
+        assert_eq!(
+            used_up.redeem(&mut convo, UserId::new(), now).err(),
+            Some(DomainError::InviteExpired)
+        );
+    }
+
+    #[test]
+    fn redeem_rejects_expired_or_revoked_invites() {
+        let owner = UserId::new();
+        let mut convo = group(&owner);
+        let now = Utc::now();
+        let expiring = Invite::create(&convo, &owner, Some(now + Duration::hours(1)), None, now).unwrap();
+        let mut revoked = Invite::create(&convo, &owner, None, None, now).unwrap();
+        revoked.revoke(&convo, &owner, now).unwrap();
+
+        assert_eq!(
+            expiring.redeem(&mut convo, UserId::new(), now + Duration::hours(2)).err(),
+            Some(DomainError::InviteExpired)
+        );
+        assert_eq!(
+            revoked.redeem(&mut convo, UserId::new(), now).err(),
+            Some(DomainError::InviteExpired)
+        );
+    }
+
+    #[test]
+    fn redeem_stops_working_once_the_creator_is_no_longer_admin() {
+        let owner = UserId::new();
+        let admin = UserId::new();
+        let mut convo = group(&owner);
+        convo.add_participant(&owner, admin.clone()).unwrap();
+        convo.change_role(&owner, &admin, ParticipantRole::Admin).unwrap();
+        let now = Utc::now();
+        let invite = Invite::create(&convo, &admin, None, None, now).unwrap();
+        convo.remove_participant(&admin, &admin).unwrap();
+
+        assert_eq!(
+            invite.redeem(&mut convo, UserId::new(), now).err(),
+            Some(DomainError::InviteExpired)
+        );
+    }
+
+    #[test]
+    fn tokens_are_distinct() {
+        let owner = UserId::new();
+        let convo = group(&owner);
+        let now = Utc::now();
+
+        let a = Invite::create(&convo, &owner, None, None, now).unwrap();
+        let b = Invite::create(&convo, &owner, None, None, now).unwrap();
+
+        assert_ne!(a.token, b.token);
+    }
+}
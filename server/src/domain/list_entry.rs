@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    events::DomainEvent,
+    ids::{ConversationId, UserId},
+};
+
+// where a conversation sits in one participant's list; nobody else sees it
+#[derive(Debug, Getters, PartialEq)]
+pub struct ListEntry {
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    archived_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    pinned_at: Option<DateTime<Utc>>,
+}
+
+impl ListEntry {
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned_at.is_some()
+    }
+
+    // None when nothing changed, so repeating a toggle is a no-op
+    pub fn set_archived(&mut self, archived: bool, now: DateTime<Utc>) -> Option<DomainEvent> {
+        if archived == self.is_archived() {
+            return None;
+        }
+        self.archived_at = archived.then_some(now);
+        Some(self.changed())
+    }
+
+    pub fn set_pinned(&mut self, pinned: bool, now: DateTime<Utc>) -> Option<DomainEvent> {
+        if pinned == self.is_pinned() {
+            return None;
+        }
+        self.pinned_at = pinned.then_some(now);
+        Some(self.changed())
+    }
+
+    fn changed(&self) -> DomainEvent {
+        DomainEvent::ListEntryChanged {
+            conversation_id: self.conversation_id.clone(),
+            user_id: self.user_id.clone(),
+            archived: self.is_archived(),
+            pinned: self.is_pinned(),
+        }
+    }
+
+    pub(crate) fn from_persistence(
+        conversation_id: ConversationId,
+        user_id: UserId,
+        archived_at: Option<DateTime<Utc>>,
+        pinned_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            conversation_id,
+            user_id,
+            archived_at,
+            pinned_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> ListEntry {
+        ListEntry::from_persistence(ConversationId::new(), UserId::new(), None, None)
+    }
+
+    #[test]
+    fn set_archived_records_the_change_once() {
+        let mut entry = entry();
+        let now = Utc::now();
+
+        let event = entry.set_archived(true, now);
+
+        assert_eq!(entry.archived_at, Some(now));
+        assert!(matches!(
+            event,
+            Some(DomainEvent::ListEntryChanged {
+                archived: true,
+                pinned: false,
+                ..
+            })
+        ));
+        assert!(entry.set_archived(true, now).is_none());
+    }
+
+    #[test]
+    fn pinning_is_independent_of_archiving() {
+        let mut entry = entry();
+        let now = Utc::now();
+        entry.set_archived(true, now);
+
+        entry.set_pinned(true, now);
+        let event = entry.set_archived(false, now);
+
+        assert!(entry.is_pinned());
+        assert!(matches!(
+            event,
+            Some(DomainEvent::ListEntryChanged {
+                archived: false,
+                pinned: true,
+                ..
+            })
+        ));
+    }
+}
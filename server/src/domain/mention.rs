@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    ids::UserId,
+    user::{User, Username},
+};
+
+// who can be mentioned in a conversation: its participants, keyed by username
+#[derive(Debug, Default)]
+pub struct MentionDirectory(HashMap<String, UserId>);
+
+impl MentionDirectory {
+    pub fn new(entries: impl IntoIterator<Item = (Username, UserId)>) -> Self {
+        Self(
+            entries
+                .into_iter()
+                .map(|(username, id)| (username.as_str().to_string(), id))
+                .collect(),
+        )
+    }
+
+    pub fn from_users(users: &[User]) -> Self {
+        Self::new(users.iter().map(|user| (user.username().clone(), user.id().clone())))
+    }
+
+    // each participant at most once, in order of first mention; unknown handles are just text
+    pub fn resolve(&self, content: &str) -> Vec<UserId> {
+        let mut mentioned: Vec<UserId> = Vec::new();
+        for handle in handles(content) {
+            if let Some(id) = self.0.get(handle)
+                && !mentioned.contains(id)
+            {
+                mentioned.push(id.clone());
+            }
+        }
+        mentioned
+    }
+}
+
+fn is_handle_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+// `@name` at the start of the text or after a non-handle character, so e-mail addresses don't count;
+// a trailing '.' is sentence punctuation, not part of the name
+fn handles(content: &str) -> impl Iterator<Item = &str> {
+    content.match_indices('@').filter_map(|(at, _)| {
+        if content[..at].chars().next_back().is_some_and(is_handle_char) {
+            return None;
+        }
+        let rest = &content[at + 1..];
+        let end = rest.find(|c: char| !is_handle_char(c)).unwrap_or(rest.len());
+        let handle = rest[..end].trim_end_matches('.');
+        (!handle.is_empty()).then_some(handle)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directory(names: &[&str]) -> (MentionDirectory, Vec<UserId>) {
+        let ids: Vec<UserId> = names.iter().map(|_| UserId::new()).collect();
+        let directory = MentionDirectory::new(
+            names
+                .iter()
+                .zip(&ids)
+                .map(|(name, id)| (Username::new(name.to_string()).unwrap(), id.clone())),
+        );
+        (directory, ids)
+    }
+
+    #[test]
+    fn resolve_finds_participants_once_in_order() {
+        let (directory, ids) = directory(&["alice", "bob"]);
+
+        let mentioned = directory.resolve("@bob can you ask @alice? thanks @bob.");
+
+        assert_eq!(mentioned, vec![ids[1].clone(), ids[0].clone()]);
+    }
+
+    #[test]
+    fn resolve_ignores_unknown_handles_and_email_addresses() {
+        let (directory, _) = directory(&["alice"]);
+
+        assert!(directory.resolve("mail alice@example.com or ping @carol").is_empty());
+        assert!(directory.resolve("just an @ sign").is_empty());
+    }
+
+    #[test]
+    fn resolve_keeps_dots_inside_usernames() {
+        let (directory, ids) = directory(&["j.doe"]);
+
+        assert_eq!(directory.resolve("(@j.doe)"), vec![ids[0].clone()]);
+    }
+}
@@ -1,10 +1,15 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use getset::Getters;
 
 use crate::domain::{
+    attachment::Attachment,
+    conversation::Conversation,
     errors::DomainError,
     events::DomainEvent,
     ids::{ConversationId, MessageId, UserId},
+    mention::MentionDirectory,
+    poll::Poll,
+    voice_note::VoiceNote,
 };
 
 #[derive(Debug, PartialEq, Clone, sqlx::Type)]
@@ -12,6 +17,16 @@ use crate::domain::{
 pub enum MessageKind {
     Text,
     Image,
+    Poll,
+    File,
+    Audio,
+}
+
+// where a forwarded message first came from; forwarding a forward keeps pointing at the original
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardedFrom {
+    pub message_id: MessageId,
+    pub conversation_id: ConversationId,
 }
 
 #[derive(Debug, Getters, PartialEq)]
@@ -27,11 +42,40 @@ pub struct Message {
     #[getset(get = "pub")]
     kind: MessageKind,
     #[getset(get = "pub")]
+    thread_root_id: Option<MessageId>,
+    #[getset(get = "pub")]
+    mentions: Vec<UserId>,
+    #[getset(get = "pub")]
     edited: bool,
     #[getset(get = "pub")]
     created_at: DateTime<Utc>,
     #[getset(get = "pub")]
     updated_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    deleted_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    expires_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    forwarded_from: Option<ForwardedFrom>,
+    #[getset(get = "pub")]
+    poll: Option<Poll>,
+    #[getset(get = "pub")]
+    attachment: Option<Attachment>,
+    #[getset(get = "pub")]
+    voice_note: Option<VoiceNote>,
+}
+
+// a superseded version of a message's content, kept when the sender edits it
+#[derive(Debug, Getters, PartialEq)]
+pub struct MessageRevision {
+    #[getset(get = "pub")]
+    message_id: MessageId,
+    #[getset(get = "pub")]
+    content: String,
+    #[getset(get = "pub")]
+    written_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    replaced_at: DateTime<Utc>,
 }
 
 impl Message {
@@ -41,6 +85,177 @@ impl Message {
         sender_id: UserId,
         content: String,
         kind: MessageKind,
+        directory: &MentionDirectory,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        Self::compose(id, conversation_id, sender_id, content, kind, None, None, None, None, directory)
+    }
+
+    // the content is the question; polls only go at the top level of a conversation, never in a thread
+    pub fn new_poll(
+        id: MessageId,
+        conversation_id: ConversationId,
+        sender_id: UserId,
+        question: String,
+        poll: Poll,
+        directory: &MentionDirectory,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        Self::compose(
+            id,
+            conversation_id,
+            sender_id,
+            question,
+            MessageKind::Poll,
+            None,
+            Some(poll),
+            None,
+            None,
+            directory,
+        )
+    }
+
+    // the content is the uploaded file's URL
+    pub fn new_file(
+        id: MessageId,
+        conversation_id: ConversationId,
+        sender_id: UserId,
+        url: String,
+        attachment: Attachment,
+        directory: &MentionDirectory,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        Self::compose(
+            id,
+            conversation_id,
+            sender_id,
+            url,
+            MessageKind::File,
+            None,
+            None,
+            Some(attachment),
+            None,
+            directory,
+        )
+    }
+
+    // the content is the uploaded recording's URL
+    pub fn new_voice_note(
+        id: MessageId,
+        conversation_id: ConversationId,
+        sender_id: UserId,
+        url: String,
+        voice_note: VoiceNote,
+        directory: &MentionDirectory,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        Self::compose(
+            id,
+            conversation_id,
+            sender_id,
+            url,
+            MessageKind::Audio,
+            None,
+            None,
+            None,
+            Some(voice_note),
+            directory,
+        )
+    }
+
+    // threads are one level deep: replying to a reply joins the parent's thread rather than nesting
+    #[allow(clippy::too_many_arguments)]
+    pub fn reply(
+        id: MessageId,
+        parent: &Message,
+        conversation_id: ConversationId,
+        sender_id: UserId,
+        content: String,
+        kind: MessageKind,
+        attachment: Option<Attachment>,
+        voice_note: Option<VoiceNote>,
+        directory: &MentionDirectory,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        if parent.conversation_id != conversation_id {
+            return Err(DomainError::ThreadParentElsewhere);
+        }
+        if parent.is_deleted() {
+            return Err(DomainError::MessageDeleted);
+        }
+        // gone for everyone already, the reaper just hasn't got to it
+        if parent.is_expired(Utc::now()) {
+            return Err(DomainError::MessageNotFound);
+        }
+        let root = parent.thread_root_id.clone().unwrap_or_else(|| parent.id.clone());
+        Self::compose(
+            id,
+            conversation_id,
+            sender_id,
+            content,
+            kind,
+            Some(root),
+            None,
+            attachment,
+            voice_note,
+            directory,
+        )
+    }
+
+    // a new top-level message from the forwarder with the source's content. mentions aren't resolved
+    // again, so forwarding never pings anyone in the target conversation
+    pub fn forward(
+        id: MessageId,
+        source: &Message,
+        source_conversation: &Conversation,
+        target: &Conversation,
+        forwarder: &UserId,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        if &source.conversation_id != source_conversation.id() {
+            return Err(DomainError::MessageNotInConversation);
+        }
+        if !source_conversation.is_participant(forwarder) || !target.is_participant(forwarder) {
+            return Err(DomainError::NotAParticipant);
+        }
+        if source.is_deleted() {
+            return Err(DomainError::MessageDeleted);
+        }
+        if source.is_expired(Utc::now()) {
+            return Err(DomainError::MessageNotFound);
+        }
+        // a forwarded poll starts over with no votes, which makes no sense once the original has closed
+        if source.poll.as_ref().is_some_and(|poll| poll.is_closed(Utc::now())) {
+            return Err(DomainError::PollClosed);
+        }
+
+        let origin = source.forwarded_from.clone().unwrap_or_else(|| ForwardedFrom {
+            message_id: source.id.clone(),
+            conversation_id: source.conversation_id.clone(),
+        });
+        let (mut message, _) = Self::compose(
+            id,
+            target.id().clone(),
+            forwarder.clone(),
+            source.content.clone(),
+            source.kind.clone(),
+            None,
+            source.poll.clone(),
+            source.attachment.clone(),
+            source.voice_note.clone(),
+            &MentionDirectory::default(),
+        )?;
+        message.forwarded_from = Some(origin);
+        let event = message.sent_event();
+        Ok((message, event))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compose(
+        id: MessageId,
+        conversation_id: ConversationId,
+        sender_id: UserId,
+        content: String,
+        kind: MessageKind,
+        thread_root_id: Option<MessageId>,
+        poll: Option<Poll>,
+        attachment: Option<Attachment>,
+        voice_note: Option<VoiceNote>,
+        directory: &MentionDirectory,
     ) -> Result<(Self, DomainEvent), DomainError> {
         if content.trim().is_empty() {
             return Err(DomainError::EmptyMessage);
@@ -48,32 +263,106 @@ impl Message {
         if matches!(kind, MessageKind::Image) && !looks_like_url(&content) {
             return Err(DomainError::ImageNeedsUrl);
         }
+        if matches!(kind, MessageKind::File) && !looks_like_url(&content) {
+            return Err(DomainError::FileNeedsUrl);
+        }
+        if matches!(kind, MessageKind::Audio) && !looks_like_url(&content) {
+            return Err(DomainError::AudioNeedsUrl);
+        }
+        if matches!(kind, MessageKind::Poll) != poll.is_some() {
+            return Err(DomainError::PollNeedsOptions);
+        }
+        if matches!(kind, MessageKind::File) != attachment.is_some() {
+            return Err(DomainError::FileNeedsAttachment);
+        }
+        if matches!(kind, MessageKind::Audio) != voice_note.is_some() {
+            return Err(DomainError::VoiceNoteNeedsMetadata);
+        }
+        let created_at = Utc::now();
+        if let Some(closes_at) = poll.as_ref().and_then(|poll| *poll.closes_at())
+            && closes_at <= created_at
+        {
+            return Err(DomainError::PollClosesInPast);
+        }
 
+        let mentions = mentions_in(&content, &sender_id, directory);
         let message = Self {
             id: id.clone(),
             conversation_id: conversation_id.clone(),
             sender_id: sender_id.clone(),
             content,
             kind,
+            thread_root_id,
+            mentions,
             edited: false,
-            created_at: Utc::now(),
+            created_at,
             updated_at: None,
+            deleted_at: None,
+            expires_at: None,
+            forwarded_from: None,
+            poll,
+            attachment,
+            voice_note,
         };
-        let event = DomainEvent::MessageSent {
-            message_id: id,
-            conversation_id,
-            sender_id,
-            content: message.content.clone(),
-            kind: message.kind.clone(),
-            created_at: message.created_at,
-        };
+        let event = message.sent_event();
         Ok((message, event))
     }
 
-    pub fn edit(&mut self, editor: &UserId, new_content: String) -> Result<DomainEvent, DomainError> {
+    fn sent_event(&self) -> DomainEvent {
+        DomainEvent::MessageSent {
+            message_id: self.id.clone(),
+            conversation_id: self.conversation_id.clone(),
+            sender_id: self.sender_id.clone(),
+            content: self.content.clone(),
+            kind: self.kind.clone(),
+            thread_root_id: self.thread_root_id.clone(),
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            forwarded_from: self.forwarded_from.clone(),
+            poll: self.poll.clone(),
+            attachment: self.attachment.clone(),
+            voice_note: self.voice_note.clone(),
+        }
+    }
+
+    // applies the conversation's disappearing-message timer to a message that hasn't gone out yet,
+    // returning the MessageSent event to publish in place of the one from composing it
+    pub fn expire_after(&mut self, ttl: Duration) -> DomainEvent {
+        self.expires_at = Some(self.created_at + ttl);
+        self.sent_event()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    pub fn is_reply(&self) -> bool {
+        self.thread_root_id.is_some()
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    pub fn edit(
+        &mut self,
+        editor: &UserId,
+        new_content: String,
+        directory: &MentionDirectory,
+    ) -> Result<(MessageRevision, DomainEvent), DomainError> {
+        if self.is_deleted() {
+            return Err(DomainError::MessageDeleted);
+        }
         if &self.sender_id != editor {
             return Err(DomainError::NotYourMessage);
         }
+        // the name, size and type describe the file that was sent, so the link can't be swapped out
+        if matches!(self.kind, MessageKind::File) {
+            return Err(DomainError::FileNotEditable);
+        }
+        if matches!(self.kind, MessageKind::Audio) {
+            return Err(DomainError::VoiceNoteNotEditable);
+        }
         if new_content.trim().is_empty() {
             return Err(DomainError::EmptyMessage);
         }
@@ -81,17 +370,63 @@ impl Message {
             return Err(DomainError::ImageNeedsUrl);
         }
         let updated_at = Utc::now();
-        self.content = new_content;
+        self.mentions = mentions_in(&new_content, &self.sender_id, directory);
+        let revision = MessageRevision {
+            message_id: self.id.clone(),
+            content: std::mem::replace(&mut self.content, new_content),
+            written_at: self.updated_at.unwrap_or(self.created_at),
+            replaced_at: updated_at,
+        };
         self.edited = true;
         self.updated_at = Some(updated_at);
-        Ok(DomainEvent::MessageEdited {
+        let event = DomainEvent::MessageEdited {
             message_id: self.id.clone(),
             conversation_id: self.conversation_id.clone(),
             content: self.content.clone(),
             updated_at,
+        };
+        Ok((revision, event))
+    }
+
+    // unsend for everyone: the sender can always retract, group admins can moderate.
+    // the content is wiped rather than hidden so it can't leak through any later read path
+    pub fn delete(&mut self, actor: &UserId, conversation: &Conversation) -> Result<DomainEvent, DomainError> {
+        if self.is_deleted() {
+            return Err(DomainError::MessageDeleted);
+        }
+        let Some(role) = conversation.role_of(actor) else {
+            return Err(DomainError::NotAParticipant);
+        };
+        if &self.sender_id != actor && !role.is_admin() {
+            return Err(DomainError::NotYourMessage);
+        }
+
+        let deleted_at = Utc::now();
+        self.content = String::new();
+        self.mentions.clear();
+        self.deleted_at = Some(deleted_at);
+        Ok(DomainEvent::MessageDeleted {
+            message_id: self.id.clone(),
+            conversation_id: self.conversation_id.clone(),
+            deleted_by: actor.clone(),
+            deleted_at,
         })
     }
 
+    // one event per mentioned participant not already notified, e.g. when an edit adds a mention
+    pub fn mention_events(&self, already_notified: &[UserId]) -> Vec<DomainEvent> {
+        self.mentions
+            .iter()
+            .filter(|user_id| !already_notified.contains(user_id))
+            .map(|user_id| DomainEvent::UserMentioned {
+                message_id: self.id.clone(),
+                conversation_id: self.conversation_id.clone(),
+                user_id: user_id.clone(),
+                mentioned_by: self.sender_id.clone(),
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_persistence(
         id: MessageId,
@@ -99,9 +434,17 @@ impl Message {
         sender_id: UserId,
         content: String,
         kind: MessageKind,
+        thread_root_id: Option<MessageId>,
+        mentions: Vec<UserId>,
         edited: bool,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
+        deleted_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+        forwarded_from: Option<ForwardedFrom>,
+        poll: Option<Poll>,
+        attachment: Option<Attachment>,
+        voice_note: Option<VoiceNote>,
     ) -> Self {
         Self {
             id,
@@ -109,24 +452,47 @@ impl Message {
             sender_id,
             content,
             kind,
+            thread_root_id,
+            mentions,
             edited,
             created_at,
             updated_at,
+            deleted_at,
+            expires_at,
+            forwarded_from,
+            poll,
+            attachment,
+            voice_note,
         }
     }
 }
 
-fn looks_like_url(s: &str) -> bool {
+// mentioning yourself is just text
+fn mentions_in(content: &str, sender_id: &UserId, directory: &MentionDirectory) -> Vec<UserId> {
+    let mut mentions = directory.resolve(content);
+    mentions.retain(|user_id| user_id != sender_id);
+    mentions
+}
+
+pub(crate) fn looks_like_url(s: &str) -> bool {
     s.starts_with("http://") || s.starts_with("https://")
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Duration, Utc};
+
     use crate::domain::{
+        attachment::Attachment,
+        conversation::{Conversation, ParticipantRole},
         errors::DomainError,
         events::DomainEvent,
         ids::{ConversationId, MessageId, UserId},
-        message::{Message, MessageKind},
+        mention::MentionDirectory,
+        message::{ForwardedFrom, Message, MessageKind},
+        poll::Poll,
+        user::Username,
+        voice_note::{VoiceNote, WAVEFORM_PEAKS},
     };
 
     #[test]
@@ -137,6 +503,7 @@ mod tests {
             UserId::new(),
             "   ".to_string(),
             MessageKind::Text,
+            &MentionDirectory::default(),
         );
 
         assert_eq!(result.err(), Some(DomainError::EmptyMessage));
@@ -150,6 +517,7 @@ mod tests {
             UserId::new(),
             "not a url".to_string(),
             MessageKind::Image,
+            &MentionDirectory::default(),
         );
 
         assert_eq!(result.err(), Some(DomainError::ImageNeedsUrl));
@@ -167,6 +535,7 @@ mod tests {
             sender_id.clone(),
             "hello".to_string(),
             MessageKind::Text,
+            &MentionDirectory::default(),
         )
         .unwrap();
 
@@ -185,19 +554,52 @@ mod tests {
                 sender_id: event_sender_id,
                 content,
                 kind,
+                thread_root_id,
                 created_at,
+                expires_at,
+                forwarded_from,
+                poll,
+                attachment,
+                voice_note,
             } => {
                 assert_eq!(message_id, id);
                 assert_eq!(event_conversation_id, conversation_id);
                 assert_eq!(event_sender_id, sender_id);
                 assert_eq!(content, "hello");
                 assert_eq!(kind, MessageKind::Text);
+                assert_eq!(thread_root_id, None);
                 assert_eq!(created_at, message.created_at);
+                assert_eq!(expires_at, None);
+                assert_eq!(forwarded_from, None);
+                assert_eq!(poll, None);
+                assert_eq!(attachment, None);
+                assert_eq!(voice_note, None);
             }
             _ => panic!("expected MessageSent event"),
         }
     }
 
+    #[test]
+    fn expire_after_counts_from_when_the_message_was_sent() {
+        let (mut message, _) = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "brief".to_string(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+
+        let event = message.expire_after(Duration::hours(1));
+
+        let expires_at = message.created_at + Duration::hours(1);
+        assert_eq!(message.expires_at, Some(expires_at));
+        assert!(matches!(event, DomainEvent::MessageSent { expires_at: Some(at), .. } if at == expires_at));
+        assert!(!message.is_expired(expires_at - Duration::seconds(1)));
+        assert!(message.is_expired(expires_at));
+    }
+
     #[test]
     fn new_builds_image_message_with_url() {
         let (message, _event) = Message::new(
@@ -206,6 +608,7 @@ mod tests {
             UserId::new(),
             "https://example.com/img.png".to_string(),
             MessageKind::Image,
+            &MentionDirectory::default(),
         )
         .unwrap();
 
@@ -221,10 +624,11 @@ mod tests {
             UserId::new(),
             "hello".to_string(),
             MessageKind::Text,
+            &MentionDirectory::default(),
         )
         .unwrap();
 
-        let result = message.edit(&UserId::new(), "changed".to_string());
+        let result = message.edit(&UserId::new(), "changed".to_string(), &MentionDirectory::default());
 
         assert_eq!(result.err(), Some(DomainError::NotYourMessage));
     }
@@ -238,10 +642,11 @@ mod tests {
             sender_id.clone(),
             "hello".to_string(),
             MessageKind::Text,
+            &MentionDirectory::default(),
         )
         .unwrap();
 
-        let result = message.edit(&sender_id, "   ".to_string());
+        let result = message.edit(&sender_id, "   ".to_string(), &MentionDirectory::default());
 
         assert_eq!(result.err(), Some(DomainError::EmptyMessage));
     }
@@ -255,10 +660,11 @@ mod tests {
             sender_id.clone(),
             "https://example.com/img.png".to_string(),
             MessageKind::Image,
+            &MentionDirectory::default(),
         )
         .unwrap();
 
-        let result = message.edit(&sender_id, "not a url".to_string());
+        let result = message.edit(&sender_id, "not a url".to_string(), &MentionDirectory::default());
 
         assert_eq!(result.err(), Some(DomainError::ImageNeedsUrl));
     }
@@ -274,14 +680,21 @@ mod tests {
             sender_id.clone(),
             "hello".to_string(),
             MessageKind::Text,
+            &MentionDirectory::default(),
         )
         .unwrap();
 
-        let event = message.edit(&sender_id, "updated".to_string()).unwrap();
+        let (revision, event) = message
+            .edit(&sender_id, "updated".to_string(), &MentionDirectory::default())
+            .unwrap();
 
         assert_eq!(message.content, "updated");
         assert!(message.edited);
         assert!(message.updated_at.is_some());
+        assert_eq!(revision.message_id(), &id);
+        assert_eq!(revision.content(), "hello");
+        assert_eq!(revision.written_at(), &message.created_at);
+        assert_eq!(Some(*revision.replaced_at()), message.updated_at);
 
         match event {
             DomainEvent::MessageEdited {
@@ -299,6 +712,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn edit_twice_records_when_each_revision_was_written() {
+        let sender_id = UserId::new();
+        let (mut message, _event) = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            sender_id.clone(),
+            "first".to_string(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+
+        let (first, _) = message
+            .edit(&sender_id, "second".to_string(), &MentionDirectory::default())
+            .unwrap();
+        let (second, _) = message.edit(&sender_id, "third".to_string(), &MentionDirectory::default()).unwrap();
+
+        assert_eq!(second.content(), "second");
+        assert_eq!(second.written_at(), first.replaced_at());
+        assert_eq!(message.content, "third");
+    }
+
     #[test]
     fn from_persistence_reconstructs_message_fields() {
         let id = MessageId::new();
@@ -312,9 +748,17 @@ mod tests {
             sender_id.clone(),
             "hello".to_string(),
             MessageKind::Text,
+            None,
+            vec![],
             true,
             created_at,
             Some(created_at),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
         );
 
         assert_eq!(message.id(), &id);
@@ -325,5 +769,442 @@ mod tests {
         assert!(message.edited);
         assert_eq!(message.created_at, created_at);
         assert_eq!(message.updated_at, Some(created_at));
+        assert!(!message.is_reply());
+        assert!(!message.is_deleted());
+    }
+
+    // a group where `sender` is a plain member, returned with the group's owner
+    fn group_message(sender: &UserId) -> (Conversation, Message, UserId) {
+        let owner = UserId::new();
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        conversation.add_participant(&owner, sender.clone()).unwrap();
+        let (message, _) = Message::new(
+            MessageId::new(),
+            conversation.id().clone(),
+            sender.clone(),
+            "hello".to_string(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        (conversation, message, owner)
+    }
+
+    #[test]
+    fn delete_by_sender_wipes_content_and_emits_event() {
+        let sender = UserId::new();
+        let (conversation, mut message, _owner) = group_message(&sender);
+
+        let event = message.delete(&sender, &conversation).unwrap();
+
+        assert!(message.is_deleted());
+        assert_eq!(message.content, "");
+        match event {
+            DomainEvent::MessageDeleted {
+                message_id,
+                deleted_by,
+                deleted_at,
+                ..
+            } => {
+                assert_eq!(&message_id, message.id());
+                assert_eq!(deleted_by, sender);
+                assert_eq!(Some(deleted_at), message.deleted_at);
+            }
+            _ => panic!("expected MessageDeleted event"),
+        }
+    }
+
+    #[test]
+    fn delete_allows_group_admin_but_not_other_members() {
+        let sender = UserId::new();
+        let other = UserId::new();
+        let (mut conversation, mut message, owner) = group_message(&sender);
+        conversation.add_participant(&owner, other.clone()).unwrap();
+
+        assert_eq!(message.delete(&other, &conversation).err(), Some(DomainError::NotYourMessage));
+        conversation.change_role(&owner, &other, ParticipantRole::Admin).unwrap();
+        assert!(message.delete(&other, &conversation).is_ok());
+    }
+
+    #[test]
+    fn delete_rejects_non_participant() {
+        let sender = UserId::new();
+        let (conversation, mut message, _owner) = group_message(&sender);
+
+        assert_eq!(
+            message.delete(&UserId::new(), &conversation).err(),
+            Some(DomainError::NotAParticipant)
+        );
+    }
+
+    #[test]
+    fn deleted_message_cannot_be_edited_or_deleted_again() {
+        let sender = UserId::new();
+        let (conversation, mut message, owner) = group_message(&sender);
+        message.delete(&sender, &conversation).unwrap();
+
+        assert_eq!(
+            message.edit(&sender, "again".to_string(), &MentionDirectory::default()).err(),
+            Some(DomainError::MessageDeleted)
+        );
+        assert_eq!(message.delete(&owner, &conversation).err(), Some(DomainError::MessageDeleted));
+    }
+
+    fn text(conversation_id: &ConversationId, content: &str) -> Message {
+        let (message, _) = Message::new(
+            MessageId::new(),
+            conversation_id.clone(),
+            UserId::new(),
+            content.to_string(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        message
+    }
+
+    #[test]
+    fn reply_joins_parent_thread_and_emits_event() {
+        let conversation_id = ConversationId::new();
+        let root = text(&conversation_id, "root");
+
+        let (reply, event) = Message::reply(
+            MessageId::new(),
+            &root,
+            conversation_id.clone(),
+            UserId::new(),
+            "reply".to_string(),
+            MessageKind::Text,
+            None,
+            None,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+
+        assert_eq!(reply.thread_root_id(), &Some(root.id().clone()));
+        match event {
+            DomainEvent::MessageSent { thread_root_id, .. } => assert_eq!(thread_root_id, Some(root.id().clone())),
+            _ => panic!("expected MessageSent event"),
+        }
+    }
+
+    #[test]
+    fn reply_to_a_reply_stays_in_the_root_thread() {
+        let conversation_id = ConversationId::new();
+        let root = text(&conversation_id, "root");
+        let (first, _) = Message::reply(
+            MessageId::new(),
+            &root,
+            conversation_id.clone(),
+            UserId::new(),
+            "first".to_string(),
+            MessageKind::Text,
+            None,
+            None,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+
+        let (second, _) = Message::reply(
+            MessageId::new(),
+            &first,
+            conversation_id,
+            UserId::new(),
+            "second".to_string(),
+            MessageKind::Text,
+            None,
+            None,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+
+        assert_eq!(second.thread_root_id(), &Some(root.id().clone()));
+    }
+
+    #[test]
+    fn reply_rejects_parent_from_another_conversation() {
+        let root = text(&ConversationId::new(), "root");
+
+        let result = Message::reply(
+            MessageId::new(),
+            &root,
+            ConversationId::new(),
+            UserId::new(),
+            "reply".to_string(),
+            MessageKind::Text,
+            None,
+            None,
+            &MentionDirectory::default(),
+        );
+
+        assert_eq!(result.err(), Some(DomainError::ThreadParentElsewhere));
+    }
+
+    #[test]
+    fn reply_rejects_deleted_parent() {
+        let sender = UserId::new();
+        let (conversation, mut root, _owner) = group_message(&sender);
+        root.delete(&sender, &conversation).unwrap();
+
+        let result = Message::reply(
+            MessageId::new(),
+            &root,
+            conversation.id().clone(),
+            sender,
+            "reply".to_string(),
+            MessageKind::Text,
+            None,
+            None,
+            &MentionDirectory::default(),
+        );
+
+        assert_eq!(result.err(), Some(DomainError::MessageDeleted));
+    }
+
+    #[test]
+    fn reply_rejects_expired_parent_not_yet_reaped() {
+        let conversation_id = ConversationId::new();
+        let mut root = text(&conversation_id, "root");
+        root.expire_after(Duration::zero());
+
+        let result = Message::reply(
+            MessageId::new(),
+            &root,
+            conversation_id,
+            UserId::new(),
+            "reply".to_string(),
+            MessageKind::Text,
+            None,
+            None,
+            &MentionDirectory::default(),
+        );
+
+        assert_eq!(result.err(), Some(DomainError::MessageNotFound));
+    }
+
+    #[test]
+    fn forward_copies_content_and_points_back_at_the_original() {
+        let sender = UserId::new();
+        let (source_conversation, source, _owner) = group_message(&sender);
+        let target = Conversation::new_group(ConversationId::new(), "Elsewhere".into(), sender.clone()).unwrap();
+        let onward = Conversation::new_group(ConversationId::new(), "Further".into(), sender.clone()).unwrap();
+
+        let (forwarded, event) = Message::forward(MessageId::new(), &source, &source_conversation, &target, &sender).unwrap();
+        let (again, _) = Message::forward(MessageId::new(), &forwarded, &target, &onward, &sender).unwrap();
+
+        let origin = ForwardedFrom {
+            message_id: source.id().clone(),
+            conversation_id: source_conversation.id().clone(),
+        };
+        assert_eq!(forwarded.conversation_id(), target.id());
+        assert_eq!(forwarded.content(), source.content());
+        assert_eq!(forwarded.forwarded_from(), &Some(origin.clone()));
+        assert_eq!(again.forwarded_from(), &Some(origin));
+        assert!(matches!(
+            event,
+            DomainEvent::MessageSent {
+                forwarded_from: Some(_),
+                thread_root_id: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn forward_requires_membership_on_both_sides_and_a_live_source() {
+        let sender = UserId::new();
+        let (source_conversation, mut source, _owner) = group_message(&sender);
+        let outsider_target = Conversation::new_group(ConversationId::new(), "Not mine".into(), UserId::new()).unwrap();
+        let target = Conversation::new_group(ConversationId::new(), "Mine".into(), sender.clone()).unwrap();
+
+        assert_eq!(
+            Message::forward(MessageId::new(), &source, &source_conversation, &outsider_target, &sender).err(),
+            Some(DomainError::NotAParticipant)
+        );
+        assert_eq!(
+            Message::forward(MessageId::new(), &source, &target, &target, &sender).err(),
+            Some(DomainError::MessageNotInConversation)
+        );
+
+        source.delete(&sender, &source_conversation).unwrap();
+        assert_eq!(
+            Message::forward(MessageId::new(), &source, &source_conversation, &target, &sender).err(),
+            Some(DomainError::MessageDeleted)
+        );
+    }
+
+    #[test]
+    fn poll_kind_and_options_go_together() {
+        let result = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "Lunch?".to_string(),
+            MessageKind::Poll,
+            &MentionDirectory::default(),
+        );
+        assert_eq!(result.err(), Some(DomainError::PollNeedsOptions));
+
+        let poll = Poll::new(vec!["yes".into(), "no".into()], false, None).unwrap();
+        let (message, event) = Message::new_poll(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "Lunch?".to_string(),
+            poll.clone(),
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        assert_eq!(message.kind(), &MessageKind::Poll);
+        assert_eq!(message.poll(), &Some(poll.clone()));
+        assert!(matches!(event, DomainEvent::MessageSent { poll: Some(sent), .. } if sent == poll));
+    }
+
+    #[test]
+    fn new_poll_must_close_in_the_future() {
+        let poll = Poll::new(vec!["yes".into(), "no".into()], false, Some(Utc::now() - Duration::minutes(1))).unwrap();
+
+        let result = Message::new_poll(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "Lunch?".to_string(),
+            poll,
+            &MentionDirectory::default(),
+        );
+
+        assert_eq!(result.err(), Some(DomainError::PollClosesInPast));
+    }
+
+    #[test]
+    fn file_messages_carry_an_attachment_and_cannot_be_edited() {
+        let sender = UserId::new();
+        let result = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            sender.clone(),
+            "https://cdn.example.com/files/report.pdf".to_string(),
+            MessageKind::File,
+            &MentionDirectory::default(),
+        );
+        assert_eq!(result.err(), Some(DomainError::FileNeedsAttachment));
+
+        let attachment = Attachment::new("report.pdf".into(), 2048, "application/pdf".into()).unwrap();
+        let result = Message::new_file(
+            MessageId::new(),
+            ConversationId::new(),
+            sender.clone(),
+            "report.pdf".to_string(),
+            attachment.clone(),
+            &MentionDirectory::default(),
+        );
+        assert_eq!(result.err(), Some(DomainError::FileNeedsUrl));
+
+        let (mut message, event) = Message::new_file(
+            MessageId::new(),
+            ConversationId::new(),
+            sender.clone(),
+            "https://cdn.example.com/files/report.pdf".to_string(),
+            attachment.clone(),
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        assert_eq!(message.kind(), &MessageKind::File);
+        assert!(matches!(event, DomainEvent::MessageSent { attachment: Some(sent), .. } if sent == attachment));
+        assert_eq!(
+            message
+                .edit(
+                    &sender,
+                    "https://cdn.example.com/files/other.pdf".into(),
+                    &MentionDirectory::default()
+                )
+                .err(),
+            Some(DomainError::FileNotEditable)
+        );
+    }
+
+    #[test]
+    fn voice_notes_need_their_measurements_and_cannot_be_edited() {
+        let sender = UserId::new();
+        let result = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            sender.clone(),
+            "https://cdn.example.com/media/note.ogg".to_string(),
+            MessageKind::Audio,
+            &MentionDirectory::default(),
+        );
+        assert_eq!(result.err(), Some(DomainError::VoiceNoteNeedsMetadata));
+
+        let voice_note = VoiceNote::new(3200, vec![128; WAVEFORM_PEAKS]).unwrap();
+        let (mut message, event) = Message::new_voice_note(
+            MessageId::new(),
+            ConversationId::new(),
+            sender.clone(),
+            "https://cdn.example.com/media/note.ogg".to_string(),
+            voice_note.clone(),
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        assert_eq!(message.kind(), &MessageKind::Audio);
+        assert!(matches!(event, DomainEvent::MessageSent { voice_note: Some(sent), .. } if sent == voice_note));
+        assert_eq!(
+            message
+                .edit(
+                    &sender,
+                    "https://cdn.example.com/media/other.ogg".into(),
+                    &MentionDirectory::default()
+                )
+                .err(),
+            Some(DomainError::VoiceNoteNotEditable)
+        );
+    }
+
+    #[test]
+    fn new_records_mentions_of_others_but_not_the_sender() {
+        let sender = UserId::new();
+        let bob = UserId::new();
+        let directory = MentionDirectory::new([
+            (Username::new("alice".into()).unwrap(), sender.clone()),
+            (Username::new("bob".into()).unwrap(), bob.clone()),
+        ]);
+
+        let (message, _) = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            sender,
+            "@alice @bob @carol lunch?".to_string(),
+            MessageKind::Text,
+            &directory,
+        )
+        .unwrap();
+
+        assert_eq!(message.mentions(), &vec![bob.clone()]);
+        match message.mention_events(&[]).as_slice() {
+            [DomainEvent::UserMentioned { user_id, .. }] => assert_eq!(user_id, &bob),
+            _ => panic!("expected one UserMentioned event"),
+        }
+        assert!(message.mention_events(&[bob]).is_empty());
+    }
+
+    #[test]
+    fn delete_clears_mentions() {
+        let sender = UserId::new();
+        let bob = UserId::new();
+        let (conversation, _, _owner) = group_message(&sender);
+        let directory = MentionDirectory::new([(Username::new("bob".into()).unwrap(), bob)]);
+        let (mut message, _) = Message::new(
+            MessageId::new(),
+            conversation.id().clone(),
+            sender.clone(),
+            "hi @bob".to_string(),
+            MessageKind::Text,
+            &directory,
+        )
+        .unwrap();
+
+        message.delete(&sender, &conversation).unwrap();
+
+        assert!(message.mentions().is_empty());
     }
 }
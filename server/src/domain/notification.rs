@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    conversation::Conversation,
+    errors::DomainError,
+    events::DomainEvent,
+    ids::{ConversationId, UserId},
+};
+
+#[derive(Debug, PartialEq, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "notify_level", rename_all = "lowercase")]
+pub enum NotifyLevel {
+    All,
+    Mentions,
+    None,
+}
+
+// what is about to be delivered to a participant
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Notification {
+    Message,
+    Mention,
+}
+
+#[derive(Debug, Getters, PartialEq)]
+pub struct NotificationSettings {
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    level: NotifyLevel,
+    #[getset(get = "pub")]
+    muted_until: Option<DateTime<Utc>>,
+}
+
+impl NotificationSettings {
+    pub fn change(
+        conversation: &Conversation,
+        user_id: UserId,
+        level: NotifyLevel,
+        muted_until: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        if !conversation.is_participant(&user_id) {
+            return Err(DomainError::NotAParticipant);
+        }
+        if muted_until.is_some_and(|until| until <= now) {
+            return Err(DomainError::MuteInPast);
+        }
+
+        let settings = Self {
+            conversation_id: conversation.id().clone(),
+            user_id,
+            level,
+            muted_until,
+        };
+        let event = DomainEvent::NotificationSettingsChanged {
+            conversation_id: settings.conversation_id.clone(),
+            user_id: settings.user_id.clone(),
+            level,
+            muted_until,
+        };
+        Ok((settings, event))
+    }
+
+    pub fn is_muted(&self, now: DateTime<Utc>) -> bool {
+        self.muted_until.is_some_and(|until| now < until)
+    }
+
+    // muting quiets the chatter but still lets mentions through; only `None` silences those too
+    pub fn allows(&self, notification: Notification, now: DateTime<Utc>) -> bool {
+        match (self.level, notification) {
+            (NotifyLevel::None, _) => false,
+            (_, Notification::Mention) => true,
+            (NotifyLevel::Mentions, Notification::Message) => false,
+            (NotifyLevel::All, Notification::Message) => !self.is_muted(now),
+        }
+    }
+
+    pub(crate) fn from_persistence(
+        conversation_id: ConversationId,
+        user_id: UserId,
+        level: NotifyLevel,
+        muted_until: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            conversation_id,
+            user_id,
+            level,
+            muted_until,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn settings(level: NotifyLevel, muted_until: Option<DateTime<Utc>>) -> NotificationSettings {
+        NotificationSettings::from_persistence(ConversationId::new(), UserId::new(), level, muted_until)
+    }
+
+    #[test]
+    fn change_requires_a_participant_and_a_future_mute() {
+        let owner = UserId::new();
+        let convo = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let now = Utc::now();
+
+        assert_eq!(
+            NotificationSettings::change(&convo, UserId::new(), NotifyLevel::All, None, now).err(),
+            Some(DomainError::NotAParticipant)
+        );
+        assert_eq!(
+            NotificationSettings::change(&convo, owner.clone(), NotifyLevel::All, Some(now), now).err(),
+            Some(DomainError::MuteInPast)
+        );
+
+        let (settings, event) = NotificationSettings::change(&convo, owner, NotifyLevel::Mentions, None, now).unwrap();
+        assert_eq!(settings.level, NotifyLevel::Mentions);
+        assert!(matches!(
+            event,
+            DomainEvent::NotificationSettingsChanged {
+                level: NotifyLevel::Mentions,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn allows_follows_the_level() {
+        let now = Utc::now();
+
+        assert!(settings(NotifyLevel::All, None).allows(Notification::Message, now));
+        assert!(!settings(NotifyLevel::Mentions, None).allows(Notification::Message, now));
+        assert!(settings(NotifyLevel::Mentions, None).allows(Notification::Mention, now));
+        assert!(!settings(NotifyLevel::None, None).allows(Notification::Mention, now));
+    }
+
+    #[test]
+    fn muting_silences_messages_until_it_lapses_but_not_mentions() {
+        let now = Utc::now();
+        let muted = settings(NotifyLevel::All, Some(now + Duration::hours(1)));
+
+        assert!(!muted.allows(Notification::Message, now));
+        assert!(muted.allows(Notification::Mention, now));
+        assert!(muted.allows(Notification::Message, now + Duration::hours(2)));
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    conversation::Conversation,
+    errors::DomainError,
+    events::DomainEvent,
+    ids::{ConversationId, MessageId, UserId},
+    message::Message,
+};
+
+#[derive(Debug, Getters, PartialEq)]
+pub struct Pin {
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    message_id: MessageId,
+    #[getset(get = "pub")]
+    pinned_by: UserId,
+    #[getset(get = "pub")]
+    pinned_at: DateTime<Utc>,
+}
+
+impl Pin {
+    // any participant may pin or unpin, in a group as much as in a direct chat
+    pub fn add(conversation: &Conversation, message: &Message, actor: UserId) -> Result<(Self, DomainEvent), DomainError> {
+        if !conversation.is_participant(&actor) {
+            return Err(DomainError::NotAParticipant);
+        }
+        if message.conversation_id() != conversation.id() {
+            return Err(DomainError::MessageNotInConversation);
+        }
+        if message.is_deleted() {
+            return Err(DomainError::MessageDeleted);
+        }
+
+        let pin = Self {
+            conversation_id: conversation.id().clone(),
+            message_id: message.id().clone(),
+            pinned_by: actor,
+            pinned_at: Utc::now(),
+        };
+        let event = DomainEvent::MessagePinned {
+            message_id: pin.message_id.clone(),
+            conversation_id: pin.conversation_id.clone(),
+            pinned_by: pin.pinned_by.clone(),
+            pinned_at: pin.pinned_at,
+        };
+        Ok((pin, event))
+    }
+
+    pub fn remove(&self, conversation: &Conversation, actor: &UserId) -> Result<DomainEvent, DomainError> {
+        if !conversation.is_participant(actor) {
+            return Err(DomainError::NotAParticipant);
+        }
+        Ok(DomainEvent::MessageUnpinned {
+            message_id: self.message_id.clone(),
+            conversation_id: self.conversation_id.clone(),
+            unpinned_by: actor.clone(),
+        })
+    }
+
+    pub(crate) fn from_persistence(
+        conversation_id: ConversationId,
+        message_id: MessageId,
+        pinned_by: UserId,
+        pinned_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            conversation_id,
+            message_id,
+            pinned_by,
+            pinned_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::MessageKind;
+
+    fn message_in(conversation_id: &ConversationId, sender: &UserId) -> Message {
+        let (message, _) = Message::new(
+            MessageId::new(),
+            conversation_id.clone(),
+            sender.clone(),
+            "see the doc".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        message
+    }
+
+    #[test]
+    fn pin_records_actor_and_emits_event() {
+        let owner = UserId::new();
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let message = message_in(conversation.id(), &owner);
+
+        let (pin, event) = Pin::add(&conversation, &message, owner.clone()).unwrap();
+
+        assert_eq!(pin.message_id(), message.id());
+        assert_eq!(pin.pinned_by(), &owner);
+        match event {
+            DomainEvent::MessagePinned { message_id, pinned_at, .. } => {
+                assert_eq!(&message_id, message.id());
+                assert_eq!(&pinned_at, pin.pinned_at());
+            }
+            _ => panic!("expected MessagePinned event"),
+        }
+    }
+
+    #[test]
+    fn pin_is_open_to_every_participant_but_nobody_else() {
+        let owner = UserId::new();
+        let member = UserId::new();
+        let mut conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        conversation.add_participant(&owner, member.clone()).unwrap();
+        let message = message_in(conversation.id(), &owner);
+
+        assert_eq!(
+            Pin::add(&conversation, &message, UserId::new()).err(),
+            Some(DomainError::NotAParticipant)
+        );
+        let (pin, _) = Pin::add(&conversation, &message, member.clone()).unwrap();
+        assert!(pin.remove(&conversation, &member).is_ok());
+    }
+
+    #[test]
+    fn pin_rejects_message_from_another_conversation() {
+        let owner = UserId::new();
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let message = message_in(&ConversationId::new(), &owner);
+
+        let result = Pin::add(&conversation, &message, owner);
+
+        assert_eq!(result.err(), Some(DomainError::MessageNotInConversation));
+    }
+
+    #[test]
+    fn pin_rejects_deleted_message() {
+        let owner = UserId::new();
+        let conversation = Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap();
+        let mut message = message_in(conversation.id(), &owner);
+        message.delete(&owner, &conversation).unwrap();
+
+        let result = Pin::add(&conversation, &message, owner);
+
+        assert_eq!(result.err(), Some(DomainError::MessageDeleted));
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    errors::DomainError,
+    events::DomainEvent,
+    ids::{ConversationId, MessageId, UserId},
+    message::Message,
+};
+
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 10;
+pub const MAX_POLL_OPTION_LENGTH: usize = 100;
+
+// the question is the message content; a poll's options and rules are fixed once it's sent
+#[derive(Debug, Clone, Getters, PartialEq)]
+pub struct Poll {
+    #[getset(get = "pub")]
+    options: Vec<String>,
+    #[getset(get = "pub")]
+    multiple_choice: bool,
+    #[getset(get = "pub")]
+    closes_at: Option<DateTime<Utc>>,
+}
+
+impl Poll {
+    pub fn new(options: Vec<String>, multiple_choice: bool, closes_at: Option<DateTime<Utc>>) -> Result<Self, DomainError> {
+        let options: Vec<String> = options.into_iter().map(|option| option.trim().to_string()).collect();
+        if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&options.len())
+            || options
+                .iter()
+                .any(|option| option.is_empty() || option.chars().count() > MAX_POLL_OPTION_LENGTH)
+        {
+            return Err(DomainError::InvalidPollOptions);
+        }
+        if options.iter().enumerate().any(|(i, option)| options[..i].contains(option)) {
+            return Err(DomainError::InvalidPollOptions);
+        }
+        Ok(Self {
+            options,
+            multiple_choice,
+            closes_at,
+        })
+    }
+
+    pub fn is_closed(&self, now: DateTime<Utc>) -> bool {
+        self.closes_at.is_some_and(|at| at <= now)
+    }
+
+    pub(crate) fn from_persistence(options: Vec<String>, multiple_choice: bool, closes_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            options,
+            multiple_choice,
+            closes_at,
+        }
+    }
+}
+
+// one voter's current choices in a poll, as indexes into its options. like reactions it is its
+// own aggregate, so votes never contend with edits to the poll message or with each other
+#[derive(Debug, Getters, PartialEq)]
+pub struct PollVote {
+    #[getset(get = "pub")]
+    message_id: MessageId,
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    choices: Vec<usize>,
+    #[getset(get = "pub")]
+    voted_at: DateTime<Utc>,
+}
+
+impl PollVote {
+    // voting again replaces the earlier choices
+    pub fn cast(
+        message: &Message,
+        user_id: UserId,
+        mut choices: Vec<usize>,
+        now: DateTime<Utc>,
+    ) -> Result<(Self, DomainEvent), DomainError> {
+        let poll = open_poll(message, now)?;
+        choices.sort_unstable();
+        choices.dedup();
+        if choices.is_empty()
+            || choices.iter().any(|&choice| choice >= poll.options.len())
+            || (!poll.multiple_choice && choices.len() > 1)
+        {
+            return Err(DomainError::InvalidPollChoice);
+        }
+
+        let vote = Self {
+            message_id: message.id().clone(),
+            conversation_id: message.conversation_id().clone(),
+            user_id,
+            choices,
+            voted_at: now,
+        };
+        let event = DomainEvent::PollVoteCast {
+            message_id: vote.message_id.clone(),
+            conversation_id: vote.conversation_id.clone(),
+            user_id: vote.user_id.clone(),
+            choices: vote.choices.clone(),
+        };
+        Ok((vote, event))
+    }
+
+    // results are final once the poll closes, so a vote can't be taken back after that either
+    pub fn retract(&self, message: &Message, now: DateTime<Utc>) -> Result<DomainEvent, DomainError> {
+        open_poll(message, now)?;
+        Ok(DomainEvent::PollVoteRetracted {
+            message_id: self.message_id.clone(),
+            conversation_id: self.conversation_id.clone(),
+            user_id: self.user_id.clone(),
+        })
+    }
+
+    pub(crate) fn from_persistence(
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        user_id: UserId,
+        choices: Vec<usize>,
+        voted_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            message_id,
+            conversation_id,
+            user_id,
+            choices,
+            voted_at,
+        }
+    }
+}
+
+fn open_poll(message: &Message, now: DateTime<Utc>) -> Result<&Poll, DomainError> {
+    let Some(poll) = message.poll() else {
+        return Err(DomainError::NotAPoll);
+    };
+    if message.is_deleted() {
+        return Err(DomainError::MessageDeleted);
+    }
+    if poll.is_closed(now) {
+        return Err(DomainError::PollClosed);
+    }
+    Ok(poll)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::MessageKind;
+
+    fn options(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn poll_message(multiple_choice: bool, closes_at: Option<DateTime<Utc>>) -> Message {
+        let poll = Poll::new(options(&["tea", "coffee", "water"]), multiple_choice, closes_at).unwrap();
+        Message::new_poll(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "What should we drink?".into(),
+            poll,
+            &MentionDirectory::default(),
+        )
+        .unwrap()
+        .0
+    }
+
+    #[test]
+    fn new_needs_distinct_non_empty_options() {
+        assert_eq!(
+            Poll::new(options(&["only"]), false, None).err(),
+            Some(DomainError::InvalidPollOptions)
+        );
+        assert_eq!(
+            Poll::new(options(&["yes", "  "]), false, None).err(),
+            Some(DomainError::InvalidPollOptions)
+        );
+        assert_eq!(
+            Poll::new(options(&["yes", " yes"]), false, None).err(),
+            Some(DomainError::InvalidPollOptions)
+        );
+        assert_eq!(
+            Poll::new(vec!["x".repeat(MAX_POLL_OPTION_LENGTH + 1), "y".into()], false, None).err(),
+            Some(DomainError::InvalidPollOptions)
+        );
+
+        let poll = Poll::new(options(&[" yes ", "no"]), false, None).unwrap();
+        assert_eq!(poll.options(), &options(&["yes", "no"]));
+    }
+
+    #[test]
+    fn single_choice_polls_take_exactly_one_option() {
+        let message = poll_message(false, None);
+        let now = Utc::now();
+
+        assert_eq!(
+            PollVote::cast(&message, UserId::new(), vec![0, 1], now).err(),
+            Some(DomainError::InvalidPollChoice)
+        );
+        assert_eq!(
+            PollVote::cast(&message, UserId::new(), vec![], now).err(),
+            Some(DomainError::InvalidPollChoice)
+        );
+        assert_eq!(
+            PollVote::cast(&message, UserId::new(), vec![3], now).err(),
+            Some(DomainError::InvalidPollChoice)
+        );
+
+        let (vote, _) = PollVote::cast(&message, UserId::new(), vec![1, 1], now).unwrap();
+        assert_eq!(vote.choices(), &vec![1]);
+    }
+
+    #[test]
+    fn multiple_choice_polls_keep_sorted_choices() {
+        let message = poll_message(true, None);
+
+        let (vote, event) = PollVote::cast(&message, UserId::new(), vec![2, 0], Utc::now()).unwrap();
+
+        assert_eq!(vote.choices(), &vec![0, 2]);
+        assert!(matches!(event, DomainEvent::PollVoteCast { choices, .. } if choices == vec![0, 2]));
+    }
+
+    #[test]
+    fn closed_polls_take_no_votes_or_retractions() {
+        let now = Utc::now();
+        let message = poll_message(false, Some(now + Duration::hours(1)));
+        let (vote, _) = PollVote::cast(&message, UserId::new(), vec![0], now).unwrap();
+
+        let later = now + Duration::hours(2);
+        assert_eq!(
+            PollVote::cast(&message, UserId::new(), vec![0], later).err(),
+            Some(DomainError::PollClosed)
+        );
+        assert_eq!(vote.retract(&message, later).err(), Some(DomainError::PollClosed));
+        assert!(vote.retract(&message, now).is_ok());
+    }
+
+    #[test]
+    fn only_polls_take_votes() {
+        let (message, _) = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "not a poll".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            PollVote::cast(&message, UserId::new(), vec![0], Utc::now()).err(),
+            Some(DomainError::NotAPoll)
+        );
+    }
+}
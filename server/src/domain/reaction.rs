@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    errors::DomainError,
+    events::DomainEvent,
+    ids::{ConversationId, MessageId, UserId},
+    message::Message,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Emoji(String);
+
+impl Emoji {
+    // exactly one emoji from the Unicode list, skin tones and ZWJ sequences included. it's kept in its
+    // fully qualified form, so ❤ and ❤️ count as the same reaction
+    pub fn new(value: String) -> Result<Self, DomainError> {
+        let emoji = emojis::get(&value).ok_or(DomainError::InvalidEmoji)?;
+        Ok(Self(emoji.as_str().to_string()))
+    }
+
+    pub(crate) fn from_persistence(value: String) -> Self {
+        Self(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+// its own aggregate rather than part of Message: reacting never touches the message row, so
+// concurrent reactions don't contend with edits or with each other
+#[derive(Debug, Getters, PartialEq)]
+pub struct Reaction {
+    #[getset(get = "pub")]
+    message_id: MessageId,
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    emoji: Emoji,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+}
+
+impl Reaction {
+    pub fn add(message: &Message, user_id: UserId, emoji: Emoji) -> (Self, DomainEvent) {
+        let reaction = Self {
+            message_id: message.id().clone(),
+            conversation_id: message.conversation_id().clone(),
+            user_id,
+            emoji,
+            created_at: Utc::now(),
+        };
+        let event = DomainEvent::ReactionAdded {
+            message_id: reaction.message_id.clone(),
+            conversation_id: reaction.conversation_id.clone(),
+            user_id: reaction.user_id.clone(),
+            emoji: reaction.emoji.as_str().to_string(),
+            created_at: reaction.created_at,
+        };
+        (reaction, event)
+    }
+
+    pub fn remove(&self) -> DomainEvent {
+        DomainEvent::ReactionRemoved {
+            message_id: self.message_id.clone(),
+            conversation_id: self.conversation_id.clone(),
+            user_id: self.user_id.clone(),
+            emoji: self.emoji.as_str().to_string(),
+        }
+    }
+
+    pub(crate) fn from_persistence(
+        message_id: MessageId,
+        conversation_id: ConversationId,
+        user_id: UserId,
+        emoji: Emoji,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            message_id,
+            conversation_id,
+            user_id,
+            emoji,
+            created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::mention::MentionDirectory;
+    use crate::domain::message::MessageKind;
+
+    #[test]
+    fn emoji_accepts_exactly_one_emoji() {
+        for value in ["", "👍 👍", "👍👍", "lol", "<script>", "A"] {
+            assert_eq!(Emoji::new(value.into()), Err(DomainError::InvalidEmoji), "{value:?}");
+        }
+        assert_eq!(Emoji::new("👩🏽‍🤝‍👨🏻".into()).unwrap().as_str(), "👩🏽‍🤝‍👨🏻");
+        assert_eq!(Emoji::new("🇳🇱".into()).unwrap().as_str(), "🇳🇱");
+    }
+
+    #[test]
+    fn emoji_is_stored_fully_qualified() {
+        assert_eq!(Emoji::new("\u{2764}".into()), Emoji::new("\u{2764}\u{fe0f}".into()));
+        assert_eq!(Emoji::new("\u{2764}".into()).unwrap().as_str(), "\u{2764}\u{fe0f}");
+    }
+
+    #[test]
+    fn add_copies_message_location_and_emits_event() {
+        let (message, _) = Message::new(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            "hello".into(),
+            MessageKind::Text,
+            &MentionDirectory::default(),
+        )
+        .unwrap();
+        let reactor = UserId::new();
+
+        let (reaction, event) = Reaction::add(&message, reactor.clone(), Emoji::new("🎉".into()).unwrap());
+
+        assert_eq!(reaction.message_id(), message.id());
+        assert_eq!(reaction.conversation_id(), message.conversation_id());
+        match event {
+            DomainEvent::ReactionAdded {
+                message_id,
+                user_id,
+                emoji,
+                ..
+            } => {
+                assert_eq!(&message_id, message.id());
+                assert_eq!(user_id, reactor);
+                assert_eq!(emoji, "🎉");
+            }
+            _ => panic!("expected ReactionAdded event"),
+        }
+    }
+
+    #[test]
+    fn remove_emits_event_for_the_same_reaction() {
+        let reaction = Reaction::from_persistence(
+            MessageId::new(),
+            ConversationId::new(),
+            UserId::new(),
+            Emoji::new("👍".into()).unwrap(),
+            Utc::now(),
+        );
+
+        match reaction.remove() {
+            DomainEvent::ReactionRemoved {
+                message_id,
+                conversation_id,
+                user_id,
+                emoji,
+            } => {
+                assert_eq!(&message_id, reaction.message_id());
+                assert_eq!(&conversation_id, reaction.conversation_id());
+                assert_eq!(&user_id, reaction.user_id());
+                assert_eq!(emoji, "👍");
+            }
+            _ => panic!("expected ReactionRemoved event"),
+        }
+    }
+}
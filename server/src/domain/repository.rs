@@ -1,16 +1,33 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 
+use crate::domain::attachment::Attachment;
+use crate::domain::block::Block;
 use crate::domain::conversation::Conversation;
+use crate::domain::credentials::Credentials;
 use crate::domain::events::DomainEvent;
-use crate::domain::ids::{ConversationId, MessageId, UserId};
-use crate::domain::message::Message;
-use crate::domain::user::User;
+use crate::domain::ids::{ConversationId, InviteId, LoginChallengeId, MessageId, ScheduledMessageId, SessionId, UserId};
+use crate::domain::invitation::Invitation;
+use crate::domain::invite::Invite;
+use crate::domain::list_entry::ListEntry;
+use crate::domain::message::{Message, MessageRevision};
+use crate::domain::notification::NotificationSettings;
+use crate::domain::pin::Pin;
+use crate::domain::poll::PollVote;
+use crate::domain::reaction::{Emoji, Reaction};
+use crate::domain::scheduled_message::ScheduledMessage;
+use crate::domain::session::{LoginChallenge, Session};
+use crate::domain::two_factor::{TotpEnrollment, TwoFactorUse};
+use crate::domain::user::{User, Username};
+use crate::domain::voice_note::VoiceNote;
 
 #[derive(Debug, Error)]
 pub enum RepoError {
     #[error(transparent)]
     Db(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
 }
 
 #[derive(Debug, Error)]
@@ -22,20 +39,174 @@ pub enum PublishError {
 #[async_trait]
 pub trait ConversationRepository: Send + Sync {
     async fn find_by_id(&self, id: &ConversationId) -> Result<Option<Conversation>, RepoError>;
+    async fn find_direct_between(&self, a: &UserId, b: &UserId) -> Result<Option<Conversation>, RepoError>;
     async fn save(&self, conversation: &Conversation) -> Result<(), RepoError>;
+    // a new group together with the invitations sent out with it, all or nothing
+    async fn create_group(&self, conversation: &Conversation, invitations: &[Invitation]) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    async fn find(&self, conversation_id: &ConversationId, user_id: &UserId) -> Result<Option<Invitation>, RepoError>;
+    async fn delete(&self, invitation: &Invitation) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait InviteRepository: Send + Sync {
+    async fn find_by_id(&self, id: &InviteId) -> Result<Option<Invite>, RepoError>;
+    async fn find_by_token(&self, token: &str) -> Result<Option<Invite>, RepoError>;
+    // leaves the use count alone, see record_use
+    async fn save(&self, invite: &Invite) -> Result<(), RepoError>;
+    // counts one use and saves the conversation the invite let someone into, both or neither; false,
+    // with nothing written, if the invite has been revoked, run out or used up in the meantime
+    async fn record_use(&self, invite: &Invite, conversation: &Conversation, now: DateTime<Utc>) -> Result<bool, RepoError>;
+}
+
+#[async_trait]
+pub trait ListEntryRepository: Send + Sync {
+    async fn find(&self, conversation_id: &ConversationId, user_id: &UserId) -> Result<Option<ListEntry>, RepoError>;
+    async fn save(&self, entry: &ListEntry) -> Result<(), RepoError>;
 }
 
 #[async_trait]
 pub trait MessageRepository: Send + Sync {
     async fn find_by_id(&self, id: &MessageId) -> Result<Option<Message>, RepoError>;
     async fn save(&self, message: &Message) -> Result<(), RepoError>;
+    // all of them or none, e.g. one message forwarded to several conversations
+    async fn save_all(&self, messages: &[Message]) -> Result<(), RepoError>;
+    // writes an edit together with the revision it replaced; false, with nothing written, if the
+    // message was edited or deleted since it was read
+    async fn save_edit(&self, message: &Message, revision: &MessageRevision) -> Result<bool, RepoError>;
+    // messages whose disappearing timer has run out, oldest first
+    async fn find_expired(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<Message>, RepoError>;
+    // removes the row outright; reactions, mentions, revisions and pins go with it, thread replies are
+    // kept and become top-level messages
+    async fn delete(&self, id: &MessageId) -> Result<(), RepoError>;
+    // whether another message, e.g. a forward, still shows the same uploaded image, file or voice note
+    async fn shares_media(&self, message: &Message) -> Result<bool, RepoError>;
+}
+
+// files uploaded to this server and served back under /media
+#[async_trait]
+pub trait MediaStore: Send + Sync {
+    // urls that don't point at a stored file are ignored
+    async fn delete(&self, url: &str) -> Result<(), RepoError>;
+}
+
+// what the upload endpoints measured about the files they stored, found again by the url they handed
+// back. urls that aren't one of our uploads of that kind find nothing
+#[async_trait]
+pub trait UploadRepository: Send + Sync {
+    async fn save_file(&self, url: &str, attachment: &Attachment) -> Result<(), RepoError>;
+    async fn find_file(&self, url: &str) -> Result<Option<Attachment>, RepoError>;
+    async fn save_voice_note(&self, url: &str, voice_note: &VoiceNote) -> Result<(), RepoError>;
+    async fn find_voice_note(&self, url: &str) -> Result<Option<VoiceNote>, RepoError>;
+}
+
+#[async_trait]
+pub trait NotificationSettingsRepository: Send + Sync {
+    async fn save(&self, settings: &NotificationSettings) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait PinRepository: Send + Sync {
+    async fn find(&self, conversation_id: &ConversationId, message_id: &MessageId) -> Result<Option<Pin>, RepoError>;
+    // false, keeping the original pin, if the message was already pinned
+    async fn save(&self, pin: &Pin) -> Result<bool, RepoError>;
+    async fn delete(&self, pin: &Pin) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait ReactionRepository: Send + Sync {
+    async fn find(&self, message_id: &MessageId, user_id: &UserId, emoji: &Emoji) -> Result<Option<Reaction>, RepoError>;
+    async fn save(&self, reaction: &Reaction) -> Result<(), RepoError>;
+    async fn delete(&self, reaction: &Reaction) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait PollVoteRepository: Send + Sync {
+    async fn find(&self, message_id: &MessageId, user_id: &UserId) -> Result<Option<PollVote>, RepoError>;
+    // replaces whatever the user picked before
+    async fn save(&self, vote: &PollVote) -> Result<(), RepoError>;
+    async fn delete(&self, vote: &PollVote) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait ScheduledMessageRepository: Send + Sync {
+    async fn find_by_id(&self, id: &ScheduledMessageId) -> Result<Option<ScheduledMessage>, RepoError>;
+    async fn add(&self, scheduled: &ScheduledMessage) -> Result<(), RepoError>;
+    // pending messages whose send time has passed, plus ones claimed before stale_before that never
+    // got recorded, oldest first. they move to sending and count an attempt in the same step so no
+    // other run can pick them up
+    async fn claim_due(&self, now: DateTime<Utc>, stale_before: DateTime<Utc>, limit: i64)
+    -> Result<Vec<ScheduledMessage>, RepoError>;
+    // records how a claimed message went; anything no longer sending is left alone
+    async fn save(&self, scheduled: &ScheduledMessage) -> Result<(), RepoError>;
+    // false if the dispatcher claimed it first
+    async fn cancel(&self, scheduled: &ScheduledMessage) -> Result<bool, RepoError>;
 }
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError>;
+    async fn find_by_username(&self, username: &Username) -> Result<Option<User>, RepoError>;
     async fn find_all(&self) -> Result<Vec<User>, RepoError>;
     async fn save(&self, user: &User) -> Result<(), RepoError>;
+    // a new account, the user and its credentials together; false, with nothing written, if the
+    // username is taken
+    async fn create(&self, user: &User, credentials: &Credentials) -> Result<bool, RepoError>;
+
+    // ids that don't exist are skipped; adapters should override this with a single query
+    async fn find_many(&self, ids: &[UserId]) -> Result<Vec<User>, RepoError> {
+        let mut users = Vec::with_capacity(ids.len());
+        for id in ids {
+            users.extend(self.find_by_id(id).await?);
+        }
+        Ok(users)
+    }
+}
+
+#[async_trait]
+pub trait BlockRepository: Send + Sync {
+    async fn find_by_blocker(&self, blocker_id: &UserId) -> Result<Vec<Block>, RepoError>;
+    // whether either user has blocked the other
+    async fn is_blocked_between(&self, a: &UserId, b: &UserId) -> Result<bool, RepoError>;
+    async fn save(&self, block: &Block) -> Result<(), RepoError>;
+    async fn delete(&self, blocker_id: &UserId, blocked_id: &UserId) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait CredentialRepository: Send + Sync {
+    async fn find_by_user_id(&self, user_id: &UserId) -> Result<Option<Credentials>, RepoError>;
+    async fn save(&self, credentials: &Credentials) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    async fn find_by_token_hash(&self, token_hash: &[u8]) -> Result<Option<Session>, RepoError>;
+    async fn save(&self, session: &Session) -> Result<(), RepoError>;
+    async fn delete(&self, id: &SessionId) -> Result<(), RepoError>;
+    async fn delete_all_for_user_except(&self, user_id: &UserId, keep: &SessionId) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait TotpRepository: Send + Sync {
+    async fn find_by_user_id(&self, user_id: &UserId) -> Result<Option<TotpEnrollment>, RepoError>;
+    async fn save(&self, enrollment: &TotpEnrollment) -> Result<(), RepoError>;
+    // spends the step or recovery code authenticate accepted; false if another request spent it first
+    async fn redeem(&self, user_id: &UserId, used: &TwoFactorUse, now: DateTime<Utc>) -> Result<bool, RepoError>;
+    async fn delete(&self, user_id: &UserId) -> Result<(), RepoError>;
+}
+
+#[async_trait]
+pub trait LoginChallengeRepository: Send + Sync {
+    async fn find_by_token_hash(&self, token_hash: &[u8]) -> Result<Option<LoginChallenge>, RepoError>;
+    async fn save(&self, challenge: &LoginChallenge) -> Result<(), RepoError>;
+    // counts an attempt before the code is checked, so parallel guesses can't share one; false once
+    // the attempts are used up or the challenge has expired
+    async fn take_attempt(&self, id: &LoginChallengeId, now: DateTime<Utc>) -> Result<bool, RepoError>;
+    // false if another request already used the challenge
+    async fn consume(&self, id: &LoginChallengeId) -> Result<bool, RepoError>;
 }
 
 #[async_trait]
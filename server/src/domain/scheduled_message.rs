@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    conversation::Conversation,
+    errors::DomainError,
+    ids::{ConversationId, MessageId, ScheduledMessageId, UserId},
+    message::{MessageKind, looks_like_url},
+};
+
+// claims a message gets before it is given up on, counting ones a crash cut short
+pub const MAX_SEND_ATTEMPTS: i32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "scheduled_status", rename_all = "lowercase")]
+pub enum ScheduledStatus {
+    Pending,
+    // claimed by the dispatcher and on its way out
+    Sending,
+    Sent,
+    Failed,
+    Cancelled,
+}
+
+// a message held back until send_at; it only becomes a real message when the dispatcher sends it
+#[derive(Debug, Getters, PartialEq)]
+pub struct ScheduledMessage {
+    #[getset(get = "pub")]
+    id: ScheduledMessageId,
+    #[getset(get = "pub")]
+    conversation_id: ConversationId,
+    #[getset(get = "pub")]
+    sender_id: UserId,
+    #[getset(get = "pub")]
+    content: String,
+    #[getset(get = "pub")]
+    kind: MessageKind,
+    #[getset(get = "pub")]
+    reply_to: Option<MessageId>,
+    #[getset(get = "pub")]
+    send_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    status: ScheduledStatus,
+    #[getset(get = "pub")]
+    message_id: Option<MessageId>,
+    #[getset(get = "pub")]
+    failure: Option<String>,
+    #[getset(get = "pub")]
+    attempts: i32,
+}
+
+impl ScheduledMessage {
+    // membership is checked again when the message goes out, since the sender may have left by then
+    #[allow(clippy::too_many_arguments)]
+    pub fn schedule(
+        id: ScheduledMessageId,
+        conversation: &Conversation,
+        sender_id: UserId,
+        content: String,
+        kind: MessageKind,
+        reply_to: Option<MessageId>,
+        send_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Self, DomainError> {
+        if !conversation.is_participant(&sender_id) {
+            return Err(DomainError::NotAParticipant);
+        }
+        if content.trim().is_empty() {
+            return Err(DomainError::EmptyMessage);
+        }
+        if matches!(kind, MessageKind::Image) && !looks_like_url(&content) {
+            return Err(DomainError::ImageNeedsUrl);
+        }
+        if send_at <= now {
+            return Err(DomainError::ScheduleInPast);
+        }
+
+        Ok(Self {
+            id,
+            conversation_id: conversation.id().clone(),
+            sender_id,
+            content,
+            kind,
+            reply_to,
+            send_at,
+            created_at: now,
+            status: ScheduledStatus::Pending,
+            message_id: None,
+            failure: None,
+            attempts: 0,
+        })
+    }
+
+    // other users' schedules are reported as missing rather than forbidden so they can't be probed,
+    // and so is anything already going out
+    pub fn cancel(&mut self, actor: &UserId) -> Result<(), DomainError> {
+        if &self.sender_id != actor || !matches!(self.status, ScheduledStatus::Pending | ScheduledStatus::Failed) {
+            return Err(DomainError::ScheduledMessageNotFound);
+        }
+        self.status = ScheduledStatus::Cancelled;
+        Ok(())
+    }
+
+    // hands a claimed message back to the queue so the next run tries again, unless it has used up
+    // its attempts
+    pub fn retry_later(&mut self, reason: String) {
+        if self.attempts >= MAX_SEND_ATTEMPTS {
+            self.mark_failed(reason);
+        } else {
+            self.status = ScheduledStatus::Pending;
+        }
+    }
+
+    // a claim that never got recorded means the dispatcher died part way; past the limit the message
+    // is failed without another try in case it is what keeps taking the dispatcher down
+    pub fn out_of_attempts(&self) -> bool {
+        self.attempts > MAX_SEND_ATTEMPTS
+    }
+
+    pub fn mark_sent(&mut self, message_id: MessageId) {
+        self.status = ScheduledStatus::Sent;
+        self.message_id = Some(message_id);
+        self.failure = None;
+    }
+
+    pub fn mark_failed(&mut self, reason: String) {
+        self.status = ScheduledStatus::Failed;
+        self.failure = Some(reason);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_persistence(
+        id: ScheduledMessageId,
+        conversation_id: ConversationId,
+        sender_id: UserId,
+        content: String,
+        kind: MessageKind,
+        reply_to: Option<MessageId>,
+        send_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+        status: ScheduledStatus,
+        message_id: Option<MessageId>,
+        failure: Option<String>,
+        attempts: i32,
+    ) -> Self {
+        Self {
+            id,
+            conversation_id,
+            sender_id,
+            content,
+            kind,
+            reply_to,
+            send_at,
+            created_at,
+            status,
+            message_id,
+            failure,
+            attempts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn group(owner: &UserId) -> Conversation {
+        Conversation::new_group(ConversationId::new(), "Group".into(), owner.clone()).unwrap()
+    }
+
+    fn schedule(
+        conversation: &Conversation,
+        sender: &UserId,
+        send_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduledMessage, DomainError> {
+        ScheduledMessage::schedule(
+            ScheduledMessageId::new(),
+            conversation,
+            sender.clone(),
+            "later".into(),
+            MessageKind::Text,
+            None,
+            send_at,
+            now,
+        )
+    }
+
+    #[test]
+    fn schedule_requires_a_participant_and_a_future_time() {
+        let owner = UserId::new();
+        let convo = group(&owner);
+        let now = Utc::now();
+
+        assert_eq!(
+            schedule(&convo, &UserId::new(), now + Duration::hours(1), now).err(),
+            Some(DomainError::NotAParticipant)
+        );
+        assert_eq!(schedule(&convo, &owner, now, now).err(), Some(DomainError::ScheduleInPast));
+
+        let scheduled = schedule(&convo, &owner, now + Duration::hours(1), now).unwrap();
+        assert_eq!(*scheduled.status(), ScheduledStatus::Pending);
+        assert_eq!(*scheduled.send_at(), now + Duration::hours(1));
+    }
+
+    #[test]
+    fn schedule_rejects_empty_content() {
+        let owner = UserId::new();
+        let convo = group(&owner);
+        let now = Utc::now();
+
+        let result = ScheduledMessage::schedule(
+            ScheduledMessageId::new(),
+            &convo,
+            owner,
+            "   ".into(),
+            MessageKind::Text,
+            None,
+            now + Duration::minutes(5),
+            now,
+        );
+
+        assert_eq!(result.err(), Some(DomainError::EmptyMessage));
+    }
+
+    #[test]
+    fn only_the_sender_can_cancel_before_it_is_sent() {
+        let owner = UserId::new();
+        let convo = group(&owner);
+        let now = Utc::now();
+        let mut scheduled = schedule(&convo, &owner, now + Duration::hours(1), now).unwrap();
+
+        assert_eq!(scheduled.cancel(&UserId::new()).err(), Some(DomainError::ScheduledMessageNotFound));
+        assert!(scheduled.cancel(&owner).is_ok());
+        assert_eq!(*scheduled.status(), ScheduledStatus::Cancelled);
+
+        let mut sent = schedule(&convo, &owner, now + Duration::hours(1), now).unwrap();
+        sent.mark_sent(MessageId::new());
+        assert_eq!(sent.cancel(&owner).err(), Some(DomainError::ScheduledMessageNotFound));
+        assert_eq!(*sent.status(), ScheduledStatus::Sent);
+    }
+
+    #[test]
+    fn retry_later_gives_up_once_the_attempts_are_used() {
+        let owner = UserId::new();
+        let convo = group(&owner);
+        let now = Utc::now();
+        let scheduled = schedule(&convo, &owner, now + Duration::hours(1), now).unwrap();
+        let claimed = |attempts| {
+            ScheduledMessage::from_persistence(
+                scheduled.id().clone(),
+                scheduled.conversation_id().clone(),
+                owner.clone(),
+                "later".into(),
+                MessageKind::Text,
+                None,
+                *scheduled.send_at(),
+                now,
+                ScheduledStatus::Sending,
+                None,
+                None,
+                attempts,
+            )
+        };
+
+        let mut early = claimed(1);
+        early.retry_later("database unavailable".into());
+        assert_eq!(*early.status(), ScheduledStatus::Pending);
+
+        let mut last = claimed(MAX_SEND_ATTEMPTS);
+        last.retry_later("database unavailable".into());
+        assert_eq!(*last.status(), ScheduledStatus::Failed);
+        assert_eq!(last.failure().as_deref(), Some("database unavailable"));
+
+        assert!(!last.out_of_attempts());
+        assert!(claimed(MAX_SEND_ATTEMPTS + 1).out_of_attempts());
+    }
+}
@@ -0,0 +1,216 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, Utc};
+use getset::Getters;
+use sha2::{Digest, Sha256};
+
+use crate::domain::ids::{LoginChallengeId, SessionId, UserId};
+
+pub const SESSION_TTL_DAYS: i64 = 30;
+pub const LOGIN_CHALLENGE_TTL_MINUTES: i64 = 5;
+pub const LOGIN_CHALLENGE_MAX_ATTEMPTS: i32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionToken(String);
+
+impl SessionToken {
+    pub fn generate() -> Self {
+        let bytes: [u8; 32] = rand::random();
+        Self(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    pub fn from_client(value: String) -> Self {
+        Self(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Sha256::digest(self.0.as_bytes()).to_vec()
+    }
+}
+
+#[derive(Debug, Getters, PartialEq)]
+pub struct Session {
+    #[getset(get = "pub")]
+    id: SessionId,
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    token_hash: Vec<u8>,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    pub fn issue(user_id: UserId, now: DateTime<Utc>) -> (Self, SessionToken) {
+        let token = SessionToken::generate();
+        let session = Self {
+            id: SessionId::new(),
+            user_id,
+            token_hash: token.hash(),
+            created_at: now,
+            expires_at: now + Duration::days(SESSION_TTL_DAYS),
+        };
+        (session, token)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+
+    pub(crate) fn from_persistence(
+        id: SessionId,
+        user_id: UserId,
+        token_hash: Vec<u8>,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            token_hash,
+            created_at,
+            expires_at,
+        }
+    }
+}
+
+// issued after a correct password when the user has two-factor enabled; trading it plus a
+// valid code for a real session is the second login step
+#[derive(Debug, Getters, PartialEq)]
+pub struct LoginChallenge {
+    #[getset(get = "pub")]
+    id: LoginChallengeId,
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    token_hash: Vec<u8>,
+    #[getset(get = "pub")]
+    expires_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    failed_attempts: i32,
+}
+
+impl LoginChallenge {
+    pub fn issue(user_id: UserId, now: DateTime<Utc>) -> (Self, SessionToken) {
+        let token = SessionToken::generate();
+        let challenge = Self {
+            id: LoginChallengeId::new(),
+            user_id,
+            token_hash: token.hash(),
+            expires_at: now + Duration::minutes(LOGIN_CHALLENGE_TTL_MINUTES),
+            failed_attempts: 0,
+        };
+        (challenge, token)
+    }
+
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        now < self.expires_at && self.failed_attempts < LOGIN_CHALLENGE_MAX_ATTEMPTS
+    }
+
+    pub(crate) fn from_persistence(
+        id: LoginChallengeId,
+        user_id: UserId,
+        token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+        failed_attempts: i32,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            token_hash,
+            expires_at,
+            failed_attempts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_produces_distinct_tokens() {
+        let a = SessionToken::generate();
+        let b = SessionToken::generate();
+
+        assert_ne!(a, b);
+        assert_eq!(a.as_str().len(), 43);
+    }
+
+    #[test]
+    fn hash_is_stable_for_the_same_token() {
+        let token = SessionToken::generate();
+        let same = SessionToken::from_client(token.as_str().to_string());
+
+        assert_eq!(token.hash(), same.hash());
+        assert_ne!(token.hash(), SessionToken::generate().hash());
+    }
+
+    #[test]
+    fn issue_stores_hash_of_returned_token() {
+        let user_id = UserId::new();
+
+        let (session, token) = Session::issue(user_id.clone(), Utc::now());
+
+        assert_eq!(session.user_id(), &user_id);
+        assert_eq!(session.token_hash(), &token.hash());
+        assert_eq!(*session.expires_at() - *session.created_at(), Duration::days(SESSION_TTL_DAYS));
+    }
+
+    #[test]
+    fn is_expired_only_after_expiry() {
+        let (session, _token) = Session::issue(UserId::new(), Utc::now());
+
+        assert!(!session.is_expired(*session.created_at()));
+        assert!(session.is_expired(*session.expires_at()));
+    }
+
+    #[test]
+    fn from_persistence_reconstructs_session_fields() {
+        let id = SessionId::new();
+        let user_id = UserId::new();
+        let created_at = Utc::now();
+        let expires_at = created_at + Duration::days(1);
+
+        let session = Session::from_persistence(id.clone(), user_id.clone(), vec![1, 2, 3], created_at, expires_at);
+
+        assert_eq!(session.id(), &id);
+        assert_eq!(session.user_id(), &user_id);
+        assert_eq!(session.token_hash(), &vec![1, 2, 3]);
+        assert_eq!(session.created_at(), &created_at);
+        assert_eq!(session.expires_at(), &expires_at);
+    }
+
+    #[test]
+    fn login_challenge_expires_after_ttl() {
+        let now = Utc::now();
+        let (challenge, token) = LoginChallenge::issue(UserId::new(), now);
+
+        assert_eq!(challenge.token_hash(), &token.hash());
+        assert!(challenge.is_usable(now));
+        assert!(!challenge.is_usable(now + Duration::minutes(LOGIN_CHALLENGE_TTL_MINUTES)));
+    }
+
+    #[test]
+    fn login_challenge_is_unusable_after_too_many_failures() {
+        let now = Utc::now();
+        let (challenge, _token) = LoginChallenge::issue(UserId::new(), now);
+        let with_failures = |failed_attempts| {
+            LoginChallenge::from_persistence(
+                challenge.id().clone(),
+                challenge.user_id().clone(),
+                challenge.token_hash().clone(),
+                *challenge.expires_at(),
+                failed_attempts,
+            )
+        };
+
+        assert!(with_failures(LOGIN_CHALLENGE_MAX_ATTEMPTS - 1).is_usable(now));
+        assert!(!with_failures(LOGIN_CHALLENGE_MAX_ATTEMPTS).is_usable(now));
+    }
+}
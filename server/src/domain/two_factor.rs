@@ -0,0 +1,365 @@
+use chrono::{DateTime, Utc};
+use data_encoding::BASE32_NOPAD;
+use getset::Getters;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::domain::{errors::DomainError, ids::UserId};
+
+pub const TOTP_STEP_SECONDS: i64 = 30;
+pub const TOTP_DIGITS: usize = 6;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+// how many 30s steps either side of "now" still count, to absorb phone clock drift
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+const SECRET_BYTES: usize = 20;
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct TotpSecret(Vec<u8>);
+
+impl TotpSecret {
+    pub fn generate() -> Self {
+        let bytes: [u8; SECRET_BYTES] = rand::random();
+        Self(bytes.to_vec())
+    }
+
+    pub(crate) fn from_persistence(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn to_base32(&self) -> String {
+        BASE32_NOPAD.encode(&self.0)
+    }
+
+    pub fn provisioning_uri(&self, issuer: &str, account: &str) -> String {
+        format!(
+            "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECONDS}",
+            issuer = percent_encode(issuer),
+            account = percent_encode(account),
+            secret = self.to_base32(),
+        )
+    }
+
+    pub fn code_at(&self, step: i64) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(&self.0).expect("hmac accepts keys of any length");
+        mac.update(&step.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+
+        // RFC 4226 dynamic truncation
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([digest[offset] & 0x7f, digest[offset + 1], digest[offset + 2], digest[offset + 3]]);
+        format!("{:0width$}", binary % 10u32.pow(TOTP_DIGITS as u32), width = TOTP_DIGITS)
+    }
+}
+
+impl std::fmt::Debug for TotpSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TotpSecret(..)")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveryCode {
+    pub code_hash: Vec<u8>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+// what a successful authenticate used up; the repository spends it so it only works once, even
+// when two requests check the same code at the same time
+#[derive(Debug, Clone, PartialEq)]
+pub enum TwoFactorUse {
+    TotpStep(i64),
+    RecoveryCode(Vec<u8>),
+}
+
+#[derive(Debug, Getters, PartialEq)]
+pub struct TotpEnrollment {
+    #[getset(get = "pub")]
+    user_id: UserId,
+    #[getset(get = "pub")]
+    secret: TotpSecret,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    confirmed_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    last_used_step: Option<i64>,
+    #[getset(get = "pub")]
+    recovery_codes: Vec<RecoveryCode>,
+}
+
+impl TotpEnrollment {
+    pub fn begin(user_id: UserId, now: DateTime<Utc>) -> Self {
+        Self {
+            user_id,
+            secret: TotpSecret::generate(),
+            created_at: now,
+            confirmed_at: None,
+            last_used_step: None,
+            recovery_codes: Vec::new(),
+        }
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+
+    // returns the plaintext recovery codes, the only time they are ever visible
+    pub fn confirm(&mut self, code: &str, now: DateTime<Utc>) -> Result<Vec<String>, DomainError> {
+        if self.is_confirmed() {
+            return Err(DomainError::TwoFactorAlreadyEnabled);
+        }
+        self.verify_totp(code, now)?;
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_recovery_code()).collect();
+        self.recovery_codes = codes
+            .iter()
+            .map(|code| RecoveryCode {
+                code_hash: hash_recovery_code(code),
+                used_at: None,
+            })
+            .collect();
+        self.confirmed_at = Some(now);
+        Ok(codes)
+    }
+
+    // accepts either a current TOTP code or an unused recovery code
+    pub fn authenticate(&mut self, code: &str, now: DateTime<Utc>) -> Result<TwoFactorUse, DomainError> {
+        if !self.is_confirmed() {
+            return Err(DomainError::TwoFactorNotEnabled);
+        }
+
+        let code = code.trim();
+        if code.len() == TOTP_DIGITS && code.bytes().all(|b| b.is_ascii_digit()) {
+            self.verify_totp(code, now).map(TwoFactorUse::TotpStep)
+        } else {
+            self.redeem_recovery_code(code, now).map(TwoFactorUse::RecoveryCode)
+        }
+    }
+
+    fn verify_totp(&mut self, code: &str, now: DateTime<Utc>) -> Result<i64, DomainError> {
+        let current = now.timestamp().div_euclid(TOTP_STEP_SECONDS);
+
+        for step in (current - ALLOWED_DRIFT_STEPS)..=(current + ALLOWED_DRIFT_STEPS) {
+            // a code can only be used once, and never one older than the last accepted
+            if self.last_used_step.is_some_and(|last| step <= last) {
+                continue;
+            }
+            if self.secret.code_at(step) == code.trim() {
+                self.last_used_step = Some(step);
+                return Ok(step);
+            }
+        }
+
+        Err(DomainError::InvalidTwoFactorCode)
+    }
+
+    fn redeem_recovery_code(&mut self, code: &str, now: DateTime<Utc>) -> Result<Vec<u8>, DomainError> {
+        let hash = hash_recovery_code(code);
+        let recovery_code = self
+            .recovery_codes
+            .iter_mut()
+            .find(|c| c.used_at.is_none() && c.code_hash == hash)
+            .ok_or(DomainError::InvalidTwoFactorCode)?;
+
+        recovery_code.used_at = Some(now);
+        Ok(hash)
+    }
+
+    pub(crate) fn from_persistence(
+        user_id: UserId,
+        secret: TotpSecret,
+        created_at: DateTime<Utc>,
+        confirmed_at: Option<DateTime<Utc>>,
+        last_used_step: Option<i64>,
+        recovery_codes: Vec<RecoveryCode>,
+    ) -> Self {
+        Self {
+            user_id,
+            secret,
+            created_at,
+            confirmed_at,
+            last_used_step,
+            recovery_codes,
+        }
+    }
+}
+
+fn generate_recovery_code() -> String {
+    let chars: String = (0..10)
+        .map(|_| RECOVERY_CODE_ALPHABET[rand::random_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+        .collect();
+    format!("{}-{}", &chars[..5], &chars[5..])
+}
+
+// users retype these by hand, so ignore case, dashes and spaces
+fn hash_recovery_code(code: &str) -> Vec<u8> {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    Sha256::digest(normalized.as_bytes()).to_vec()
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    // RFC 6238 appendix B uses this ASCII secret for its SHA-1 vectors
+    fn rfc_secret() -> TotpSecret {
+        TotpSecret::from_persistence(b"12345678901234567890".to_vec())
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(seconds, 0).unwrap()
+    }
+
+    fn confirmed_enrollment(now: DateTime<Utc>) -> (TotpEnrollment, Vec<String>) {
+        let mut enrollment = TotpEnrollment::begin(UserId::new(), now);
+        enrollment.secret = rfc_secret();
+        let code = enrollment.secret.code_at(now.timestamp() / TOTP_STEP_SECONDS);
+        let recovery_codes = enrollment.confirm(&code, now).unwrap();
+        (enrollment, recovery_codes)
+    }
+
+    #[test]
+    fn code_at_matches_rfc_6238_vectors() {
+        let secret = rfc_secret();
+
+        assert_eq!(secret.code_at(59 / TOTP_STEP_SECONDS), "287082");
+        assert_eq!(secret.code_at(1_111_111_109 / TOTP_STEP_SECONDS), "081804");
+        assert_eq!(secret.code_at(1_111_111_111 / TOTP_STEP_SECONDS), "050471");
+        assert_eq!(secret.code_at(1_234_567_890 / TOTP_STEP_SECONDS), "005924");
+        assert_eq!(secret.code_at(2_000_000_000 / TOTP_STEP_SECONDS), "279037");
+    }
+
+    #[test]
+    fn provisioning_uri_encodes_account_and_secret() {
+        let uri = rfc_secret().provisioning_uri("Volt", "alice smith");
+
+        assert_eq!(
+            uri,
+            "otpauth://totp/Volt:alice%20smith?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Volt&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn secret_debug_does_not_leak_bytes() {
+        assert_eq!(format!("{:?}", rfc_secret()), "TotpSecret(..)");
+    }
+
+    #[test]
+    fn confirm_rejects_wrong_code() {
+        let mut enrollment = TotpEnrollment::begin(UserId::new(), at(59));
+        enrollment.secret = rfc_secret();
+
+        let result = enrollment.confirm("000000", at(59));
+
+        assert_eq!(result, Err(DomainError::InvalidTwoFactorCode));
+        assert!(!enrollment.is_confirmed());
+    }
+
+    #[test]
+    fn confirm_issues_recovery_codes_and_stores_only_hashes() {
+        let (enrollment, codes) = confirmed_enrollment(at(59));
+
+        assert!(enrollment.is_confirmed());
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(enrollment.recovery_codes().len(), RECOVERY_CODE_COUNT);
+        assert!(codes.iter().all(|c| c.len() == 11 && c.as_bytes()[5] == b'-'));
+        assert_eq!(enrollment.recovery_codes()[0].code_hash, hash_recovery_code(&codes[0]));
+    }
+
+    #[test]
+    fn confirm_rejects_already_confirmed_enrollment() {
+        let (mut enrollment, _codes) = confirmed_enrollment(at(59));
+
+        let result = enrollment.confirm("287082", at(59));
+
+        assert_eq!(result, Err(DomainError::TwoFactorAlreadyEnabled));
+    }
+
+    #[test]
+    fn authenticate_rejects_unconfirmed_enrollment() {
+        let mut enrollment = TotpEnrollment::begin(UserId::new(), at(59));
+
+        let result = enrollment.authenticate("287082", at(59));
+
+        assert_eq!(result, Err(DomainError::TwoFactorNotEnabled));
+    }
+
+    #[test]
+    fn authenticate_accepts_code_from_adjacent_step_but_not_further() {
+        let (mut enrollment, _codes) = confirmed_enrollment(at(0));
+        let now = at(1_111_111_111);
+
+        assert_eq!(
+            enrollment.authenticate("081804", now),
+            Ok(TwoFactorUse::TotpStep(now.timestamp() / TOTP_STEP_SECONDS - 1))
+        );
+        assert_eq!(
+            enrollment.authenticate(&rfc_secret().code_at(now.timestamp() / TOTP_STEP_SECONDS + 2), now),
+            Err(DomainError::InvalidTwoFactorCode)
+        );
+    }
+
+    #[test]
+    fn authenticate_rejects_replayed_code() {
+        let (mut enrollment, _codes) = confirmed_enrollment(at(0));
+        let now = at(1_234_567_890);
+
+        enrollment.authenticate("005924", now).unwrap();
+        let result = enrollment.authenticate("005924", now);
+
+        assert_eq!(result, Err(DomainError::InvalidTwoFactorCode));
+    }
+
+    #[test]
+    fn authenticate_accepts_each_recovery_code_once_ignoring_format() {
+        let (mut enrollment, codes) = confirmed_enrollment(at(59));
+        let retyped = codes[3].replace('-', " ").to_uppercase();
+
+        assert_eq!(
+            enrollment.authenticate(&retyped, at(120)),
+            Ok(TwoFactorUse::RecoveryCode(enrollment.recovery_codes()[3].code_hash.clone()))
+        );
+        assert_eq!(enrollment.recovery_codes()[3].used_at, Some(at(120)));
+        assert_eq!(enrollment.authenticate(&codes[3], at(180)), Err(DomainError::InvalidTwoFactorCode));
+    }
+
+    #[test]
+    fn from_persistence_reconstructs_enrollment_fields() {
+        let user_id = UserId::new();
+        let codes = vec![RecoveryCode {
+            code_hash: vec![1, 2, 3],
+            used_at: None,
+        }];
+
+        let enrollment = TotpEnrollment::from_persistence(user_id.clone(), rfc_secret(), at(0), Some(at(30)), Some(7), codes.clone());
+
+        assert_eq!(enrollment.user_id(), &user_id);
+        assert_eq!(enrollment.secret(), &rfc_secret());
+        assert_eq!(enrollment.created_at(), &at(0));
+        assert_eq!(enrollment.confirmed_at(), &Some(at(30)));
+        assert_eq!(enrollment.last_used_step(), &Some(7));
+        assert_eq!(enrollment.recovery_codes(), &codes);
+    }
+}
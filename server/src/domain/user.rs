@@ -64,6 +64,8 @@ pub struct User {
     #[getset(get = "pub")]
     display_name: DisplayName,
     #[getset(get = "pub")]
+    two_factor_enabled: bool,
+    #[getset(get = "pub")]
     created_at: DateTime<Utc>,
 }
 
@@ -73,15 +75,39 @@ impl User {
             id,
             username,
             display_name,
+            two_factor_enabled: false,
             created_at: Utc::now(),
         }
     }
 
-    pub(crate) fn from_persistence(id: UserId, username: Username, display_name: DisplayName, created_at: DateTime<Utc>) -> Self {
+    pub fn enable_two_factor(&mut self) -> Result<(), DomainError> {
+        if self.two_factor_enabled {
+            return Err(DomainError::TwoFactorAlreadyEnabled);
+        }
+        self.two_factor_enabled = true;
+        Ok(())
+    }
+
+    pub fn disable_two_factor(&mut self) -> Result<(), DomainError> {
+        if !self.two_factor_enabled {
+            return Err(DomainError::TwoFactorNotEnabled);
+        }
+        self.two_factor_enabled = false;
+        Ok(())
+    }
+
+    pub(crate) fn from_persistence(
+        id: UserId,
+        username: Username,
+        display_name: DisplayName,
+        two_factor_enabled: bool,
+        created_at: DateTime<Utc>,
+    ) -> Self {
         Self {
             id,
             username,
             display_name,
+            two_factor_enabled,
             created_at,
         }
     }
@@ -116,6 +142,32 @@ mod tests {
         assert_eq!(user.id(), &id);
         assert_eq!(user.username(), &username);
         assert_eq!(user.display_name(), &display_name);
+        assert!(!user.two_factor_enabled());
+    }
+
+    #[test]
+    fn enable_two_factor_rejects_when_already_enabled() {
+        let mut user = User::new(
+            UserId::new(),
+            Username::new("alice".into()).unwrap(),
+            DisplayName::new("Alice".into()).unwrap(),
+        );
+
+        user.enable_two_factor().unwrap();
+
+        assert!(user.two_factor_enabled());
+        assert_eq!(user.enable_two_factor(), Err(DomainError::TwoFactorAlreadyEnabled));
+    }
+
+    #[test]
+    fn disable_two_factor_rejects_when_not_enabled() {
+        let mut user = User::new(
+            UserId::new(),
+            Username::new("alice".into()).unwrap(),
+            DisplayName::new("Alice".into()).unwrap(),
+        );
+
+        assert_eq!(user.disable_two_factor(), Err(DomainError::TwoFactorNotEnabled));
     }
 
     #[test]
@@ -125,11 +177,12 @@ mod tests {
         let display_name = DisplayName::from_persistence("Alice Smith".into());
         let created_at = Utc::now();
 
-        let user = User::from_persistence(id.clone(), username.clone(), display_name.clone(), created_at);
+        let user = User::from_persistence(id.clone(), username.clone(), display_name.clone(), true, created_at);
 
         assert_eq!(user.id(), &id);
         assert_eq!(user.username(), &username);
         assert_eq!(user.display_name(), &display_name);
+        assert!(user.two_factor_enabled());
         assert_eq!(user.created_at(), &created_at);
     }
 }
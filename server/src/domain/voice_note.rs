@@ -0,0 +1,50 @@
+use getset::Getters;
+
+use crate::domain::errors::DomainError;
+
+pub const MAX_VOICE_NOTE_MINUTES: i32 = 15;
+pub const WAVEFORM_PEAKS: usize = 64;
+
+// what the upload measured about a recording, so clients can draw the player before fetching the
+// audio. peaks are evenly spaced over the duration and scaled so the loudest one is 255
+#[derive(Debug, Clone, Getters, PartialEq)]
+pub struct VoiceNote {
+    #[getset(get = "pub")]
+    duration_ms: i32,
+    #[getset(get = "pub")]
+    peaks: Vec<u8>,
+}
+
+impl VoiceNote {
+    pub fn new(duration_ms: i32, peaks: Vec<u8>) -> Result<Self, DomainError> {
+        if !(1..=MAX_VOICE_NOTE_MINUTES * 60 * 1000).contains(&duration_ms) || peaks.len() != WAVEFORM_PEAKS {
+            return Err(DomainError::InvalidVoiceNote);
+        }
+        Ok(Self { duration_ms, peaks })
+    }
+
+    pub(crate) fn from_persistence(duration_ms: i32, peaks: Vec<u8>) -> Self {
+        Self { duration_ms, peaks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_needs_a_sane_duration_and_a_full_waveform() {
+        let peaks = vec![0; WAVEFORM_PEAKS];
+
+        assert_eq!(VoiceNote::new(0, peaks.clone()).err(), Some(DomainError::InvalidVoiceNote));
+        assert_eq!(
+            VoiceNote::new(MAX_VOICE_NOTE_MINUTES * 60 * 1000 + 1, peaks.clone()).err(),
+            Some(DomainError::InvalidVoiceNote)
+        );
+        assert_eq!(
+            VoiceNote::new(1500, vec![0; WAVEFORM_PEAKS - 1]).err(),
+            Some(DomainError::InvalidVoiceNote)
+        );
+        assert!(VoiceNote::new(1500, peaks).is_ok());
+    }
+}
@@ -28,6 +28,13 @@ impl AppError {
             status: StatusCode::BAD_REQUEST,
         }
     }
+
+    pub fn unauthorized(msg: impl std::fmt::Display) -> Self {
+        Self {
+            message: msg.to_string(),
+            status: StatusCode::UNAUTHORIZED,
+        }
+    }
 }
 
 pub trait OptionExt<T> {
@@ -58,8 +65,23 @@ impl IntoResponse for AppError {
 impl From<DomainError> for AppError {
     fn from(err: DomainError) -> Self {
         let status = match err {
-            DomainError::ConversationNotFound => StatusCode::NOT_FOUND,
-            DomainError::NotAParticipant | DomainError::NotYourMessage => StatusCode::FORBIDDEN,
+            DomainError::ConversationNotFound
+            | DomainError::UserNotFound
+            | DomainError::InviteNotFound
+            | DomainError::InvitationNotFound
+            | DomainError::ScheduledMessageNotFound => StatusCode::NOT_FOUND,
+            DomainError::InviteExpired => StatusCode::GONE,
+            DomainError::InvalidSession
+            | DomainError::InvalidCredentials
+            | DomainError::InvalidTwoFactorCode
+            | DomainError::InvalidLoginChallenge => StatusCode::UNAUTHORIZED,
+            DomainError::UsernameTaken
+            | DomainError::TwoFactorAlreadyEnabled
+            | DomainError::AlreadyParticipant
+            | DomainError::MessageEditConflict => StatusCode::CONFLICT,
+            DomainError::NotAParticipant | DomainError::NotYourMessage | DomainError::NotAnAdmin | DomainError::Blocked => {
+                StatusCode::FORBIDDEN
+            }
             DomainError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
             _ => StatusCode::BAD_REQUEST,
         };
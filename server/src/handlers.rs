@@ -3,26 +3,42 @@ use std::{sync::Arc, time::Duration};
 use axum::{
     Router,
     error_handling::HandleErrorLayer,
+    extract::DefaultBodyLimit,
     http::{self, HeaderValue, Method, StatusCode},
-    routing::{get, post},
+    routing::{delete, get, post, put},
 };
 use tower::{BoxError, ServiceBuilder};
-use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
+use tower_http::{cors::CorsLayer, services::ServeDir, set_header::SetResponseHeaderLayer, trace::TraceLayer};
 
 use crate::{
     AppState,
     config::AppConfig,
     handlers::{
+        auth::{login, logout, verify_login},
         chat::chat,
-        conversation::{create_conversation, leave_conversation, mark_as_read, query_conversations_by_user},
-        messages::{edit_message, query_messages},
-        upload::upload_image,
-        user::{create_or_read_user, get_users},
+        conversation::{
+            add_participants, archive_conversation, change_participant_role, create_conversation, kick_participant,
+            leave_conversation, mark_as_read, pin_conversation, pin_message, query_conversations_by_user, query_pins,
+            rename_conversation, set_message_timer, unpin_message, update_conversation_details, update_notification_settings,
+        },
+        invite::{
+            accept_invitation, create_invite, decline_invitation, join_via_invite, query_invitations, query_invites, revoke_invite,
+        },
+        messages::{
+            add_reaction, cancel_scheduled_message, cast_vote, delete_message, edit_message, forward_message, query_mentions,
+            query_messages, query_revisions, query_scheduled, query_thread, remove_reaction, retract_vote, schedule_message,
+        },
+        upload::{MAX_UPLOAD_BYTES, upload_audio, upload_file, upload_image},
+        user::{
+            block_user, change_password, confirm_totp, disable_totp, enroll_totp, get_users, query_blocks, register_user, unblock_user,
+        },
     },
 };
 
+pub mod auth;
 pub mod chat;
 pub mod conversation;
+pub mod invite;
 pub mod messages;
 pub mod upload;
 pub mod user;
@@ -30,32 +46,85 @@ pub mod user;
 fn conversation_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/conversation", post(create_conversation))
-        .route("/conversation/{id}/read/{user_id}", post(mark_as_read))
-        .route("/conversation/{id}/leave/{user_id}", post(leave_conversation))
-        .route("/conversations/{user_id}", get(query_conversations_by_user))
+        .route("/conversation/{id}/name", put(rename_conversation))
+        .route("/conversation/{id}/details", put(update_conversation_details))
+        .route("/conversation/{id}/notifications", put(update_notification_settings))
+        .route("/conversation/{id}/message-timer", put(set_message_timer))
+        .route("/conversation/{id}/archived", put(archive_conversation))
+        .route("/conversation/{id}/pinned", put(pin_conversation))
+        .route("/conversation/{id}/read", post(mark_as_read))
+        .route("/conversation/{id}/leave", post(leave_conversation))
+        .route("/conversation/{id}/participants", post(add_participants))
+        .route("/conversation/{id}/participants/{user_id}", delete(kick_participant))
+        .route("/conversation/{id}/participants/{user_id}/role", put(change_participant_role))
+        .route("/conversation/{id}/pins", get(query_pins).post(pin_message))
+        .route("/conversation/{id}/pins/{message_id}", delete(unpin_message))
+        .route("/conversations", get(query_conversations_by_user))
+}
+
+fn invite_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/conversation/{id}/invites", get(query_invites).post(create_invite))
+        .route("/conversation/{id}/invites/{invite_id}", delete(revoke_invite))
+        .route("/invite/{token}/join", post(join_via_invite))
+        .route("/invitations", get(query_invitations))
+        .route("/invitations/{id}/accept", post(accept_invitation))
+        .route("/invitations/{id}/decline", post(decline_invitation))
 }
 
 fn message_routes() -> Router<Arc<AppState>> {
-    Router::new().route("/messages/{id}", get(query_messages).patch(edit_message))
+    Router::new()
+        .route("/messages/{id}", get(query_messages).patch(edit_message).delete(delete_message))
+        .route("/messages/{id}/thread", get(query_thread))
+        .route("/messages/{id}/revisions", get(query_revisions))
+        .route("/messages/{id}/forward", post(forward_message))
+        .route("/messages/{id}/reactions", post(add_reaction))
+        .route("/messages/{id}/reactions/{emoji}", delete(remove_reaction))
+        .route("/messages/{id}/vote", put(cast_vote).delete(retract_vote))
+        .route("/mentions", get(query_mentions))
+        .route("/conversation/{id}/scheduled", post(schedule_message))
+        .route("/scheduled", get(query_scheduled))
+        .route("/scheduled/{id}", delete(cancel_scheduled_message))
 }
 
 fn chat_routes() -> Router<Arc<AppState>> {
-    Router::new().route("/chat/{user_id}", get(chat))
+    Router::new().route("/chat", get(chat))
 }
 
 fn user_routes() -> Router<Arc<AppState>> {
-    Router::new().route("/user", post(create_or_read_user)).route("/users", get(get_users))
+    Router::new()
+        .route("/user", post(register_user))
+        .route("/user/password", put(change_password))
+        .route("/user/totp", post(enroll_totp).delete(disable_totp))
+        .route("/user/totp/confirm", post(confirm_totp))
+        .route("/user/blocks", get(query_blocks))
+        .route("/user/blocks/{user_id}", put(block_user).delete(unblock_user))
+        .route("/users", get(get_users))
+}
+
+fn auth_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/login", post(login))
+        .route("/login/totp", post(verify_login))
+        .route("/logout", post(logout))
 }
 
 fn upload_routes() -> Router<Arc<AppState>> {
-    Router::new().route("/upload", post(upload_image))
+    Router::new()
+        .route("/upload", post(upload_image))
+        .route("/upload/file", post(upload_file))
+        .route("/upload/audio", post(upload_audio))
+        // headroom over the file itself for the multipart framing
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES + 64 * 1024))
 }
 
 pub fn routes(config: &AppConfig) -> Router<Arc<AppState>> {
     let http_routes = Router::new()
         .merge(conversation_routes())
+        .merge(invite_routes())
         .merge(message_routes())
         .merge(user_routes())
+        .merge(auth_routes())
         .merge(upload_routes())
         .layer(
             ServiceBuilder::new()
@@ -75,6 +144,20 @@ pub fn routes(config: &AppConfig) -> Router<Arc<AppState>> {
     Router::new()
         .nest("/api/v1", api_routes)
         .nest_service("/media", ServeDir::new(&config.upload_dir))
+        // never rendered inline, whatever the type: an uploaded HTML or SVG file can't run as our origin
+        .nest_service(
+            "/files",
+            ServiceBuilder::new()
+                .layer(SetResponseHeaderLayer::overriding(
+                    http::header::CONTENT_DISPOSITION,
+                    HeaderValue::from_static("attachment"),
+                ))
+                .layer(SetResponseHeaderLayer::overriding(
+                    http::header::X_CONTENT_TYPE_OPTIONS,
+                    HeaderValue::from_static("nosniff"),
+                ))
+                .service(ServeDir::new(&config.file_dir)),
+        )
         .layer(
             CorsLayer::new()
                 .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION])
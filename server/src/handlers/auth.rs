@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{FromRequestParts, State},
+    http::{StatusCode, request::Parts},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use headers::{Authorization, HeaderMapExt, authorization::Bearer};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState,
+    application::commands::{
+        login::{LoginCommand, LoginOutcome, LoginResult},
+        logout::LogoutCommand,
+        verify_login::VerifyLoginCommand,
+    },
+    domain::{
+        errors::DomainError,
+        ids::{SessionId, UserId},
+        repository::SessionRepository,
+        session::SessionToken,
+    },
+    errors::AppError,
+    handlers::user::UserResponse,
+};
+
+pub struct AuthenticatedUser {
+    pub user_id: UserId,
+    pub session_id: SessionId,
+}
+
+impl FromRequestParts<Arc<AppState>> for AuthenticatedUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let Some(Authorization(bearer)) = parts.headers.typed_get::<Authorization<Bearer>>() else {
+            return Err(AppError::unauthorized("missing bearer token"));
+        };
+
+        authenticate(state, SessionToken::from_client(bearer.token().to_string())).await
+    }
+}
+
+// shared with the websocket upgrade, where browsers can't attach an Authorization header
+pub async fn authenticate(state: &AppState, token: SessionToken) -> Result<AuthenticatedUser, AppError> {
+    let session = state
+        .sessions
+        .find_by_token_hash(&token.hash())
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .filter(|session| !session.is_expired(Utc::now()))
+        .ok_or(DomainError::InvalidSession)?;
+
+    Ok(AuthenticatedUser {
+        user_id: session.user_id().clone(),
+        session_id: session.id().clone(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyLoginRequest {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LoginResponse {
+    Authenticated {
+        token: String,
+        expires_at: DateTime<Utc>,
+        user: UserResponse,
+    },
+    TwoFactorRequired {
+        challenge_token: String,
+        expires_at: DateTime<Utc>,
+    },
+}
+
+impl From<LoginResult> for LoginResponse {
+    fn from(result: LoginResult) -> Self {
+        Self::Authenticated {
+            token: result.token.as_str().to_string(),
+            expires_at: *result.session.expires_at(),
+            user: UserResponse::from(&result.user),
+        }
+    }
+}
+
+pub async fn login(State(state): State<Arc<AppState>>, Json(request): Json<LoginRequest>) -> Result<impl IntoResponse, AppError> {
+    let outcome = state
+        .login
+        .handle(LoginCommand {
+            username: request.username,
+            password: request.password,
+        })
+        .await?;
+
+    // 202: the password was right but no session exists until POST /login/totp succeeds
+    Ok(match outcome {
+        LoginOutcome::Authenticated(result) => (StatusCode::CREATED, Json(LoginResponse::from(result))),
+        LoginOutcome::TwoFactorRequired { challenge, token } => (
+            StatusCode::ACCEPTED,
+            Json(LoginResponse::TwoFactorRequired {
+                challenge_token: token.as_str().to_string(),
+                expires_at: *challenge.expires_at(),
+            }),
+        ),
+    })
+}
+
+pub async fn verify_login(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<VerifyLoginRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let result = state
+        .verify_login
+        .handle(VerifyLoginCommand {
+            challenge_token: request.challenge_token,
+            code: request.code,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(LoginResponse::from(result))))
+}
+
+pub async fn logout(State(state): State<Arc<AppState>>, user: AuthenticatedUser) -> Result<impl IntoResponse, AppError> {
+    state
+        .logout
+        .handle(LogoutCommand {
+            session_id: user.session_id,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
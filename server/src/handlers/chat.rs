@@ -1,19 +1,28 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State, ws::WebSocketUpgrade},
+    extract::{Query, State, ws::WebSocketUpgrade},
     response::IntoResponse,
 };
-use uuid::Uuid;
+use serde::Deserialize;
 
-use crate::{AppState, domain::ids::UserId, infrastructure::websocket::hub};
+use crate::{AppState, domain::session::SessionToken, errors::AppError, handlers::auth::authenticate, infrastructure::websocket::hub};
 
-pub async fn chat(ws: WebSocketUpgrade, State(state): State<Arc<AppState>>, Path(user_id): Path<Uuid>) -> impl IntoResponse {
-    let user_id = UserId::from_persistence(user_id);
+#[derive(Deserialize)]
+pub struct ChatParams {
+    pub token: String,
+}
+
+pub async fn chat(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ChatParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = authenticate(&state, SessionToken::from_client(params.token)).await?;
     let rx = state.event_bus.subscribe();
     let pool = state.pool.clone();
     let send_message = state.send_message.clone();
     let views = state.views.clone();
 
-    ws.on_upgrade(move |socket| hub::handle_socket(socket, user_id, pool, send_message, views, rx))
+    Ok(ws.on_upgrade(move |socket| hub::handle_socket(socket, user.user_id, pool, send_message, views, rx)))
 }
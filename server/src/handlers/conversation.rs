@@ -2,46 +2,108 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
+use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
     AppState,
     application::commands::{
-        create_conversation::CreateConversationCommand, leave_conversation::LeaveConversationCommand,
-        mark_message_read::MarkMessageReadCommand,
+        add_participants::AddParticipantsCommand, archive_conversation::ArchiveConversationCommand,
+        change_participant_role::ChangeParticipantRoleCommand, create_conversation::CreateConversationCommand,
+        kick_participant::KickParticipantCommand, leave_conversation::LeaveConversationCommand,
+        mark_message_read::MarkMessageReadCommand, pin_conversation::PinConversationCommand, pin_message::PinMessageCommand,
+        rename_conversation::RenameConversationCommand, set_message_timer::SetMessageTimerCommand, unpin_message::UnpinMessageCommand,
+        update_conversation_details::UpdateConversationDetailsCommand,
+        update_notification_settings::UpdateNotificationSettingsCommand,
     },
-    application::queries::conversation_list::ConversationViewQueries,
-    domain::conversation::ConversationKind,
+    application::queries::conversation_list::{ConversationFilter, ConversationListQuery, ConversationViewQueries},
+    application::queries::message_history::MessageHistoryQueries,
+    domain::conversation::{ConversationKind, ParticipantRole},
+    domain::errors::DomainError,
     domain::ids::{ConversationId, MessageId, UserId},
+    domain::notification::NotifyLevel,
+    domain::repository::ConversationRepository,
     errors::{AppError, OptionExt},
+    handlers::auth::AuthenticatedUser,
 };
 
 #[derive(Deserialize)]
 pub struct CreateConversationRequest {
     pub conversation_type: String,
-    pub sender_id: String,
     pub participants: Vec<String>,
     pub name: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct AddParticipantsRequest {
+    pub participants: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ChangeRoleRequest {
+    pub role: String,
+}
+
+#[derive(Deserialize)]
+pub struct RenameConversationRequest {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct ConversationDetailsRequest {
+    pub description: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MessageTimerRequest {
+    pub ttl_seconds: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct NotificationSettingsRequest {
+    pub notify_level: String,
+    pub muted_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub struct ConversationListParams {
+    pub filter: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ArchiveConversationRequest {
+    pub archived: bool,
+}
+
+#[derive(Deserialize)]
+pub struct PinConversationRequest {
+    pub pinned: bool,
+}
+
+#[derive(Deserialize)]
+pub struct PinMessageRequest {
+    pub message_id: String,
+}
+
 fn parse_uuid(value: &str) -> Result<Uuid, AppError> {
     Uuid::parse_str(value).map_err(|_| AppError::not_found(format!("invalid id: {value}")))
 }
 
 pub async fn create_conversation(
     State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
     Json(request): Json<CreateConversationRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     let kind = match request.conversation_type.as_str() {
         "direct" => ConversationKind::Direct,
         _ => ConversationKind::Group,
     };
-    let creator_id = UserId::from_persistence(parse_uuid(&request.sender_id)?);
     let participants = request
         .participants
         .iter()
@@ -52,32 +114,48 @@ pub async fn create_conversation(
         .create_conversation
         .handle(CreateConversationCommand {
             kind,
-            creator_id,
+            creator_id: user.user_id.clone(),
             participants,
             title: request.name,
         })
         .await?;
 
-    let view = state.views.by_id(&id).await?.ok_or_not_found("conversation not found")?;
+    let view = state
+        .views
+        .by_id(&id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
 
     Ok((StatusCode::CREATED, Json(view)))
 }
 
 pub async fn query_conversations_by_user(
     State(state): State<Arc<AppState>>,
-    Path(user_id): Path<String>,
+    user: AuthenticatedUser,
+    Query(params): Query<ConversationListParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let user_id = UserId::from_persistence(parse_uuid(&user_id)?);
-    let conversations = state.views.for_user(&user_id).await?;
+    let filter = match params.filter.as_deref() {
+        None | Some("active") => ConversationFilter::Active,
+        Some("archived") => ConversationFilter::Archived,
+        Some(other) => return Err(AppError::bad_request(format!("unknown filter: {other}"))),
+    };
+
+    let conversations = state
+        .views
+        .for_user(ConversationListQuery {
+            user_id: user.user_id,
+            filter,
+        })
+        .await?;
     Ok(Json(conversations))
 }
 
 pub async fn mark_as_read(
     State(state): State<Arc<AppState>>,
-    Path((id, user_id)): Path<(String, String)>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
     let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
-    let user_id = UserId::from_persistence(parse_uuid(&user_id)?);
 
     let last_message_id = sqlx::query!(
         "SELECT last_message_id FROM conversations WHERE id = $1",
@@ -93,7 +171,7 @@ pub async fn mark_as_read(
             .mark_read
             .handle(MarkMessageReadCommand {
                 conversation_id,
-                user_id,
+                user_id: user.user_id,
                 message_id: MessageId::from_persistence(message_id),
             })
             .await?;
@@ -104,12 +182,312 @@ pub async fn mark_as_read(
 
 pub async fn leave_conversation(
     State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+
+    state
+        .leave_conversation
+        .handle(LeaveConversationCommand {
+            conversation_id,
+            user_id: user.user_id,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn add_participants(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<AddParticipantsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+    let user_ids = request
+        .participants
+        .iter()
+        .map(|p| parse_uuid(p).map(UserId::from_persistence))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    state
+        .add_participants
+        .handle(AddParticipantsCommand {
+            conversation_id: conversation_id.clone(),
+            actor_id: user.user_id.clone(),
+            user_ids,
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn kick_participant(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
     Path((id, user_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .kick_participant
+        .handle(KickParticipantCommand {
+            conversation_id: ConversationId::from_persistence(parse_uuid(&id)?),
+            actor_id: user.user_id,
+            user_id: UserId::from_persistence(parse_uuid(&user_id)?),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn change_participant_role(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, user_id)): Path<(String, String)>,
+    Json(request): Json<ChangeRoleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = match request.role.as_str() {
+        "owner" => ParticipantRole::Owner,
+        "admin" => ParticipantRole::Admin,
+        "member" => ParticipantRole::Member,
+        other => return Err(AppError::bad_request(format!("unknown role: {other}"))),
+    };
+
+    state
+        .change_participant_role
+        .handle(ChangeParticipantRoleCommand {
+            conversation_id: ConversationId::from_persistence(parse_uuid(&id)?),
+            actor_id: user.user_id,
+            user_id: UserId::from_persistence(parse_uuid(&user_id)?),
+            role,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn rename_conversation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<RenameConversationRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
-    let user_id = UserId::from_persistence(parse_uuid(&user_id)?);
 
-    state.leave_conversation.handle(LeaveConversationCommand { conversation_id, user_id }).await?;
+    state
+        .rename_conversation
+        .handle(RenameConversationCommand {
+            conversation_id: conversation_id.clone(),
+            actor_id: user.user_id.clone(),
+            title: request.name,
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn update_conversation_details(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<ConversationDetailsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+
+    state
+        .update_conversation_details
+        .handle(UpdateConversationDetailsCommand {
+            conversation_id: conversation_id.clone(),
+            actor_id: user.user_id.clone(),
+            description: request.description,
+            avatar_url: request.avatar_url,
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn set_message_timer(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<MessageTimerRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+
+    state
+        .set_message_timer
+        .handle(SetMessageTimerCommand {
+            conversation_id: conversation_id.clone(),
+            actor_id: user.user_id.clone(),
+            ttl: request.ttl_seconds.map(Duration::seconds),
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn update_notification_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<NotificationSettingsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+    let level = match request.notify_level.as_str() {
+        "all" => NotifyLevel::All,
+        "mentions" => NotifyLevel::Mentions,
+        "none" => NotifyLevel::None,
+        other => return Err(AppError::bad_request(format!("unknown notify level: {other}"))),
+    };
+
+    state
+        .update_notification_settings
+        .handle(UpdateNotificationSettingsCommand {
+            conversation_id: conversation_id.clone(),
+            user_id: user.user_id.clone(),
+            level,
+            muted_until: request.muted_until,
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn archive_conversation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<ArchiveConversationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+
+    state
+        .archive_conversation
+        .handle(ArchiveConversationCommand {
+            conversation_id: conversation_id.clone(),
+            user_id: user.user_id.clone(),
+            archived: request.archived,
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn pin_conversation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<PinConversationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+
+    state
+        .pin_conversation
+        .handle(PinConversationCommand {
+            conversation_id: conversation_id.clone(),
+            user_id: user.user_id.clone(),
+            pinned: request.pinned,
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn query_pins(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+    let conversation = state
+        .conversations
+        .find_by_id(&conversation_id)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .ok_or(DomainError::ConversationNotFound)?;
+    if !conversation.is_participant(&user.user_id) {
+        return Err(DomainError::NotAParticipant.into());
+    }
+
+    let pinned = state.views.pinned(&conversation_id).await?;
+
+    Ok(Json(pinned))
+}
+
+pub async fn pin_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<PinMessageRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .pin_message
+        .handle(PinMessageCommand {
+            conversation_id: ConversationId::from_persistence(parse_uuid(&id)?),
+            message_id: MessageId::from_persistence(parse_uuid(&request.message_id)?),
+            actor_id: user.user_id,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unpin_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, message_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .unpin_message
+        .handle(UnpinMessageCommand {
+            conversation_id: ConversationId::from_persistence(parse_uuid(&id)?),
+            message_id: MessageId::from_persistence(parse_uuid(&message_id)?),
+            actor_id: user.user_id,
+        })
+        .await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Duration;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    application::commands::{
+        accept_invitation::AcceptInvitationCommand, create_invite::CreateInviteCommand, decline_invitation::DeclineInvitationCommand,
+        join_via_invite::JoinViaInviteCommand, revoke_invite::RevokeInviteCommand,
+    },
+    application::queries::conversation_list::{ConversationViewQueries, InviteView},
+    domain::errors::DomainError,
+    domain::ids::{ConversationId, InviteId},
+    domain::repository::ConversationRepository,
+    errors::{AppError, OptionExt},
+    handlers::auth::AuthenticatedUser,
+};
+
+#[derive(Deserialize)]
+pub struct CreateInviteRequest {
+    pub expires_in_seconds: Option<i64>,
+    pub max_uses: Option<i32>,
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::not_found(format!("invalid id: {value}")))
+}
+
+pub async fn create_invite(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<CreateInviteRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let invite = state
+        .create_invite
+        .handle(CreateInviteCommand {
+            conversation_id: ConversationId::from_persistence(parse_uuid(&id)?),
+            actor_id: user.user_id,
+            expires_in: request.expires_in_seconds.map(Duration::seconds),
+            max_uses: request.max_uses,
+        })
+        .await?;
+
+    let view = InviteView {
+        id: invite.id().to_string(),
+        conversation_id: invite.conversation_id().to_string(),
+        token: invite.token().clone(),
+        created_by: invite.created_by().to_string(),
+        created_at: *invite.created_at(),
+        expires_at: *invite.expires_at(),
+        max_uses: *invite.max_uses(),
+        uses: *invite.uses(),
+    };
+
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+// links are as good as membership, so only admins get to see them
+pub async fn query_invites(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+    let conversation = state
+        .conversations
+        .find_by_id(&conversation_id)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .ok_or(DomainError::ConversationNotFound)?;
+    conversation.require_admin(&user.user_id)?;
+
+    let invites = state.views.invites(&conversation_id).await?;
+
+    Ok(Json(invites))
+}
+
+pub async fn revoke_invite(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, invite_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .revoke_invite
+        .handle(RevokeInviteCommand {
+            conversation_id: ConversationId::from_persistence(parse_uuid(&id)?),
+            invite_id: InviteId::from_persistence(parse_uuid(&invite_id)?),
+            actor_id: user.user_id,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn join_via_invite(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = state
+        .join_via_invite
+        .handle(JoinViaInviteCommand {
+            token,
+            user_id: user.user_id.clone(),
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn query_invitations(State(state): State<Arc<AppState>>, user: AuthenticatedUser) -> Result<impl IntoResponse, AppError> {
+    let invitations = state.views.invitations(&user.user_id).await?;
+
+    Ok(Json(invitations))
+}
+
+pub async fn accept_invitation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(parse_uuid(&id)?);
+    state
+        .accept_invitation
+        .handle(AcceptInvitationCommand {
+            conversation_id: conversation_id.clone(),
+            user_id: user.user_id.clone(),
+        })
+        .await?;
+
+    let view = state
+        .views
+        .by_id(&conversation_id, &user.user_id)
+        .await?
+        .ok_or_not_found("conversation not found")?;
+
+    Ok(Json(view))
+}
+
+pub async fn decline_invitation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .decline_invitation
+        .handle(DeclineInvitationCommand {
+            conversation_id: ConversationId::from_persistence(parse_uuid(&id)?),
+            user_id: user.user_id,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -6,15 +6,28 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     AppState,
-    application::commands::edit_message::EditMessageCommand,
-    application::queries::message_history::{MessageHistoryQueries, MessageHistoryQuery},
-    domain::ids::{ConversationId, MessageId, UserId},
+    application::commands::{
+        add_reaction::AddReactionCommand, cancel_scheduled_message::CancelScheduledMessageCommand, cast_vote::CastVoteCommand,
+        delete_message::DeleteMessageCommand, edit_message::EditMessageCommand, forward_message::ForwardMessageCommand,
+        remove_reaction::RemoveReactionCommand, retract_vote::RetractVoteCommand, schedule_message::ScheduleMessageCommand,
+    },
+    application::queries::message_history::{
+        MentionsQuery, MessageHistoryQueries, MessageHistoryQuery, ScheduledMessageView, ThreadHistoryQuery,
+    },
+    domain::{
+        errors::DomainError,
+        ids::{ConversationId, MessageId, ScheduledMessageId},
+        message::MessageKind,
+        repository::{ConversationRepository, MessageRepository},
+    },
     errors::AppError,
+    handlers::auth::AuthenticatedUser,
 };
 
 #[derive(Deserialize, Default)]
@@ -25,13 +38,42 @@ pub struct Pagination {
 
 pub async fn query_messages(
     State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
     Path(conversation_id): Path<Uuid>,
     Query(pagination): Query<Pagination>,
 ) -> Result<impl IntoResponse, AppError> {
+    let conversation_id = ConversationId::from_persistence(conversation_id);
+    let conversation = state
+        .conversations
+        .find_by_id(&conversation_id)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .ok_or(DomainError::ConversationNotFound)?;
+    if !conversation.is_participant(&user.user_id) {
+        return Err(DomainError::NotAParticipant.into());
+    }
+
     let messages = state
         .views
         .for_conversation(MessageHistoryQuery {
-            conversation_id: ConversationId::from_persistence(conversation_id),
+            conversation_id,
+            offset: pagination.offset,
+            limit: pagination.limit,
+        })
+        .await?;
+
+    Ok(Json(messages))
+}
+
+pub async fn query_mentions(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(pagination): Query<Pagination>,
+) -> Result<impl IntoResponse, AppError> {
+    let messages = state
+        .views
+        .mentions(MentionsQuery {
+            user_id: user.user_id,
             offset: pagination.offset,
             limit: pagination.limit,
         })
@@ -40,25 +82,276 @@ pub async fn query_messages(
     Ok(Json(messages))
 }
 
+pub async fn query_thread(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+    Query(pagination): Query<Pagination>,
+) -> Result<impl IntoResponse, AppError> {
+    let message = state
+        .messages
+        .find_by_id(&MessageId::from_persistence(message_id))
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .ok_or(DomainError::MessageNotFound)?;
+    let conversation = state
+        .conversations
+        .find_by_id(message.conversation_id())
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .ok_or(DomainError::ConversationNotFound)?;
+    if !conversation.is_participant(&user.user_id) {
+        return Err(DomainError::NotAParticipant.into());
+    }
+
+    let replies = state
+        .views
+        .for_thread(ThreadHistoryQuery {
+            thread_root_id: message.thread_root_id().clone().unwrap_or_else(|| message.id().clone()),
+            offset: pagination.offset,
+            limit: pagination.limit,
+        })
+        .await?;
+
+    Ok(Json(replies))
+}
+
+pub async fn query_revisions(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let message = state
+        .messages
+        .find_by_id(&MessageId::from_persistence(message_id))
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .ok_or(DomainError::MessageNotFound)?;
+    let conversation = state
+        .conversations
+        .find_by_id(message.conversation_id())
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .ok_or(DomainError::ConversationNotFound)?;
+    if !conversation.is_participant(&user.user_id) {
+        return Err(DomainError::NotAParticipant.into());
+    }
+
+    let revisions = state.views.revisions(message.id()).await?;
+
+    Ok(Json(revisions))
+}
+
 #[derive(Deserialize)]
 pub struct EditMessageRequest {
-    pub editor_id: String,
     pub content: String,
 }
 
 pub async fn edit_message(
     State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
     Path(message_id): Path<Uuid>,
     Json(request): Json<EditMessageRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let editor_id = Uuid::parse_str(&request.editor_id).map_err(|_| AppError::bad_request("invalid editor_id"))?;
-
     state
         .edit_message
         .handle(EditMessageCommand {
             message_id: MessageId::from_persistence(message_id),
-            editor_id: UserId::from_persistence(editor_id),
+            editor_id: user.user_id,
+            content: request.content,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct ForwardMessageRequest {
+    pub conversation_ids: Vec<Uuid>,
+}
+
+#[derive(Serialize)]
+pub struct ForwardMessageResponse {
+    pub message_ids: Vec<String>,
+}
+
+pub async fn forward_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+    Json(request): Json<ForwardMessageRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let ids = state
+        .forward_message
+        .handle(ForwardMessageCommand {
+            message_id: MessageId::from_persistence(message_id),
+            forwarder_id: user.user_id,
+            target_ids: request.conversation_ids.into_iter().map(ConversationId::from_persistence).collect(),
+        })
+        .await?;
+
+    let message_ids = ids.iter().map(|id| id.to_string()).collect();
+    Ok((StatusCode::CREATED, Json(ForwardMessageResponse { message_ids })))
+}
+
+pub async fn delete_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .delete_message
+        .handle(DeleteMessageCommand {
+            message_id: MessageId::from_persistence(message_id),
+            actor_id: user.user_id,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct AddReactionRequest {
+    pub emoji: String,
+}
+
+pub async fn add_reaction(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+    Json(request): Json<AddReactionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .add_reaction
+        .handle(AddReactionCommand {
+            message_id: MessageId::from_persistence(message_id),
+            user_id: user.user_id,
+            emoji: request.emoji,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn remove_reaction(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((message_id, emoji)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .remove_reaction
+        .handle(RemoveReactionCommand {
+            message_id: MessageId::from_persistence(message_id),
+            user_id: user.user_id,
+            emoji,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct CastVoteRequest {
+    pub choices: Vec<usize>,
+}
+
+pub async fn cast_vote(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+    Json(request): Json<CastVoteRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .cast_vote
+        .handle(CastVoteCommand {
+            message_id: MessageId::from_persistence(message_id),
+            user_id: user.user_id,
+            choices: request.choices,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn retract_vote(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .retract_vote
+        .handle(RetractVoteCommand {
+            message_id: MessageId::from_persistence(message_id),
+            user_id: user.user_id,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct ScheduleMessageRequest {
+    pub content: String,
+    pub kind: Option<String>,
+    pub reply_to: Option<Uuid>,
+    pub send_at: DateTime<Utc>,
+}
+
+pub async fn schedule_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<Uuid>,
+    Json(request): Json<ScheduleMessageRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let kind = match request.kind.as_deref() {
+        None | Some("text") => MessageKind::Text,
+        Some("image") => MessageKind::Image,
+        Some(other) => return Err(AppError::bad_request(format!("unknown message kind: {other}"))),
+    };
+
+    let scheduled = state
+        .schedule_message
+        .handle(ScheduleMessageCommand {
+            conversation_id: ConversationId::from_persistence(conversation_id),
+            sender_id: user.user_id,
             content: request.content,
+            kind,
+            reply_to: request.reply_to.map(MessageId::from_persistence),
+            send_at: request.send_at,
+        })
+        .await?;
+
+    let view = ScheduledMessageView {
+        id: scheduled.id().to_string(),
+        conversation_id: scheduled.conversation_id().to_string(),
+        content: scheduled.content().clone(),
+        kind: request.kind.unwrap_or_else(|| "text".to_string()),
+        reply_to: scheduled.reply_to().as_ref().map(|id| id.to_string()),
+        send_at: *scheduled.send_at(),
+        created_at: *scheduled.created_at(),
+        status: "pending".to_string(),
+        failure: None,
+    };
+
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+pub async fn query_scheduled(State(state): State<Arc<AppState>>, user: AuthenticatedUser) -> Result<impl IntoResponse, AppError> {
+    let scheduled = state.views.scheduled(&user.user_id).await?;
+
+    Ok(Json(scheduled))
+}
+
+pub async fn cancel_scheduled_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .cancel_scheduled_message
+        .handle(CancelScheduledMessageCommand {
+            id: ScheduledMessageId::from_persistence(id),
+            user_id: user.user_id,
         })
         .await?;
 
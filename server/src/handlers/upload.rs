@@ -4,16 +4,26 @@ use axum::{Json, extract::Multipart, extract::State, http::StatusCode, response:
 use serde::Serialize;
 use uuid::Uuid;
 
-use crate::{AppState, domain::errors::DomainError, errors::AppError};
+use crate::{
+    AppState,
+    domain::{attachment::Attachment, errors::DomainError, repository::UploadRepository},
+    errors::AppError,
+    handlers::auth::AuthenticatedUser,
+    infrastructure::audio::probe_voice_note,
+};
 
-const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
 
 #[derive(Serialize)]
 pub struct UploadResponse {
     pub url: String,
 }
 
-pub async fn upload_image(State(state): State<Arc<AppState>>, mut multipart: Multipart) -> Result<impl IntoResponse, AppError> {
+pub async fn upload_image(
+    State(state): State<Arc<AppState>>,
+    _user: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
     let Some(field) = multipart.next_field().await? else {
         return Err(AppError::bad_request("no file provided"));
     };
@@ -43,3 +53,142 @@ pub async fn upload_image(State(state): State<Arc<AppState>>, mut multipart: Mul
 
     Ok((StatusCode::CREATED, Json(UploadResponse { url })))
 }
+
+#[derive(Serialize)]
+pub struct FileUploadResponse {
+    pub url: String,
+    pub filename: String,
+    pub size_bytes: i64,
+    pub mime_type: String,
+}
+
+// anything on the configured allowlist; the stored name is ours, the original one is kept with the
+// size and type we saw so file messages can't claim different ones
+pub async fn upload_file(
+    State(state): State<Arc<AppState>>,
+    _user: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(field) = multipart.next_field().await? else {
+        return Err(AppError::bad_request("no file provided"));
+    };
+
+    let mime_type = field
+        .content_type()
+        .and_then(|t| t.split(';').next())
+        .unwrap_or("application/octet-stream")
+        .trim()
+        .to_ascii_lowercase();
+    if !state.allowed_file_types.contains(&mime_type) {
+        return Err(AppError::bad_request(format!("unsupported content type: {mime_type}")));
+    }
+    let original_name = field.file_name().unwrap_or_default().to_string();
+
+    let bytes = field.bytes().await?;
+    if bytes.len() > MAX_UPLOAD_BYTES {
+        return Err(AppError::bad_request("file too large"));
+    }
+    let attachment = Attachment::new(original_name, bytes.len() as i64, mime_type)?;
+
+    let filename = format!("{}.{}", Uuid::now_v7(), extension_of(attachment.filename()));
+    let path = std::path::Path::new(&state.file_dir).join(&filename);
+
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+    let url = format!("{}/files/{filename}", state.public_url);
+    state
+        .uploads
+        .save_file(&url, &attachment)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(FileUploadResponse {
+            url,
+            filename: attachment.filename().clone(),
+            size_bytes: *attachment.size_bytes(),
+            mime_type: attachment.mime_type().clone(),
+        }),
+    ))
+}
+
+#[derive(Serialize)]
+pub struct VoiceNoteUploadResponse {
+    pub url: String,
+    pub duration_ms: i32,
+    pub peaks: Vec<u8>,
+}
+
+// recordings are measured before they're kept and the measurements stored with them, so a voice note
+// sent with the returned url gets our duration and waveform. they sit with the images because players
+// stream them inline
+pub async fn upload_audio(
+    State(state): State<Arc<AppState>>,
+    _user: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(field) = multipart.next_field().await? else {
+        return Err(AppError::bad_request("no file provided"));
+    };
+
+    // browsers label recordings with the codec, e.g. audio/webm;codecs=opus
+    let content_type = field
+        .content_type()
+        .and_then(|t| t.split(';').next())
+        .unwrap_or("application/octet-stream")
+        .trim()
+        .to_ascii_lowercase();
+    let extension = match content_type.as_str() {
+        "audio/ogg" | "audio/opus" => "ogg",
+        "audio/webm" => "webm",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => "m4a",
+        other => return Err(AppError::bad_request(format!("unsupported content type: {other}"))),
+    };
+
+    let bytes = field.bytes().await?;
+    if bytes.len() > MAX_UPLOAD_BYTES {
+        return Err(AppError::bad_request("file too large"));
+    }
+
+    let recording = bytes.to_vec();
+    let voice_note = tokio::task::spawn_blocking(move || probe_voice_note(recording, extension))
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .map_err(|e| AppError::bad_request(e.to_string()))?;
+
+    let filename = format!("{}.{extension}", Uuid::now_v7());
+    let path = std::path::Path::new(&state.upload_dir).join(&filename);
+
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+    let url = format!("{}/media/{filename}", state.public_url);
+    state
+        .uploads
+        .save_voice_note(&url, &voice_note)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(VoiceNoteUploadResponse {
+            url,
+            duration_ms: *voice_note.duration_ms(),
+            peaks: voice_note.peaks().clone(),
+        }),
+    ))
+}
+
+// keeps a short alphanumeric extension so downloads still open with the right program
+fn extension_of(filename: &str) -> String {
+    match filename.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && (1..=10).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            ext.to_ascii_lowercase()
+        }
+        _ => "bin".to_string(),
+    }
+}
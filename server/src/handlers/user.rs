@@ -1,22 +1,64 @@
 use std::sync::Arc;
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     AppState,
-    application::commands::create_user::CreateUserCommand,
-    domain::{ids::UserId, repository::UserRepository},
+    application::commands::{
+        block_user::BlockUserCommand, change_password::ChangePasswordCommand, confirm_totp::ConfirmTotpCommand,
+        disable_totp::DisableTotpCommand, enroll_totp::EnrollTotpCommand, register::RegisterCommand, unblock_user::UnblockUserCommand,
+    },
+    domain::{
+        errors::DomainError,
+        ids::UserId,
+        repository::{BlockRepository, UserRepository},
+        user::User,
+    },
     errors::AppError,
+    handlers::auth::AuthenticatedUser,
 };
 
 #[derive(Deserialize)]
-pub struct CreateUserRequest {
-    pub id: Option<String>,
+pub struct RegisterRequest {
     pub username: String,
     pub display_name: String,
+    pub password: String,
+}
+
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct DisableTotpRequest {
+    pub password: String,
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct TotpSetupResponse {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -27,47 +69,161 @@ pub struct UserResponse {
     pub created_at: DateTime<Utc>,
 }
 
-pub async fn create_or_read_user(
+impl From<&User> for UserResponse {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id().to_string(),
+            username: user.username().as_str().to_string(),
+            display_name: user.display_name().as_str().to_string(),
+            created_at: *user.created_at(),
+        }
+    }
+}
+
+pub async fn register_user(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<CreateUserRequest>,
+    Json(request): Json<RegisterRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let id = request
-        .id
-        .and_then(|id| Uuid::parse_str(&id).ok())
-        .map(UserId::from_persistence);
-
     let user = state
-        .create_user
-        .handle(CreateUserCommand {
-            id,
+        .register
+        .handle(RegisterCommand {
             username: request.username,
             display_name: request.display_name,
+            password: request.password,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(UserResponse::from(&user))))
+}
+
+pub async fn change_password(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<ChangePasswordRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .change_password
+        .handle(ChangePasswordCommand {
+            user_id: user.user_id,
+            session_id: user.session_id,
+            current_password: request.current_password,
+            new_password: request.new_password,
         })
         .await?;
 
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn enroll_totp(State(state): State<Arc<AppState>>, user: AuthenticatedUser) -> Result<impl IntoResponse, AppError> {
+    let setup = state.enroll_totp.handle(EnrollTotpCommand { user_id: user.user_id }).await?;
+
     Ok((
         StatusCode::CREATED,
-        Json(UserResponse {
-            id: user.id().to_string(),
-            username: user.username().as_str().to_string(),
-            display_name: user.display_name().as_str().to_string(),
-            created_at: *user.created_at(),
+        Json(TotpSetupResponse {
+            secret: setup.secret,
+            provisioning_uri: setup.provisioning_uri,
         }),
     ))
 }
 
-pub async fn get_users(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, AppError> {
-    let users = state.users.find_all().await.map_err(|e| crate::domain::errors::DomainError::Internal(e.to_string()))?;
+pub async fn confirm_totp(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<TotpCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let recovery_codes = state
+        .confirm_totp
+        .handle(ConfirmTotpCommand {
+            user_id: user.user_id,
+            code: request.code,
+        })
+        .await?;
 
-    let response: Vec<UserResponse> = users
-        .into_iter()
-        .map(|u| UserResponse {
-            id: u.id().to_string(),
-            username: u.username().as_str().to_string(),
-            display_name: u.display_name().as_str().to_string(),
-            created_at: *u.created_at(),
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+pub async fn disable_totp(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<DisableTotpRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .disable_totp
+        .handle(DisableTotpCommand {
+            user_id: user.user_id,
+            password: request.password,
+            code: request.code,
         })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn get_users(State(state): State<Arc<AppState>>, _user: AuthenticatedUser) -> Result<impl IntoResponse, AppError> {
+    let users = state.users.find_all().await.map_err(|e| DomainError::Internal(e.to_string()))?;
+
+    let response: Vec<UserResponse> = users.iter().map(UserResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+fn parse_user_id(value: &str) -> Result<UserId, AppError> {
+    Uuid::parse_str(value)
+        .map(UserId::from_persistence)
+        .map_err(|_| AppError::not_found(format!("invalid id: {value}")))
+}
+
+pub async fn query_blocks(State(state): State<Arc<AppState>>, user: AuthenticatedUser) -> Result<impl IntoResponse, AppError> {
+    let blocks = state
+        .blocks
+        .find_by_blocker(&user.user_id)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?;
+    let ids: Vec<UserId> = blocks.iter().map(|b| b.blocked_id().clone()).collect();
+    let users = state
+        .users
+        .find_many(&ids)
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+    // newest block first, as the repository returned them
+    let response: Vec<UserResponse> = ids
+        .iter()
+        .filter_map(|id| users.iter().find(|u| u.id() == id))
+        .map(UserResponse::from)
         .collect();
 
     Ok(Json(response))
 }
+
+pub async fn block_user(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .block_user
+        .handle(BlockUserCommand {
+            blocker_id: user.user_id,
+            blocked_id: parse_user_id(&user_id)?,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unblock_user(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .unblock_user
+        .handle(UnblockUserCommand {
+            blocker_id: user.user_id,
+            blocked_id: parse_user_id(&user_id)?,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,4 +1,8 @@
+pub mod audio;
+pub mod crypto;
 pub mod events;
+pub mod jobs;
+pub mod media;
 pub mod postgres;
 pub mod projections;
 pub mod websocket;
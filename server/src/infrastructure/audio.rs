@@ -0,0 +1,219 @@
+use std::io::{Cursor, ErrorKind};
+
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{CODEC_TYPE_NULL, DecoderOptions},
+    errors::Error as SymphoniaError,
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+    units::TimeBase,
+};
+use thiserror::Error;
+
+use crate::domain::{
+    errors::DomainError,
+    voice_note::{VoiceNote, WAVEFORM_PEAKS},
+};
+
+#[derive(Debug, Error)]
+pub enum AudioError {
+    #[error("not a recording we can read: {0}")]
+    Unreadable(#[from] SymphoniaError),
+    #[error("recording has no audio track")]
+    NoAudio,
+    #[error(transparent)]
+    Invalid(#[from] DomainError),
+}
+
+// a packet's position and length in the track's time base, and how loud it is on whatever scale
+// the track allows
+struct Level {
+    ts: u64,
+    dur: u64,
+    level: f32,
+}
+
+// measures a recording by reading it through once: the duration is where the last packet ends, so
+// it holds even for browser recordings whose headers never got one. symphonia has no opus decoder,
+// so unless the codec decodes, packet sizes stand in for loudness; VBR encoders spend next to
+// nothing on silence, which is what the waveform needs to show
+pub fn probe_voice_note(bytes: Vec<u8>, extension: &str) -> Result<VoiceNote, AudioError> {
+    let mut hint = Hint::new();
+    hint.with_extension(extension);
+    let source = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+    let mut format = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())?
+        .format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or(AudioError::NoAudio)?;
+    let track_id = track.id;
+    let time_base = track
+        .codec_params
+        .time_base
+        .or_else(|| track.codec_params.sample_rate.map(|rate| TimeBase::new(1, rate)))
+        .ok_or(AudioError::NoAudio)?;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .ok();
+
+    let mut levels = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let level = match decoder.as_mut() {
+            Some(decoder) => match decoder.decode(&packet) {
+                Ok(decoded) => {
+                    let mut samples = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+                    samples.copy_interleaved_ref(decoded);
+                    samples.samples().iter().fold(0.0, |peak: f32, sample| peak.max(sample.abs()))
+                }
+                // a damaged frame is a gap in the waveform, not a reason to turn the upload away
+                Err(SymphoniaError::DecodeError(_)) => 0.0,
+                Err(e) => return Err(e.into()),
+            },
+            None => packet.data.len() as f32,
+        };
+        // webm blocks usually leave their duration out; recorders write evenly spaced frames, so the
+        // gap since the previous one is a good stand-in
+        let dur = match packet.dur() {
+            0 => levels.last().map_or(0, |previous: &Level| packet.ts().saturating_sub(previous.ts)),
+            dur => dur,
+        };
+        levels.push(Level {
+            ts: packet.ts(),
+            dur,
+            level,
+        });
+    }
+
+    let end = levels.iter().map(|l| l.ts + l.dur).max().unwrap_or(0);
+    let time = time_base.calc_time(end);
+    let duration_ms = time.seconds.saturating_mul(1000) + (time.frac * 1000.0).round() as u64;
+    Ok(VoiceNote::new(
+        i32::try_from(duration_ms).unwrap_or(i32::MAX),
+        waveform(&levels, end),
+    )?)
+}
+
+// the loudest level in each of WAVEFORM_PEAKS equal slices of the recording, scaled to 0..=255.
+// a packet counts towards every slice it overlaps, so short clips don't come out full of holes
+fn waveform(levels: &[Level], end: u64) -> Vec<u8> {
+    let mut peaks = [0.0f32; WAVEFORM_PEAKS];
+    if end > 0 {
+        let scaled = |ts: u64| ts as u128 * WAVEFORM_PEAKS as u128;
+        for level in levels {
+            let first = ((scaled(level.ts) / end as u128) as usize).min(WAVEFORM_PEAKS - 1);
+            let last = (scaled(level.ts + level.dur).div_ceil(end as u128) as usize).clamp(first + 1, WAVEFORM_PEAKS);
+            for peak in &mut peaks[first..last] {
+                *peak = peak.max(level.level);
+            }
+        }
+    }
+
+    let loudest = peaks.iter().copied().fold(0.0, f32::max);
+    peaks
+        .iter()
+        .map(|&peak| if loudest > 0.0 { (peak / loudest * 255.0).round() as u8 } else { 0 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+    use super::*;
+
+    // 48kHz samples in one 20ms opus frame
+    const FRAME: u64 = 960;
+
+    // an ogg opus stream of 20ms packets, one per page, whose sizes are the given byte counts.
+    // the payload is never decoded so it doesn't need to be real audio
+    fn ogg_opus(packet_sizes: &[usize]) -> Vec<u8> {
+        let mut writer = PacketWriter::new(Vec::new());
+        let mut head = b"OpusHead".to_vec();
+        head.extend_from_slice(&[1, 1, 0, 0, 0x80, 0xbb, 0, 0, 0, 0, 0]);
+        writer
+            .write_packet(head.into_boxed_slice(), 1, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+        let mut tags = b"OpusTags".to_vec();
+        tags.extend_from_slice(&[0; 8]);
+        writer
+            .write_packet(tags.into_boxed_slice(), 1, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+
+        for (i, &size) in packet_sizes.iter().enumerate() {
+            // TOC byte: SILK narrowband, one 20ms frame
+            let mut packet = vec![0x08];
+            packet.resize(size, 0x55);
+            let end = if i + 1 == packet_sizes.len() {
+                PacketWriteEndInfo::EndStream
+            } else {
+                PacketWriteEndInfo::EndPage
+            };
+            writer
+                .write_packet(packet.into_boxed_slice(), 1, end, (i as u64 + 1) * FRAME)
+                .unwrap();
+        }
+        writer.into_inner()
+    }
+
+    #[test]
+    fn probes_duration_and_waveform_of_an_opus_recording() {
+        let mut sizes = vec![3; 50];
+        sizes.extend(vec![60; 50]);
+
+        let voice_note = probe_voice_note(ogg_opus(&sizes), "ogg").unwrap();
+
+        assert_eq!(*voice_note.duration_ms(), 2000);
+        assert_eq!(voice_note.peaks().len(), WAVEFORM_PEAKS);
+        assert!(voice_note.peaks()[..WAVEFORM_PEAKS / 2 - 1].iter().all(|&peak| peak < 20));
+        assert!(voice_note.peaks()[WAVEFORM_PEAKS / 2 + 1..].iter().all(|&peak| peak == 255));
+    }
+
+    #[test]
+    fn rejects_anything_that_is_not_audio() {
+        assert!(matches!(
+            probe_voice_note(b"%PDF-1.4 definitely not a recording".to_vec(), "ogg"),
+            Err(AudioError::Unreadable(_))
+        ));
+    }
+
+    #[test]
+    fn waveform_spreads_long_packets_over_every_slice_they_cover() {
+        let levels = [
+            Level {
+                ts: 0,
+                dur: 10,
+                level: 0.5,
+            },
+            Level {
+                ts: 10,
+                dur: 10,
+                level: 1.0,
+            },
+        ];
+
+        let peaks = waveform(&levels, 20);
+
+        assert!(peaks[..WAVEFORM_PEAKS / 2].iter().all(|&peak| peak == 128));
+        assert!(peaks[WAVEFORM_PEAKS / 2..].iter().all(|&peak| peak == 255));
+    }
+
+    #[test]
+    fn waveform_of_silence_is_flat() {
+        assert_eq!(waveform(&[], 0), vec![0; WAVEFORM_PEAKS]);
+        assert_eq!(waveform(&[Level { ts: 0, dur: 5, level: 0.0 }], 5), vec![0; WAVEFORM_PEAKS]);
+    }
+}
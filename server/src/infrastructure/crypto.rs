@@ -0,0 +1,95 @@
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use thiserror::Error;
+
+const KEY_BYTES: usize = 32;
+const NONCE_BYTES: usize = 12;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CryptoError {
+    #[error("encryption key must be {KEY_BYTES} bytes of base64")]
+    InvalidKey,
+    #[error("ciphertext could not be decrypted")]
+    Decrypt,
+}
+
+// AES-256-GCM for secrets we must be able to read back, unlike passwords and tokens which are hashed
+#[derive(Clone)]
+pub struct SecretCipher {
+    cipher: Aes256Gcm,
+}
+
+impl SecretCipher {
+    pub fn from_base64_key(key: &str) -> Result<Self, CryptoError> {
+        let bytes = STANDARD.decode(key.trim()).map_err(|_| CryptoError::InvalidKey)?;
+        if bytes.len() != KEY_BYTES {
+            return Err(CryptoError::InvalidKey);
+        }
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)),
+        })
+    }
+
+    // returns (ciphertext, nonce); a fresh nonce per call so equal plaintexts never share ciphertext
+    pub fn encrypt(&self, plaintext: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let nonce: [u8; NONCE_BYTES] = rand::random();
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .expect("aes-gcm encryption of an in-memory buffer cannot fail");
+        (ciphertext, nonce.to_vec())
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8], nonce: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if nonce.len() != NONCE_BYTES {
+            return Err(CryptoError::Decrypt);
+        }
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| CryptoError::Decrypt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> String {
+        STANDARD.encode([7u8; KEY_BYTES])
+    }
+
+    #[test]
+    fn from_base64_key_rejects_wrong_length() {
+        let short = STANDARD.encode([7u8; 16]);
+
+        assert_eq!(SecretCipher::from_base64_key(&short).err(), Some(CryptoError::InvalidKey));
+        assert_eq!(SecretCipher::from_base64_key("not base64!").err(), Some(CryptoError::InvalidKey));
+    }
+
+    #[test]
+    fn encrypt_round_trips_with_fresh_nonces() {
+        let cipher = SecretCipher::from_base64_key(&key()).unwrap();
+
+        let (first, first_nonce) = cipher.encrypt(b"secret");
+        let (second, second_nonce) = cipher.encrypt(b"secret");
+
+        assert_ne!(first, second);
+        assert_ne!(first_nonce, second_nonce);
+        assert_eq!(cipher.decrypt(&first, &first_nonce).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn decrypt_rejects_tampered_ciphertext_and_other_keys() {
+        let cipher = SecretCipher::from_base64_key(&key()).unwrap();
+        let other = SecretCipher::from_base64_key(&STANDARD.encode([9u8; KEY_BYTES])).unwrap();
+        let (mut ciphertext, nonce) = cipher.encrypt(b"secret");
+
+        assert_eq!(other.decrypt(&ciphertext, &nonce).err(), Some(CryptoError::Decrypt));
+        ciphertext[0] ^= 1;
+        assert_eq!(cipher.decrypt(&ciphertext, &nonce).err(), Some(CryptoError::Decrypt));
+    }
+}
@@ -0,0 +1,5 @@
+mod expired_messages;
+mod scheduled_messages;
+
+pub use expired_messages::spawn_message_reaper;
+pub use scheduled_messages::spawn_scheduled_message_dispatcher;
@@ -0,0 +1,34 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{
+    application::commands::expire_messages::ExpireMessagesHandler,
+    domain::{
+        clock::Clock,
+        repository::{EventPublisher, MediaStore, MessageRepository},
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+pub fn spawn_message_reaper<M, F, P, Clk>(reaper: Arc<ExpireMessagesHandler<M, F, P, Clk>>) -> JoinHandle<()>
+where
+    M: MessageRepository + 'static,
+    F: MediaStore + 'static,
+    P: EventPublisher + 'static,
+    Clk: Clock + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match reaper.handle().await {
+                Ok(0) => {}
+                Ok(removed) => tracing::debug!("removed {removed} expired messages"),
+                Err(err) => tracing::warn!("failed to remove expired messages: {err}"),
+            }
+        }
+    })
+}
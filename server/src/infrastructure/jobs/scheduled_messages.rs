@@ -0,0 +1,45 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{
+    application::commands::dispatch_scheduled_messages::DispatchScheduledMessagesHandler,
+    domain::{
+        clock::Clock,
+        repository::{
+            BlockRepository, ConversationRepository, EventPublisher, MessageRepository, ScheduledMessageRepository, UploadRepository,
+            UserRepository,
+        },
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// the queue lives in the database, so anything that came due while the server was down goes out on the first tick
+#[allow(clippy::type_complexity)]
+pub fn spawn_scheduled_message_dispatcher<C, M, U, B, F, P, S, Clk>(
+    dispatcher: Arc<DispatchScheduledMessagesHandler<C, M, U, B, F, P, S, Clk>>,
+) -> JoinHandle<()>
+where
+    C: ConversationRepository + 'static,
+    M: MessageRepository + 'static,
+    U: UserRepository + 'static,
+    B: BlockRepository + 'static,
+    F: UploadRepository + 'static,
+    P: EventPublisher + 'static,
+    S: ScheduledMessageRepository + 'static,
+    Clk: Clock + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match dispatcher.handle().await {
+                Ok(0) => {}
+                Ok(sent) => tracing::debug!("sent {sent} scheduled messages"),
+                Err(err) => tracing::warn!("failed to dispatch scheduled messages: {err}"),
+            }
+        }
+    })
+}
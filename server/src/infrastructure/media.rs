@@ -0,0 +1,87 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use async_trait::async_trait;
+
+use crate::domain::repository::{MediaStore, RepoError};
+
+// images in upload_dir, served back as {public_url}/media/{filename}, and other files in file_dir,
+// served as downloads from {public_url}/files/{filename}
+#[derive(Clone)]
+pub struct LocalMediaStore {
+    upload_dir: PathBuf,
+    file_dir: PathBuf,
+    public_url: String,
+}
+
+impl LocalMediaStore {
+    pub fn new(upload_dir: impl Into<PathBuf>, file_dir: impl Into<PathBuf>, public_url: impl Into<String>) -> Self {
+        Self {
+            upload_dir: upload_dir.into(),
+            file_dir: file_dir.into(),
+            public_url: public_url.into(),
+        }
+    }
+
+    fn stored_path(&self, url: &str) -> Option<PathBuf> {
+        if let Some(filename) = stored_name(&self.public_url, "/media/", url) {
+            Some(self.upload_dir.join(filename))
+        } else {
+            stored_name(&self.public_url, "/files/", url).map(|filename| self.file_dir.join(filename))
+        }
+    }
+}
+
+// the name a url was stored under if it's served from that route of ours. stored names are flat, so
+// anything with a separator or a leading dot can't be one of them
+pub fn stored_name<'a>(public_url: &str, route: &str, url: &'a str) -> Option<&'a str> {
+    let filename = url.strip_prefix(public_url)?.strip_prefix(route)?;
+    if filename.is_empty() || filename.starts_with('.') || filename.contains(['/', '\\']) {
+        return None;
+    }
+    Some(filename)
+}
+
+#[async_trait]
+impl MediaStore for LocalMediaStore {
+    async fn delete(&self, url: &str) -> Result<(), RepoError> {
+        let Some(path) = self.stored_path(url) else { return Ok(()) };
+
+        match tokio::fs::remove_file(path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> LocalMediaStore {
+        LocalMediaStore::new("uploads", "files", "http://127.0.0.1:3000")
+    }
+
+    #[test]
+    fn resolves_urls_for_stored_files() {
+        assert_eq!(
+            store().stored_path("http://127.0.0.1:3000/media/abc.png"),
+            Some(PathBuf::from("uploads/abc.png"))
+        );
+        assert_eq!(
+            store().stored_path("http://127.0.0.1:3000/files/abc.pdf"),
+            Some(PathBuf::from("files/abc.pdf"))
+        );
+    }
+
+    #[test]
+    fn ignores_foreign_urls_and_path_tricks() {
+        let store = store();
+
+        assert_eq!(store.stored_path("https://example.com/media/abc.png"), None);
+        assert_eq!(store.stored_path("http://127.0.0.1:3000/media/../secrets"), None);
+        assert_eq!(store.stored_path("http://127.0.0.1:3000/media/a/b.png"), None);
+        assert_eq!(store.stored_path("http://127.0.0.1:3000/media/"), None);
+        assert_eq!(store.stored_path("http://127.0.0.1:3000/files/../media/abc.png"), None);
+        assert_eq!(store.stored_path("http://127.0.0.1:3000/other/abc.pdf"), None);
+    }
+}
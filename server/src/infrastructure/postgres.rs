@@ -1,4 +1,18 @@
+pub mod block_repository;
 pub mod conversation_repository;
+pub mod credential_repository;
+pub mod invitation_repository;
+pub mod invite_repository;
+pub mod list_entry_repository;
+pub mod login_challenge_repository;
 pub mod message_repository;
+pub mod notification_settings_repository;
+pub mod pin_repository;
+pub mod poll_vote_repository;
 pub mod queries;
+pub mod reaction_repository;
+pub mod scheduled_message_repository;
+pub mod session_repository;
+pub mod totp_repository;
+pub mod upload_repository;
 pub mod user_repository;
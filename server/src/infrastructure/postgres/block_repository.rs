@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::block::Block;
+use crate::domain::ids::UserId;
+use crate::domain::repository::{BlockRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxBlockRepository {
+    pool: PgPool,
+}
+
+impl SqlxBlockRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BlockRepository for SqlxBlockRepository {
+    async fn find_by_blocker(&self, blocker_id: &UserId) -> Result<Vec<Block>, RepoError> {
+        let rows = sqlx::query!(
+            "SELECT blocker_id, blocked_id, created_at FROM user_blocks WHERE blocker_id = $1 ORDER BY created_at DESC",
+            Uuid::from(blocker_id.clone())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                Block::from_persistence(
+                    UserId::from_persistence(r.blocker_id),
+                    UserId::from_persistence(r.blocked_id),
+                    r.created_at,
+                )
+            })
+            .collect())
+    }
+
+    async fn is_blocked_between(&self, a: &UserId, b: &UserId) -> Result<bool, RepoError> {
+        let (a, b) = (Uuid::from(a.clone()), Uuid::from(b.clone()));
+        let blocked = sqlx::query_scalar!(
+            "SELECT EXISTS (
+                SELECT 1 FROM user_blocks
+                WHERE (blocker_id = $1 AND blocked_id = $2) OR (blocker_id = $2 AND blocked_id = $1)
+             ) AS \"blocked!\"",
+            a,
+            b
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(blocked)
+    }
+
+    async fn save(&self, block: &Block) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO user_blocks (blocker_id, blocked_id, created_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (blocker_id, blocked_id) DO NOTHING",
+            Uuid::from(block.blocker_id().clone()),
+            Uuid::from(block.blocked_id().clone()),
+            *block.created_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, blocker_id: &UserId, blocked_id: &UserId) -> Result<(), RepoError> {
+        sqlx::query!(
+            "DELETE FROM user_blocks WHERE blocker_id = $1 AND blocked_id = $2",
+            Uuid::from(blocker_id.clone()),
+            Uuid::from(blocked_id.clone())
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
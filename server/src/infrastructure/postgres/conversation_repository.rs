@@ -1,9 +1,11 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use chrono::Duration;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::domain::conversation::{Conversation, ConversationKind, Participant};
+use crate::domain::conversation::{Conversation, ConversationKind, Participant, ParticipantRole};
 use crate::domain::ids::{ConversationId, UserId};
+use crate::domain::invitation::Invitation;
 use crate::domain::repository::{ConversationRepository, RepoError};
 
 #[derive(Clone)]
@@ -21,7 +23,8 @@ impl SqlxConversationRepository {
 impl ConversationRepository for SqlxConversationRepository {
     async fn find_by_id(&self, id: &ConversationId) -> Result<Option<Conversation>, RepoError> {
         let conv = sqlx::query!(
-            "SELECT id, kind AS \"kind: ConversationKind\", title, created_at FROM conversations WHERE id = $1",
+            "SELECT id, kind AS \"kind: ConversationKind\", title, description, avatar_url, message_ttl_seconds, created_at
+             FROM conversations WHERE id = $1",
             Uuid::from(id.clone())
         )
         .fetch_optional(&self.pool)
@@ -29,7 +32,7 @@ impl ConversationRepository for SqlxConversationRepository {
         let Some(conv) = conv else { return Ok(None) };
 
         let parts = sqlx::query!(
-            "SELECT user_id, joined_at FROM user_conversations WHERE conversation_id = $1",
+            "SELECT user_id, role AS \"role: ParticipantRole\", joined_at FROM user_conversations WHERE conversation_id = $1",
             Uuid::from(id.clone())
         )
         .fetch_all(&self.pool)
@@ -39,6 +42,7 @@ impl ConversationRepository for SqlxConversationRepository {
             .into_iter()
             .map(|p| Participant {
                 user_id: UserId::from_persistence(p.user_id),
+                role: p.role,
                 joined_at: p.joined_at,
             })
             .collect();
@@ -47,46 +51,47 @@ impl ConversationRepository for SqlxConversationRepository {
             ConversationId::from_persistence(conv.id),
             conv.kind,
             conv.title,
+            conv.description,
+            conv.avatar_url,
             participants,
+            conv.message_ttl_seconds.map(|secs| Duration::seconds(secs.into())),
             conv.created_at,
         )))
     }
 
-    async fn save(&self, c: &Conversation) -> Result<(), RepoError> {
-        let mut tx = self.pool.begin().await?;
-
-        // note: no updated_at in this statement — projector owns that column
-        sqlx::query!(
-            "INSERT INTO conversations (id, kind, title, created_at)
-               VALUES ($1, $2::conversation_kind, $3, $4)
-               ON CONFLICT (id) DO UPDATE SET title = $3",
-            Uuid::from(c.id().clone()),
-            c.kind().clone() as _,
-            c.title().as_deref(),
-            *c.created_at()
+    async fn find_direct_between(&self, a: &UserId, b: &UserId) -> Result<Option<Conversation>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT conversation_id FROM direct_conversations
+               WHERE user_low = LEAST($1::uuid, $2::uuid) AND user_high = GREATEST($1::uuid, $2::uuid)",
+            Uuid::from(a.clone()),
+            Uuid::from(b.clone())
         )
-        .execute(&mut *tx)
+        .fetch_optional(&self.pool)
         .await?;
+        let Some(row) = row else { return Ok(None) };
 
-        let current_ids: Vec<Uuid> = c.participants().iter().map(|p| Uuid::from(p.user_id.clone())).collect();
+        self.find_by_id(&ConversationId::from_persistence(row.conversation_id)).await
+    }
 
-        // drop rows for participants no longer in the aggregate (e.g. after remove_participant)
-        sqlx::query!(
-            "DELETE FROM user_conversations WHERE conversation_id = $1 AND user_id <> ALL($2)",
-            Uuid::from(c.id().clone()),
-            &current_ids
-        )
-        .execute(&mut *tx)
-        .await?;
+    async fn save(&self, c: &Conversation) -> Result<(), RepoError> {
+        let mut tx = self.pool.begin().await?;
+        write_conversation(&mut tx, c).await?;
+        tx.commit().await?;
+        Ok(())
+    }
 
-        for participant in c.participants() {
+    async fn create_group(&self, c: &Conversation, invitations: &[Invitation]) -> Result<(), RepoError> {
+        let mut tx = self.pool.begin().await?;
+        write_conversation(&mut tx, c).await?;
+
+        for invitation in invitations {
             sqlx::query!(
-                "INSERT INTO user_conversations (user_id, conversation_id, joined_at)
-                   VALUES ($1, $2, $3)
-                   ON CONFLICT (user_id, conversation_id) DO NOTHING",
-                Uuid::from(participant.user_id.clone()),
-                Uuid::from(c.id().clone()),
-                participant.joined_at
+                "INSERT INTO conversation_invitations (conversation_id, user_id, invited_by, created_at)
+                 VALUES ($1, $2, $3, $4)",
+                Uuid::from(invitation.conversation_id().clone()),
+                Uuid::from(invitation.user_id().clone()),
+                Uuid::from(invitation.invited_by().clone()),
+                *invitation.created_at()
             )
             .execute(&mut *tx)
             .await?;
@@ -96,3 +101,76 @@ impl ConversationRepository for SqlxConversationRepository {
         Ok(())
     }
 }
+
+pub(crate) async fn write_conversation(tx: &mut Transaction<'_, Postgres>, c: &Conversation) -> Result<(), RepoError> {
+    // note: no updated_at in this statement — projector owns that column
+    sqlx::query!(
+        "INSERT INTO conversations (id, kind, title, description, avatar_url, message_ttl_seconds, created_at)
+               VALUES ($1, $2::conversation_kind, $3, $4, $5, $6, $7)
+               ON CONFLICT (id) DO UPDATE SET title = $3, description = $4, avatar_url = $5, message_ttl_seconds = $6",
+        Uuid::from(c.id().clone()),
+        c.kind().clone() as _,
+        c.title().as_deref(),
+        c.description().as_deref(),
+        c.avatar_url().as_deref(),
+        c.message_ttl().map(|ttl| ttl.num_seconds() as i32),
+        *c.created_at()
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    // only the rows this copy changed, so two requests adding or removing different people at
+    // the same time don't undo each other. a role change never brings back someone who just left
+    let changes = c.participant_changes();
+    let left: Vec<Uuid> = changes.left.iter().map(|&user_id| Uuid::from(user_id.clone())).collect();
+    sqlx::query!(
+        "DELETE FROM user_conversations WHERE conversation_id = $1 AND user_id = ANY($2)",
+        Uuid::from(c.id().clone()),
+        &left
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    for participant in changes.joined {
+        sqlx::query!(
+            "INSERT INTO user_conversations (user_id, conversation_id, role, joined_at)
+                   VALUES ($1, $2, $3, $4)
+                   ON CONFLICT (user_id, conversation_id) DO NOTHING",
+            Uuid::from(participant.user_id.clone()),
+            Uuid::from(c.id().clone()),
+            participant.role as _,
+            participant.joined_at
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    for participant in changes.role_changed {
+        sqlx::query!(
+            "UPDATE user_conversations SET role = $3 WHERE user_id = $1 AND conversation_id = $2",
+            Uuid::from(participant.user_id.clone()),
+            Uuid::from(c.id().clone()),
+            participant.role as _
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    // a second direct conversation for the same pair fails here on the primary key
+    if matches!(c.kind(), ConversationKind::Direct)
+        && let [a, b] = c.participants().as_slice()
+    {
+        sqlx::query!(
+            "INSERT INTO direct_conversations (user_low, user_high, conversation_id)
+                   VALUES (LEAST($1::uuid, $2::uuid), GREATEST($1::uuid, $2::uuid), $3)
+                   ON CONFLICT (conversation_id) DO NOTHING",
+            Uuid::from(a.user_id.clone()),
+            Uuid::from(b.user_id.clone()),
+            Uuid::from(c.id().clone())
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
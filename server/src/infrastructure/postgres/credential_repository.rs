@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::credentials::Credentials;
+use crate::domain::ids::UserId;
+use crate::domain::repository::{CredentialRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxCredentialRepository {
+    pool: PgPool,
+}
+
+impl SqlxCredentialRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CredentialRepository for SqlxCredentialRepository {
+    async fn find_by_user_id(&self, user_id: &UserId) -> Result<Option<Credentials>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT user_id, password_hash, updated_at FROM credentials WHERE user_id = $1",
+            Uuid::from(user_id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| Credentials::from_persistence(UserId::from_persistence(r.user_id), r.password_hash, r.updated_at)))
+    }
+
+    async fn save(&self, credentials: &Credentials) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO credentials (user_id, password_hash, updated_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_id) DO UPDATE SET password_hash = $2, updated_at = $3",
+            Uuid::from(credentials.user_id().clone()),
+            credentials.password_hash().as_str(),
+            *credentials.updated_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{ConversationId, UserId};
+use crate::domain::invitation::Invitation;
+use crate::domain::repository::{InvitationRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxInvitationRepository {
+    pool: PgPool,
+}
+
+impl SqlxInvitationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for SqlxInvitationRepository {
+    async fn find(&self, conversation_id: &ConversationId, user_id: &UserId) -> Result<Option<Invitation>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT conversation_id, user_id, invited_by, created_at
+             FROM conversation_invitations
+             WHERE conversation_id = $1 AND user_id = $2",
+            Uuid::from(conversation_id.clone()),
+            Uuid::from(user_id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            Invitation::from_persistence(
+                ConversationId::from_persistence(r.conversation_id),
+                UserId::from_persistence(r.user_id),
+                UserId::from_persistence(r.invited_by),
+                r.created_at,
+            )
+        }))
+    }
+
+    async fn delete(&self, invitation: &Invitation) -> Result<(), RepoError> {
+        sqlx::query!(
+            "DELETE FROM conversation_invitations WHERE conversation_id = $1 AND user_id = $2",
+            Uuid::from(invitation.conversation_id().clone()),
+            Uuid::from(invitation.user_id().clone())
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
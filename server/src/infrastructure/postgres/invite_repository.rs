@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::conversation::Conversation;
+use crate::domain::ids::{ConversationId, InviteId, UserId};
+use crate::domain::invite::Invite;
+use crate::domain::repository::{InviteRepository, RepoError};
+use crate::infrastructure::postgres::conversation_repository::write_conversation;
+
+#[derive(Clone)]
+pub struct SqlxInviteRepository {
+    pool: PgPool,
+}
+
+impl SqlxInviteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl InviteRepository for SqlxInviteRepository {
+    async fn find_by_id(&self, id: &InviteId) -> Result<Option<Invite>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT id, conversation_id, token, created_by, created_at, expires_at, max_uses, uses, revoked_at
+             FROM conversation_invites WHERE id = $1",
+            Uuid::from(id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            Invite::from_persistence(
+                InviteId::from_persistence(r.id),
+                ConversationId::from_persistence(r.conversation_id),
+                r.token,
+                UserId::from_persistence(r.created_by),
+                r.created_at,
+                r.expires_at,
+                r.max_uses,
+                r.uses,
+                r.revoked_at,
+            )
+        }))
+    }
+
+    async fn find_by_token(&self, token: &str) -> Result<Option<Invite>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT id, conversation_id, token, created_by, created_at, expires_at, max_uses, uses, revoked_at
+             FROM conversation_invites WHERE token = $1",
+            token
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            Invite::from_persistence(
+                InviteId::from_persistence(r.id),
+                ConversationId::from_persistence(r.conversation_id),
+                r.token,
+                UserId::from_persistence(r.created_by),
+                r.created_at,
+                r.expires_at,
+                r.max_uses,
+                r.uses,
+                r.revoked_at,
+            )
+        }))
+    }
+
+    async fn save(&self, invite: &Invite) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO conversation_invites (id, conversation_id, token, created_by, created_at, expires_at, max_uses, uses, revoked_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (id) DO UPDATE SET revoked_at = $9",
+            Uuid::from(invite.id().clone()),
+            Uuid::from(invite.conversation_id().clone()),
+            invite.token().as_str(),
+            Uuid::from(invite.created_by().clone()),
+            *invite.created_at(),
+            *invite.expires_at(),
+            *invite.max_uses(),
+            *invite.uses(),
+            *invite.revoked_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // checked and counted in one statement, so two people joining at once can't both take the last use
+    async fn record_use(&self, invite: &Invite, conversation: &Conversation, now: DateTime<Utc>) -> Result<bool, RepoError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query!(
+            "UPDATE conversation_invites SET uses = uses + 1
+             WHERE id = $1
+               AND revoked_at IS NULL
+               AND (max_uses IS NULL OR uses < max_uses)
+               AND (expires_at IS NULL OR expires_at > $2)",
+            Uuid::from(invite.id().clone()),
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        write_conversation(&mut tx, conversation).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{ConversationId, UserId};
+use crate::domain::list_entry::ListEntry;
+use crate::domain::repository::{ListEntryRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxListEntryRepository {
+    pool: PgPool,
+}
+
+impl SqlxListEntryRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ListEntryRepository for SqlxListEntryRepository {
+    async fn find(&self, conversation_id: &ConversationId, user_id: &UserId) -> Result<Option<ListEntry>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT archived_at, pinned_at FROM user_conversations WHERE conversation_id = $1 AND user_id = $2",
+            Uuid::from(conversation_id.clone()),
+            Uuid::from(user_id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| ListEntry::from_persistence(conversation_id.clone(), user_id.clone(), r.archived_at, r.pinned_at)))
+    }
+
+    async fn save(&self, entry: &ListEntry) -> Result<(), RepoError> {
+        sqlx::query!(
+            "UPDATE user_conversations SET archived_at = $3, pinned_at = $4
+             WHERE conversation_id = $1 AND user_id = $2",
+            Uuid::from(entry.conversation_id().clone()),
+            Uuid::from(entry.user_id().clone()),
+            *entry.archived_at(),
+            *entry.pinned_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{LoginChallengeId, UserId};
+use crate::domain::repository::{LoginChallengeRepository, RepoError};
+use crate::domain::session::{LOGIN_CHALLENGE_MAX_ATTEMPTS, LoginChallenge};
+
+#[derive(Clone)]
+pub struct SqlxLoginChallengeRepository {
+    pool: PgPool,
+}
+
+impl SqlxLoginChallengeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LoginChallengeRepository for SqlxLoginChallengeRepository {
+    async fn find_by_token_hash(&self, token_hash: &[u8]) -> Result<Option<LoginChallenge>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT id, user_id, token_hash, expires_at, failed_attempts FROM login_challenges WHERE token_hash = $1",
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            LoginChallenge::from_persistence(
+                LoginChallengeId::from_persistence(r.id),
+                UserId::from_persistence(r.user_id),
+                r.token_hash,
+                r.expires_at,
+                r.failed_attempts,
+            )
+        }))
+    }
+
+    async fn save(&self, challenge: &LoginChallenge) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO login_challenges (id, user_id, token_hash, expires_at, failed_attempts)
+             VALUES ($1, $2, $3, $4, $5)",
+            Uuid::from(challenge.id().clone()),
+            Uuid::from(challenge.user_id().clone()),
+            challenge.token_hash().as_slice(),
+            *challenge.expires_at(),
+            *challenge.failed_attempts()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn take_attempt(&self, id: &LoginChallengeId, now: DateTime<Utc>) -> Result<bool, RepoError> {
+        let row = sqlx::query!(
+            "UPDATE login_challenges SET failed_attempts = failed_attempts + 1
+             WHERE id = $1 AND failed_attempts < $2 AND expires_at > $3
+             RETURNING failed_attempts",
+            Uuid::from(id.clone()),
+            LOGIN_CHALLENGE_MAX_ATTEMPTS,
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.is_some())
+    }
+
+    async fn consume(&self, id: &LoginChallengeId) -> Result<bool, RepoError> {
+        let result = sqlx::query!("DELETE FROM login_challenges WHERE id = $1", Uuid::from(id.clone()))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,10 +1,14 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+use crate::domain::attachment::Attachment;
 use crate::domain::ids::{ConversationId, MessageId, UserId};
-use crate::domain::message::{Message, MessageKind};
+use crate::domain::message::{ForwardedFrom, Message, MessageKind, MessageRevision};
+use crate::domain::poll::Poll;
 use crate::domain::repository::{MessageRepository, RepoError};
+use crate::domain::voice_note::VoiceNote;
 
 #[derive(Clone)]
 pub struct SqlxMessageRepository {
@@ -17,48 +21,279 @@ impl SqlxMessageRepository {
     }
 }
 
+struct MessageRow {
+    id: Uuid,
+    conversation_id: Uuid,
+    sender_id: Uuid,
+    content: String,
+    kind: MessageKind,
+    thread_root_id: Option<Uuid>,
+    edited: bool,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    forwarded_from_message_id: Option<Uuid>,
+    forwarded_from_conversation_id: Option<Uuid>,
+    poll_options: Option<Vec<String>>,
+    poll_multiple_choice: Option<bool>,
+    poll_closes_at: Option<DateTime<Utc>>,
+    attachment_filename: Option<String>,
+    attachment_size_bytes: Option<i64>,
+    attachment_mime_type: Option<String>,
+    voice_note_duration_ms: Option<i32>,
+    voice_note_peaks: Option<Vec<u8>>,
+    mentions: Vec<Uuid>,
+}
+
+impl From<MessageRow> for Message {
+    fn from(r: MessageRow) -> Self {
+        Message::from_persistence(
+            MessageId::from_persistence(r.id),
+            ConversationId::from_persistence(r.conversation_id),
+            UserId::from_persistence(r.sender_id),
+            r.content,
+            r.kind,
+            r.thread_root_id.map(MessageId::from_persistence),
+            r.mentions.into_iter().map(UserId::from_persistence).collect(),
+            r.edited,
+            r.created_at,
+            r.updated_at,
+            r.deleted_at,
+            r.expires_at,
+            r.forwarded_from_message_id
+                .zip(r.forwarded_from_conversation_id)
+                .map(|(message_id, conversation_id)| ForwardedFrom {
+                    message_id: MessageId::from_persistence(message_id),
+                    conversation_id: ConversationId::from_persistence(conversation_id),
+                }),
+            r.poll_options
+                .zip(r.poll_multiple_choice)
+                .map(|(options, multiple_choice)| Poll::from_persistence(options, multiple_choice, r.poll_closes_at)),
+            r.attachment_filename
+                .zip(r.attachment_size_bytes)
+                .zip(r.attachment_mime_type)
+                .map(|((filename, size_bytes), mime_type)| Attachment::from_persistence(filename, size_bytes, mime_type)),
+            r.voice_note_duration_ms
+                .zip(r.voice_note_peaks)
+                .map(|(duration_ms, peaks)| VoiceNote::from_persistence(duration_ms, peaks)),
+        )
+    }
+}
+
 #[async_trait]
 impl MessageRepository for SqlxMessageRepository {
     async fn find_by_id(&self, id: &MessageId) -> Result<Option<Message>, RepoError> {
-        let row = sqlx::query!(
-            "SELECT id, conversation_id, sender_id, content, kind AS \"kind: MessageKind\", edited, created_at, updated_at
-             FROM messages WHERE id = $1",
+        let row = sqlx::query_as!(
+            MessageRow,
+            "SELECT id, conversation_id, sender_id, content, kind AS \"kind: MessageKind\", thread_root_id, edited, created_at, updated_at,
+                    deleted_at, expires_at, forwarded_from_message_id, forwarded_from_conversation_id,
+                    options AS \"poll_options?\", multiple_choice AS \"poll_multiple_choice?\", closes_at AS \"poll_closes_at?\",
+                    filename AS \"attachment_filename?\", size_bytes AS \"attachment_size_bytes?\", mime_type AS \"attachment_mime_type?\",
+                    duration_ms AS \"voice_note_duration_ms?\", peaks AS \"voice_note_peaks?\",
+                    ARRAY(SELECT user_id FROM message_mentions WHERE message_id = messages.id) AS \"mentions!\"
+             FROM messages
+             LEFT JOIN message_polls ON message_polls.message_id = messages.id
+             LEFT JOIN message_attachments ON message_attachments.message_id = messages.id
+             LEFT JOIN message_voice_notes ON message_voice_notes.message_id = messages.id
+             WHERE id = $1",
             Uuid::from(id.clone())
         )
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|r| {
-            Message::from_persistence(
-                MessageId::from_persistence(r.id),
-                ConversationId::from_persistence(r.conversation_id),
-                UserId::from_persistence(r.sender_id),
-                r.content,
-                r.kind,
-                r.edited,
-                r.created_at,
-                r.updated_at,
-            )
-        }))
+        Ok(row.map(Message::from))
+    }
+
+    async fn find_expired(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<Message>, RepoError> {
+        let rows = sqlx::query_as!(
+            MessageRow,
+            "SELECT id, conversation_id, sender_id, content, kind AS \"kind: MessageKind\", thread_root_id, edited, created_at, updated_at,
+                    deleted_at, expires_at, forwarded_from_message_id, forwarded_from_conversation_id,
+                    options AS \"poll_options?\", multiple_choice AS \"poll_multiple_choice?\", closes_at AS \"poll_closes_at?\",
+                    filename AS \"attachment_filename?\", size_bytes AS \"attachment_size_bytes?\", mime_type AS \"attachment_mime_type?\",
+                    duration_ms AS \"voice_note_duration_ms?\", peaks AS \"voice_note_peaks?\",
+                    ARRAY(SELECT user_id FROM message_mentions WHERE message_id = messages.id) AS \"mentions!\"
+             FROM messages
+             LEFT JOIN message_polls ON message_polls.message_id = messages.id
+             LEFT JOIN message_attachments ON message_attachments.message_id = messages.id
+             LEFT JOIN message_voice_notes ON message_voice_notes.message_id = messages.id
+             WHERE expires_at <= $1
+             ORDER BY expires_at
+             LIMIT $2",
+            now,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Message::from).collect())
+    }
+
+    async fn shares_media(&self, message: &Message) -> Result<bool, RepoError> {
+        let shared = sqlx::query_scalar!(
+            "SELECT EXISTS(SELECT 1 FROM messages WHERE kind IN ('image', 'file', 'audio') AND content = $1 AND id <> $2) AS \"shared!\"",
+            message.content().as_str(),
+            Uuid::from(message.id().clone())
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(shared)
+    }
+
+    async fn delete(&self, id: &MessageId) -> Result<(), RepoError> {
+        sqlx::query!("DELETE FROM messages WHERE id = $1", Uuid::from(id.clone()))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
     }
 
     async fn save(&self, message: &Message) -> Result<(), RepoError> {
-        sqlx::query!(
-            "INSERT INTO messages (id, conversation_id, sender_id, content, kind, edited, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5::message_kind, $6, $7, $8)
-             ON CONFLICT (id) DO UPDATE SET content = $4, edited = $6, updated_at = $8",
+        let mut tx = self.pool.begin().await?;
+        write_message(&mut tx, message).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn save_all(&self, messages: &[Message]) -> Result<(), RepoError> {
+        let mut tx = self.pool.begin().await?;
+        for message in messages {
+            write_message(&mut tx, message).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn save_edit(&self, message: &Message, revision: &MessageRevision) -> Result<bool, RepoError> {
+        let mut tx = self.pool.begin().await?;
+
+        // the revision holds the version it replaces; if that's no longer the one stored, another
+        // edit or a delete got in first and this one is dropped along with its revision
+        let updated = sqlx::query!(
+            "UPDATE messages SET content = $2, edited = $3, updated_at = $4
+             WHERE id = $1 AND COALESCE(updated_at, created_at) = $5 AND deleted_at IS NULL",
             Uuid::from(message.id().clone()),
-            Uuid::from(message.conversation_id().clone()),
-            Uuid::from(message.sender_id().clone()),
             message.content().as_str(),
-            message.kind().clone() as _,
             *message.edited(),
-            *message.created_at(),
-            *message.updated_at()
+            *message.updated_at(),
+            *revision.written_at()
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
 
-        Ok(())
+        sqlx::query!(
+            "INSERT INTO message_revisions (message_id, content, written_at, replaced_at)
+             VALUES ($1, $2, $3, $4)",
+            Uuid::from(revision.message_id().clone()),
+            revision.content().as_str(),
+            *revision.written_at(),
+            *revision.replaced_at()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        write_mentions(&mut tx, message).await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
+
+async fn write_message(tx: &mut Transaction<'_, Postgres>, message: &Message) -> Result<(), RepoError> {
+    sqlx::query!(
+        "INSERT INTO messages
+            (id, conversation_id, sender_id, content, kind, edited, created_at, updated_at, deleted_at, thread_root_id, expires_at,
+          forwarded_from_message_id, forwarded_from_conversation_id)
+         VALUES ($1, $2, $3, $4, $5::message_kind, $6, $7, $8, $9, $10, $11, $12, $13)
+         ON CONFLICT (id) DO UPDATE SET content = $4, edited = $6, updated_at = $8, deleted_at = $9",
+        Uuid::from(message.id().clone()),
+        Uuid::from(message.conversation_id().clone()),
+        Uuid::from(message.sender_id().clone()),
+        message.content().as_str(),
+        message.kind().clone() as _,
+        *message.edited(),
+        *message.created_at(),
+        *message.updated_at(),
+        *message.deleted_at(),
+        message.thread_root_id().clone().map(Uuid::from),
+        *message.expires_at(),
+        message.forwarded_from().as_ref().map(|f| Uuid::from(f.message_id.clone())),
+        message.forwarded_from().as_ref().map(|f| Uuid::from(f.conversation_id.clone()))
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    // a poll's options never change, so they only need writing the first time
+    if let Some(poll) = message.poll() {
+        sqlx::query!(
+            "INSERT INTO message_polls (message_id, options, multiple_choice, closes_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (message_id) DO NOTHING",
+            Uuid::from(message.id().clone()),
+            poll.options(),
+            *poll.multiple_choice(),
+            *poll.closes_at()
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    if let Some(attachment) = message.attachment() {
+        sqlx::query!(
+            "INSERT INTO message_attachments (message_id, filename, size_bytes, mime_type)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (message_id) DO NOTHING",
+            Uuid::from(message.id().clone()),
+            attachment.filename().as_str(),
+            *attachment.size_bytes(),
+            attachment.mime_type().as_str()
+        )
+        .execute(&mut **tx)
+        .await?;
     }
+
+    if let Some(voice_note) = message.voice_note() {
+        sqlx::query!(
+            "INSERT INTO message_voice_notes (message_id, duration_ms, peaks)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (message_id) DO NOTHING",
+            Uuid::from(message.id().clone()),
+            *voice_note.duration_ms(),
+            voice_note.peaks().as_slice()
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    write_mentions(tx, message).await?;
+
+    Ok(())
+}
+
+// mentions follow the content: an edit can add or drop them, a delete clears them
+async fn write_mentions(tx: &mut Transaction<'_, Postgres>, message: &Message) -> Result<(), RepoError> {
+    let mentions: Vec<Uuid> = message.mentions().iter().cloned().map(Uuid::from).collect();
+    sqlx::query!(
+        "DELETE FROM message_mentions WHERE message_id = $1 AND user_id <> ALL($2)",
+        Uuid::from(message.id().clone()),
+        &mentions
+    )
+    .execute(&mut **tx)
+    .await?;
+    sqlx::query!(
+        "INSERT INTO message_mentions (message_id, user_id)
+         SELECT $1, unnest($2::uuid[])
+         ON CONFLICT DO NOTHING",
+        Uuid::from(message.id().clone()),
+        &mentions
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
 }
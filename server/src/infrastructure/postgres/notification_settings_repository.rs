@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::notification::NotificationSettings;
+use crate::domain::repository::{NotificationSettingsRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxNotificationSettingsRepository {
+    pool: PgPool,
+}
+
+impl SqlxNotificationSettingsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationSettingsRepository for SqlxNotificationSettingsRepository {
+    // settings live on the participant's row, so leaving the conversation discards them
+    async fn save(&self, settings: &NotificationSettings) -> Result<(), RepoError> {
+        sqlx::query!(
+            "UPDATE user_conversations SET notify_level = $3, muted_until = $4
+             WHERE conversation_id = $1 AND user_id = $2",
+            Uuid::from(settings.conversation_id().clone()),
+            Uuid::from(settings.user_id().clone()),
+            *settings.level() as _,
+            *settings.muted_until()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{ConversationId, MessageId, UserId};
+use crate::domain::pin::Pin;
+use crate::domain::repository::{PinRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxPinRepository {
+    pool: PgPool,
+}
+
+impl SqlxPinRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PinRepository for SqlxPinRepository {
+    async fn find(&self, conversation_id: &ConversationId, message_id: &MessageId) -> Result<Option<Pin>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT conversation_id, message_id, pinned_by, pinned_at
+             FROM pinned_messages
+             WHERE conversation_id = $1 AND message_id = $2",
+            Uuid::from(conversation_id.clone()),
+            Uuid::from(message_id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            Pin::from_persistence(
+                ConversationId::from_persistence(r.conversation_id),
+                MessageId::from_persistence(r.message_id),
+                UserId::from_persistence(r.pinned_by),
+                r.pinned_at,
+            )
+        }))
+    }
+
+    async fn save(&self, pin: &Pin) -> Result<bool, RepoError> {
+        let result = sqlx::query!(
+            "INSERT INTO pinned_messages (conversation_id, message_id, pinned_by, pinned_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (conversation_id, message_id) DO NOTHING",
+            Uuid::from(pin.conversation_id().clone()),
+            Uuid::from(pin.message_id().clone()),
+            Uuid::from(pin.pinned_by().clone()),
+            *pin.pinned_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, pin: &Pin) -> Result<(), RepoError> {
+        sqlx::query!(
+            "DELETE FROM pinned_messages WHERE conversation_id = $1 AND message_id = $2",
+            Uuid::from(pin.conversation_id().clone()),
+            Uuid::from(pin.message_id().clone())
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
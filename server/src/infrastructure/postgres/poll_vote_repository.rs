@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{ConversationId, MessageId, UserId};
+use crate::domain::poll::PollVote;
+use crate::domain::repository::{PollVoteRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxPollVoteRepository {
+    pool: PgPool,
+}
+
+impl SqlxPollVoteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PollVoteRepository for SqlxPollVoteRepository {
+    async fn find(&self, message_id: &MessageId, user_id: &UserId) -> Result<Option<PollVote>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT m.conversation_id, v.choices, v.voted_at
+             FROM poll_votes v
+             JOIN messages m ON m.id = v.message_id
+             WHERE v.message_id = $1 AND v.user_id = $2",
+            Uuid::from(message_id.clone()),
+            Uuid::from(user_id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            PollVote::from_persistence(
+                message_id.clone(),
+                ConversationId::from_persistence(r.conversation_id),
+                user_id.clone(),
+                r.choices.into_iter().map(|choice| choice as usize).collect(),
+                r.voted_at,
+            )
+        }))
+    }
+
+    // a single statement, so two votes from the same user racing each other leave one of them, whole
+    async fn save(&self, vote: &PollVote) -> Result<(), RepoError> {
+        let choices: Vec<i16> = vote.choices().iter().map(|&choice| choice as i16).collect();
+        sqlx::query!(
+            "INSERT INTO poll_votes (message_id, user_id, choices, voted_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (message_id, user_id) DO UPDATE SET choices = EXCLUDED.choices, voted_at = EXCLUDED.voted_at",
+            Uuid::from(vote.message_id().clone()),
+            Uuid::from(vote.user_id().clone()),
+            &choices,
+            *vote.voted_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, vote: &PollVote) -> Result<(), RepoError> {
+        sqlx::query!(
+            "DELETE FROM poll_votes WHERE message_id = $1 AND user_id = $2",
+            Uuid::from(vote.message_id().clone()),
+            Uuid::from(vote.user_id().clone())
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,12 +1,42 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::application::queries::conversation_list::{ConversationView, ConversationViewQueries, ParticipantView, QueryError};
-use crate::application::queries::message_history::{MessageHistoryQueries, MessageHistoryQuery, MessageView, QueryError as MessageQueryError};
-use crate::domain::ids::{ConversationId, UserId};
+use crate::application::queries::conversation_list::{
+    ConversationFilter, ConversationListQuery, ConversationView, ConversationViewQueries, InvitationView, InviteView, ParticipantView,
+    QueryError,
+};
+use crate::application::queries::message_history::{
+    AttachmentView, ForwardedFromView, MentionsQuery, MessageHistoryQueries, MessageHistoryQuery, MessageRevisionView, MessageView,
+    PollOptionView, PollView, QueryError as MessageQueryError, ReactionCountView, ScheduledMessageView, ThreadHistoryQuery,
+    VoiceNoteView,
+};
+use crate::domain::ids::{ConversationId, MessageId, UserId};
+
+struct MessageRow {
+    id: Uuid,
+    conversation_id: Uuid,
+    sender_id: Uuid,
+    content: String,
+    kind: String,
+    thread_root_id: Option<Uuid>,
+    edited: bool,
+    revision_count: i64,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    forwarded_from_message_id: Option<Uuid>,
+    forwarded_from_conversation_id: Option<Uuid>,
+}
+
+struct ThreadSummary {
+    reply_count: i64,
+    last_reply_at: DateTime<Utc>,
+}
 
 #[derive(Clone)]
 pub struct SqlxViewQueries {
@@ -18,9 +48,12 @@ impl SqlxViewQueries {
         Self { pool }
     }
 
-    async fn participants_by_conversation(&self, conversation_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<ParticipantView>>, QueryError> {
+    async fn participants_by_conversation(
+        &self,
+        conversation_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<ParticipantView>>, QueryError> {
         let rows = sqlx::query!(
-            "SELECT uc.conversation_id, uc.user_id, u.username, u.display_name, uc.joined_at, uc.last_seen_at
+            "SELECT uc.conversation_id, uc.user_id, u.username, u.display_name, uc.role::text AS \"role!\", uc.joined_at, uc.last_seen_at
              FROM user_conversations uc
              JOIN users u ON u.id = uc.user_id
              WHERE uc.conversation_id = ANY($1)",
@@ -35,24 +68,238 @@ impl SqlxViewQueries {
                 user_id: r.user_id.to_string(),
                 username: r.username,
                 display_name: r.display_name,
+                role: r.role,
                 joined_at: r.joined_at,
                 last_read_at: r.last_seen_at,
             });
         }
         Ok(grouped)
     }
+
+    async fn reactions_by_message(&self, message_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<ReactionCountView>>, MessageQueryError> {
+        // emoji in the order they were first used on each message, reactors oldest first
+        let rows = sqlx::query!(
+            "SELECT message_id, emoji, COUNT(*) AS \"count!\", array_agg(user_id ORDER BY created_at) AS \"user_ids!\"
+             FROM message_reactions
+             WHERE message_id = ANY($1)
+             GROUP BY message_id, emoji
+             ORDER BY MIN(created_at)",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut grouped: HashMap<Uuid, Vec<ReactionCountView>> = HashMap::new();
+        for r in rows {
+            grouped.entry(r.message_id).or_default().push(ReactionCountView {
+                emoji: r.emoji,
+                count: r.count,
+                user_ids: r.user_ids.iter().map(Uuid::to_string).collect(),
+            });
+        }
+        Ok(grouped)
+    }
+
+    async fn attachments_by_message(&self, message_ids: &[Uuid]) -> Result<HashMap<Uuid, AttachmentView>, MessageQueryError> {
+        let rows = sqlx::query!(
+            "SELECT message_id, filename, size_bytes, mime_type FROM message_attachments WHERE message_id = ANY($1)",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let view = AttachmentView {
+                    filename: r.filename,
+                    size_bytes: r.size_bytes,
+                    mime_type: r.mime_type,
+                };
+                (r.message_id, view)
+            })
+            .collect())
+    }
+
+    async fn voice_notes_by_message(&self, message_ids: &[Uuid]) -> Result<HashMap<Uuid, VoiceNoteView>, MessageQueryError> {
+        let rows = sqlx::query!(
+            "SELECT message_id, duration_ms, peaks FROM message_voice_notes WHERE message_id = ANY($1)",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let view = VoiceNoteView {
+                    duration_ms: r.duration_ms,
+                    peaks: r.peaks,
+                };
+                (r.message_id, view)
+            })
+            .collect())
+    }
+
+    async fn polls_by_message(&self, message_ids: &[Uuid]) -> Result<HashMap<Uuid, PollView>, MessageQueryError> {
+        let polls = sqlx::query!(
+            "SELECT message_id, options, multiple_choice, closes_at FROM message_polls WHERE message_id = ANY($1)",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        if polls.is_empty() {
+            return Ok(HashMap::new());
+        }
+        // voters oldest first, like reactions
+        let votes = sqlx::query!(
+            "SELECT v.message_id, c.option_index AS \"option_index!\", array_agg(v.user_id ORDER BY v.voted_at) AS \"user_ids!\"
+             FROM poll_votes v
+             CROSS JOIN LATERAL unnest(v.choices) AS c(option_index)
+             WHERE v.message_id = ANY($1)
+             GROUP BY v.message_id, c.option_index",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut voters: HashMap<(Uuid, i16), Vec<Uuid>> =
+            votes.into_iter().map(|r| ((r.message_id, r.option_index), r.user_ids)).collect();
+        let now = Utc::now();
+        Ok(polls
+            .into_iter()
+            .map(|p| {
+                let options: Vec<PollOptionView> = p
+                    .options
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, text)| {
+                        let user_ids = voters.remove(&(p.message_id, i as i16)).unwrap_or_default();
+                        PollOptionView {
+                            text,
+                            votes: user_ids.len() as i64,
+                            user_ids: user_ids.iter().map(Uuid::to_string).collect(),
+                        }
+                    })
+                    .collect();
+                let voter_count = options.iter().flat_map(|o| o.user_ids.iter()).collect::<HashSet<_>>().len() as i64;
+                let view = PollView {
+                    options,
+                    multiple_choice: p.multiple_choice,
+                    closes_at: p.closes_at,
+                    closed: p.closes_at.is_some_and(|at| at <= now),
+                    voter_count,
+                };
+                (p.message_id, view)
+            })
+            .collect())
+    }
+
+    async fn thread_summaries(&self, message_ids: &[Uuid]) -> Result<HashMap<Uuid, ThreadSummary>, MessageQueryError> {
+        // unsent replies stay in the thread as tombstones but no longer count towards it
+        let rows = sqlx::query!(
+            "SELECT thread_root_id AS \"thread_root_id!\", COUNT(*) AS \"reply_count!\", MAX(created_at) AS \"last_reply_at!\"
+             FROM messages
+             WHERE thread_root_id = ANY($1) AND deleted_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+             GROUP BY thread_root_id",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                (
+                    r.thread_root_id,
+                    ThreadSummary {
+                        reply_count: r.reply_count,
+                        last_reply_at: r.last_reply_at,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn mentions_by_message(&self, message_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<String>>, MessageQueryError> {
+        let rows = sqlx::query!(
+            "SELECT message_id, user_id FROM message_mentions WHERE message_id = ANY($1)",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut mentions: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for r in rows {
+            mentions.entry(r.message_id).or_default().push(r.user_id.to_string());
+        }
+        Ok(mentions)
+    }
+
+    async fn message_views(&self, rows: Vec<MessageRow>) -> Result<Vec<MessageView>, MessageQueryError> {
+        let message_ids: Vec<Uuid> = rows.iter().map(|r| r.id).collect();
+        let mut reactions_by_message = self.reactions_by_message(&message_ids).await?;
+        let mut mentions_by_message = self.mentions_by_message(&message_ids).await?;
+        let mut threads = self.thread_summaries(&message_ids).await?;
+        let mut polls = self.polls_by_message(&message_ids).await?;
+        let mut attachments = self.attachments_by_message(&message_ids).await?;
+        let mut voice_notes = self.voice_notes_by_message(&message_ids).await?;
+
+        // deleted messages stay in place as tombstones so the timeline doesn't shift under readers
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let deleted = r.deleted_at.is_some();
+                let thread = threads.remove(&r.id);
+                MessageView {
+                    id: r.id.to_string(),
+                    conversation_id: r.conversation_id.to_string(),
+                    sender_id: r.sender_id.to_string(),
+                    content: if deleted { String::new() } else { r.content },
+                    kind: r.kind,
+                    thread_root_id: r.thread_root_id.map(|id| id.to_string()),
+                    reply_count: thread.as_ref().map_or(0, |t| t.reply_count),
+                    last_reply_at: thread.map(|t| t.last_reply_at),
+                    edited: r.edited,
+                    revision_count: if deleted { 0 } else { r.revision_count },
+                    created_at: r.created_at,
+                    updated_at: r.updated_at,
+                    deleted_at: r.deleted_at,
+                    expires_at: r.expires_at,
+                    forwarded_from: r.forwarded_from_message_id.zip(r.forwarded_from_conversation_id).map(
+                        |(message_id, conversation_id)| ForwardedFromView {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                        },
+                    ),
+                    poll: if deleted { None } else { polls.remove(&r.id) },
+                    attachment: if deleted { None } else { attachments.remove(&r.id) },
+                    voice_note: if deleted { None } else { voice_notes.remove(&r.id) },
+                    mentions: mentions_by_message.remove(&r.id).unwrap_or_default(),
+                    reactions: if deleted {
+                        Vec::new()
+                    } else {
+                        reactions_by_message.remove(&r.id).unwrap_or_default()
+                    },
+                }
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
 impl ConversationViewQueries for SqlxViewQueries {
-    async fn for_user(&self, user_id: &UserId) -> Result<Vec<ConversationView>, QueryError> {
+    async fn for_user(&self, query: ConversationListQuery) -> Result<Vec<ConversationView>, QueryError> {
+        // pinned conversations first, most recently pinned on top; the rest by latest activity
         let rows = sqlx::query!(
-            "SELECT c.id, c.kind::text AS \"kind!\", c.title, c.created_at, c.updated_at
+            "SELECT c.id, c.kind::text AS \"kind!\", c.title, c.description, c.avatar_url, uc.notify_level::text AS \"notify_level!\",
+                    uc.muted_until, uc.archived_at, uc.pinned_at, c.message_ttl_seconds, c.created_at, c.updated_at
              FROM conversations c
              JOIN user_conversations uc ON uc.conversation_id = c.id
-             WHERE uc.user_id = $1
-             ORDER BY c.updated_at DESC",
-            Uuid::from(user_id.clone())
+             WHERE uc.user_id = $1 AND (uc.archived_at IS NOT NULL) = $2
+             ORDER BY uc.pinned_at DESC NULLS LAST, c.updated_at DESC",
+            Uuid::from(query.user_id),
+            query.filter == ConversationFilter::Archived
         )
         .fetch_all(&self.pool)
         .await?;
@@ -66,17 +313,31 @@ impl ConversationViewQueries for SqlxViewQueries {
                 id: r.id.to_string(),
                 conversation_type: r.kind,
                 name: r.title,
+                description: r.description,
+                avatar_url: r.avatar_url,
                 participants: participants_by_conversation.remove(&r.id).unwrap_or_default(),
+                notify_level: r.notify_level,
+                muted_until: r.muted_until,
+                archived: r.archived_at.is_some(),
+                pinned: r.pinned_at.is_some(),
+                message_ttl_seconds: r.message_ttl_seconds,
                 created_at: r.created_at,
                 updated_at: Some(r.updated_at),
             })
             .collect())
     }
 
-    async fn by_id(&self, id: &ConversationId) -> Result<Option<ConversationView>, QueryError> {
+    async fn by_id(&self, id: &ConversationId, viewer: &UserId) -> Result<Option<ConversationView>, QueryError> {
+        // a viewer outside the conversation just sees the defaults
         let row = sqlx::query!(
-            "SELECT id, kind::text AS \"kind!\", title, created_at, updated_at FROM conversations WHERE id = $1",
-            Uuid::from(id.clone())
+            "SELECT c.id, c.kind::text AS \"kind!\", c.title, c.description, c.avatar_url,
+                    COALESCE(uc.notify_level::text, 'all') AS \"notify_level!\", uc.muted_until, uc.archived_at, uc.pinned_at,
+                    c.message_ttl_seconds, c.created_at, c.updated_at
+             FROM conversations c
+             LEFT JOIN user_conversations uc ON uc.conversation_id = c.id AND uc.user_id = $2
+             WHERE c.id = $1",
+            Uuid::from(id.clone()),
+            Uuid::from(viewer.clone())
         )
         .fetch_optional(&self.pool)
         .await?;
@@ -92,20 +353,84 @@ impl ConversationViewQueries for SqlxViewQueries {
             id: row.id.to_string(),
             conversation_type: row.kind,
             name: row.title,
+            description: row.description,
+            avatar_url: row.avatar_url,
             participants,
+            notify_level: row.notify_level,
+            muted_until: row.muted_until,
+            archived: row.archived_at.is_some(),
+            pinned: row.pinned_at.is_some(),
+            message_ttl_seconds: row.message_ttl_seconds,
             created_at: row.created_at,
             updated_at: Some(row.updated_at),
         }))
     }
+
+    async fn invites(&self, conversation_id: &ConversationId) -> Result<Vec<InviteView>, QueryError> {
+        // only links that would still let someone in
+        let rows = sqlx::query!(
+            "SELECT id, conversation_id, token, created_by, created_at, expires_at, max_uses, uses
+             FROM conversation_invites
+             WHERE conversation_id = $1
+               AND revoked_at IS NULL
+               AND (expires_at IS NULL OR expires_at > NOW())
+               AND (max_uses IS NULL OR uses < max_uses)
+             ORDER BY created_at DESC",
+            Uuid::from(conversation_id.clone())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| InviteView {
+                id: r.id.to_string(),
+                conversation_id: r.conversation_id.to_string(),
+                token: r.token,
+                created_by: r.created_by.to_string(),
+                created_at: r.created_at,
+                expires_at: r.expires_at,
+                max_uses: r.max_uses,
+                uses: r.uses,
+            })
+            .collect())
+    }
+
+    async fn invitations(&self, user_id: &UserId) -> Result<Vec<InvitationView>, QueryError> {
+        let rows = sqlx::query!(
+            "SELECT i.conversation_id, c.title, i.invited_by, i.created_at
+             FROM conversation_invitations i
+             JOIN conversations c ON c.id = i.conversation_id
+             WHERE i.user_id = $1
+             ORDER BY i.created_at DESC",
+            Uuid::from(user_id.clone())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| InvitationView {
+                conversation_id: r.conversation_id.to_string(),
+                name: r.title,
+                invited_by: r.invited_by.to_string(),
+                created_at: r.created_at,
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
 impl MessageHistoryQueries for SqlxViewQueries {
     async fn for_conversation(&self, query: MessageHistoryQuery) -> Result<Vec<MessageView>, MessageQueryError> {
-        let rows = sqlx::query!(
-            "SELECT id, conversation_id, sender_id, content, kind::text AS \"kind!\", edited, created_at, updated_at
+        // thread replies live in their own history; the main timeline only carries roots
+        let rows = sqlx::query_as!(
+            MessageRow,
+            "SELECT id, conversation_id, sender_id, content, kind::text AS \"kind!\", thread_root_id, edited,
+                    (SELECT COUNT(*) FROM message_revisions r WHERE r.message_id = messages.id) AS \"revision_count!\",
+                    created_at, updated_at, deleted_at, expires_at, forwarded_from_message_id, forwarded_from_conversation_id
              FROM messages
-             WHERE conversation_id = $1
+             WHERE conversation_id = $1 AND thread_root_id IS NULL AND (expires_at IS NULL OR expires_at > NOW())
              ORDER BY created_at ASC
              OFFSET $2 LIMIT $3",
             Uuid::from(query.conversation_id),
@@ -115,17 +440,120 @@ impl MessageHistoryQueries for SqlxViewQueries {
         .fetch_all(&self.pool)
         .await?;
 
+        self.message_views(rows).await
+    }
+
+    async fn for_thread(&self, query: ThreadHistoryQuery) -> Result<Vec<MessageView>, MessageQueryError> {
+        let rows = sqlx::query_as!(
+            MessageRow,
+            "SELECT id, conversation_id, sender_id, content, kind::text AS \"kind!\", thread_root_id, edited,
+                    (SELECT COUNT(*) FROM message_revisions r WHERE r.message_id = messages.id) AS \"revision_count!\",
+                    created_at, updated_at, deleted_at, expires_at, forwarded_from_message_id, forwarded_from_conversation_id
+             FROM messages
+             WHERE thread_root_id = $1 AND (expires_at IS NULL OR expires_at > NOW())
+             ORDER BY created_at ASC
+             OFFSET $2 LIMIT $3",
+            Uuid::from(query.thread_root_id),
+            query.offset.unwrap_or(0),
+            query.limit.unwrap_or(i64::MAX)
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.message_views(rows).await
+    }
+
+    async fn pinned(&self, conversation_id: &ConversationId) -> Result<Vec<MessageView>, MessageQueryError> {
+        // most recently pinned first; pins on since-unsent messages are dropped rather than shown as tombstones
+        let rows = sqlx::query_as!(
+            MessageRow,
+            "SELECT m.id, m.conversation_id, m.sender_id, m.content, m.kind::text AS \"kind!\", m.thread_root_id, m.edited,
+                    (SELECT COUNT(*) FROM message_revisions r WHERE r.message_id = m.id) AS \"revision_count!\",
+                    m.created_at, m.updated_at, m.deleted_at, m.expires_at,
+                    m.forwarded_from_message_id, m.forwarded_from_conversation_id
+             FROM pinned_messages p
+             JOIN messages m ON m.id = p.message_id
+             WHERE p.conversation_id = $1 AND m.deleted_at IS NULL AND (m.expires_at IS NULL OR m.expires_at > NOW())
+             ORDER BY p.pinned_at DESC",
+            Uuid::from(conversation_id.clone())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.message_views(rows).await
+    }
+
+    async fn revisions(&self, message_id: &MessageId) -> Result<Vec<MessageRevisionView>, MessageQueryError> {
+        // the rows outlive an unsend for compliance, but they're never served once the message is gone
+        let rows = sqlx::query!(
+            "SELECT r.content, r.written_at, r.replaced_at
+             FROM message_revisions r
+             JOIN messages m ON m.id = r.message_id
+             WHERE r.message_id = $1 AND m.deleted_at IS NULL
+             ORDER BY r.written_at ASC",
+            Uuid::from(message_id.clone())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| MessageRevisionView {
+                content: r.content,
+                written_at: r.written_at,
+                replaced_at: r.replaced_at,
+            })
+            .collect())
+    }
+
+    async fn mentions(&self, query: MentionsQuery) -> Result<Vec<MessageView>, MessageQueryError> {
+        // newest first, and only from conversations the user is still in
+        let rows = sqlx::query_as!(
+            MessageRow,
+            "SELECT m.id, m.conversation_id, m.sender_id, m.content, m.kind::text AS \"kind!\", m.thread_root_id, m.edited,
+                    (SELECT COUNT(*) FROM message_revisions r WHERE r.message_id = m.id) AS \"revision_count!\",
+                    m.created_at, m.updated_at, m.deleted_at, m.expires_at,
+                    m.forwarded_from_message_id, m.forwarded_from_conversation_id
+             FROM message_mentions mm
+             JOIN messages m ON m.id = mm.message_id
+             JOIN user_conversations uc ON uc.conversation_id = m.conversation_id AND uc.user_id = mm.user_id
+             WHERE mm.user_id = $1 AND m.deleted_at IS NULL AND (m.expires_at IS NULL OR m.expires_at > NOW())
+             ORDER BY m.created_at DESC
+             OFFSET $2 LIMIT $3",
+            Uuid::from(query.user_id),
+            query.offset.unwrap_or(0),
+            query.limit.unwrap_or(i64::MAX)
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.message_views(rows).await
+    }
+
+    async fn scheduled(&self, user_id: &UserId) -> Result<Vec<ScheduledMessageView>, MessageQueryError> {
+        let rows = sqlx::query!(
+            "SELECT id, conversation_id, content, kind::text AS \"kind!\", reply_to, send_at, created_at,
+                    status::text AS \"status!\", failure
+             FROM scheduled_messages
+             WHERE sender_id = $1 AND status NOT IN ('sent', 'cancelled')
+             ORDER BY send_at ASC",
+            Uuid::from(user_id.clone())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
         Ok(rows
             .into_iter()
-            .map(|r| MessageView {
+            .map(|r| ScheduledMessageView {
                 id: r.id.to_string(),
                 conversation_id: r.conversation_id.to_string(),
-                sender_id: r.sender_id.to_string(),
                 content: r.content,
                 kind: r.kind,
-                edited: r.edited,
+                reply_to: r.reply_to.map(|id| id.to_string()),
+                send_at: r.send_at,
                 created_at: r.created_at,
-                updated_at: r.updated_at,
+                status: r.status,
+                failure: r.failure,
             })
             .collect())
     }
@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{ConversationId, MessageId, UserId};
+use crate::domain::reaction::{Emoji, Reaction};
+use crate::domain::repository::{ReactionRepository, RepoError};
+
+#[derive(Clone)]
+pub struct SqlxReactionRepository {
+    pool: PgPool,
+}
+
+impl SqlxReactionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReactionRepository for SqlxReactionRepository {
+    async fn find(&self, message_id: &MessageId, user_id: &UserId, emoji: &Emoji) -> Result<Option<Reaction>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT r.message_id, m.conversation_id, r.user_id, r.emoji, r.created_at
+             FROM message_reactions r
+             JOIN messages m ON m.id = r.message_id
+             WHERE r.message_id = $1 AND r.user_id = $2 AND r.emoji = $3",
+            Uuid::from(message_id.clone()),
+            Uuid::from(user_id.clone()),
+            emoji.as_str()
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            Reaction::from_persistence(
+                MessageId::from_persistence(r.message_id),
+                ConversationId::from_persistence(r.conversation_id),
+                UserId::from_persistence(r.user_id),
+                Emoji::from_persistence(r.emoji),
+                r.created_at,
+            )
+        }))
+    }
+
+    async fn save(&self, reaction: &Reaction) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO message_reactions (message_id, user_id, emoji, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (message_id, user_id, emoji) DO NOTHING",
+            Uuid::from(reaction.message_id().clone()),
+            Uuid::from(reaction.user_id().clone()),
+            reaction.emoji().as_str(),
+            *reaction.created_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, reaction: &Reaction) -> Result<(), RepoError> {
+        sqlx::query!(
+            "DELETE FROM message_reactions WHERE message_id = $1 AND user_id = $2 AND emoji = $3",
+            Uuid::from(reaction.message_id().clone()),
+            Uuid::from(reaction.user_id().clone()),
+            reaction.emoji().as_str()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
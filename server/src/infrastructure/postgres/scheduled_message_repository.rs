@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{ConversationId, MessageId, ScheduledMessageId, UserId};
+use crate::domain::message::MessageKind;
+use crate::domain::repository::{RepoError, ScheduledMessageRepository};
+use crate::domain::scheduled_message::{ScheduledMessage, ScheduledStatus};
+
+#[derive(Clone)]
+pub struct SqlxScheduledMessageRepository {
+    pool: PgPool,
+}
+
+impl SqlxScheduledMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+struct ScheduledMessageRow {
+    id: Uuid,
+    conversation_id: Uuid,
+    sender_id: Uuid,
+    content: String,
+    kind: MessageKind,
+    reply_to: Option<Uuid>,
+    send_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    status: ScheduledStatus,
+    message_id: Option<Uuid>,
+    failure: Option<String>,
+    attempts: i32,
+}
+
+impl From<ScheduledMessageRow> for ScheduledMessage {
+    fn from(r: ScheduledMessageRow) -> Self {
+        ScheduledMessage::from_persistence(
+            ScheduledMessageId::from_persistence(r.id),
+            ConversationId::from_persistence(r.conversation_id),
+            UserId::from_persistence(r.sender_id),
+            r.content,
+            r.kind,
+            r.reply_to.map(MessageId::from_persistence),
+            r.send_at,
+            r.created_at,
+            r.status,
+            r.message_id.map(MessageId::from_persistence),
+            r.failure,
+            r.attempts,
+        )
+    }
+}
+
+#[async_trait]
+impl ScheduledMessageRepository for SqlxScheduledMessageRepository {
+    async fn find_by_id(&self, id: &ScheduledMessageId) -> Result<Option<ScheduledMessage>, RepoError> {
+        let row = sqlx::query_as!(
+            ScheduledMessageRow,
+            "SELECT id, conversation_id, sender_id, content, kind AS \"kind: MessageKind\", reply_to, send_at, created_at,
+                    status AS \"status: ScheduledStatus\", message_id, failure, attempts
+             FROM scheduled_messages WHERE id = $1",
+            Uuid::from(id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(ScheduledMessage::from))
+    }
+
+    async fn add(&self, scheduled: &ScheduledMessage) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO scheduled_messages
+                (id, conversation_id, sender_id, content, kind, reply_to, send_at, created_at, status, message_id, failure)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            Uuid::from(scheduled.id().clone()),
+            Uuid::from(scheduled.conversation_id().clone()),
+            Uuid::from(scheduled.sender_id().clone()),
+            scheduled.content().as_str(),
+            scheduled.kind().clone() as _,
+            scheduled.reply_to().clone().map(Uuid::from),
+            *scheduled.send_at(),
+            *scheduled.created_at(),
+            *scheduled.status() as _,
+            scheduled.message_id().clone().map(Uuid::from),
+            scheduled.failure().as_deref()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // SKIP LOCKED lets two dispatchers claim side by side without waiting on or sharing each other's rows.
+    // a stale claim is taken over as it stands: if the crash came after the message went out it goes out
+    // again, which beats never sending it
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ScheduledMessage>, RepoError> {
+        let mut rows = sqlx::query_as!(
+            ScheduledMessageRow,
+            "UPDATE scheduled_messages SET status = 'sending', claimed_at = $1, attempts = attempts + 1
+             WHERE id IN (
+                 SELECT id FROM scheduled_messages
+                 WHERE (status = 'pending' AND send_at <= $1) OR (status = 'sending' AND claimed_at < $2)
+                 ORDER BY send_at
+                 LIMIT $3
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING id, conversation_id, sender_id, content, kind AS \"kind: MessageKind\", reply_to, send_at, created_at,
+                       status AS \"status: ScheduledStatus\", message_id, failure, attempts",
+            now,
+            stale_before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.sort_by_key(|r| r.send_at);
+        Ok(rows.into_iter().map(ScheduledMessage::from).collect())
+    }
+
+    async fn save(&self, scheduled: &ScheduledMessage) -> Result<(), RepoError> {
+        sqlx::query!(
+            "UPDATE scheduled_messages SET status = $2, message_id = $3, failure = $4
+             WHERE id = $1 AND status = 'sending'",
+            Uuid::from(scheduled.id().clone()),
+            *scheduled.status() as _,
+            scheduled.message_id().clone().map(Uuid::from),
+            scheduled.failure().as_deref()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn cancel(&self, scheduled: &ScheduledMessage) -> Result<bool, RepoError> {
+        let result = sqlx::query!(
+            "UPDATE scheduled_messages SET status = 'cancelled'
+             WHERE id = $1 AND status IN ('pending', 'failed')",
+            Uuid::from(scheduled.id().clone())
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::{SessionId, UserId};
+use crate::domain::repository::{RepoError, SessionRepository};
+use crate::domain::session::Session;
+
+#[derive(Clone)]
+pub struct SqlxSessionRepository {
+    pool: PgPool,
+}
+
+impl SqlxSessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SqlxSessionRepository {
+    async fn find_by_token_hash(&self, token_hash: &[u8]) -> Result<Option<Session>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT id, user_id, token_hash, created_at, expires_at FROM sessions WHERE token_hash = $1",
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            Session::from_persistence(
+                SessionId::from_persistence(r.id),
+                UserId::from_persistence(r.user_id),
+                r.token_hash,
+                r.created_at,
+                r.expires_at,
+            )
+        }))
+    }
+
+    async fn save(&self, session: &Session) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO sessions (id, user_id, token_hash, created_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (id) DO UPDATE SET expires_at = $5",
+            Uuid::from(session.id().clone()),
+            Uuid::from(session.user_id().clone()),
+            session.token_hash().as_slice(),
+            *session.created_at(),
+            *session.expires_at()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &SessionId) -> Result<(), RepoError> {
+        sqlx::query!("DELETE FROM sessions WHERE id = $1", Uuid::from(id.clone()))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_all_for_user_except(&self, user_id: &UserId, keep: &SessionId) -> Result<(), RepoError> {
+        sqlx::query!(
+            "DELETE FROM sessions WHERE user_id = $1 AND id <> $2",
+            Uuid::from(user_id.clone()),
+            Uuid::from(keep.clone())
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
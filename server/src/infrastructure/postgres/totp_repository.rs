@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::ids::UserId;
+use crate::domain::repository::{RepoError, TotpRepository};
+use crate::domain::two_factor::{RecoveryCode, TotpEnrollment, TotpSecret, TwoFactorUse};
+use crate::infrastructure::crypto::SecretCipher;
+
+#[derive(Clone)]
+pub struct SqlxTotpRepository {
+    pool: PgPool,
+    cipher: SecretCipher,
+}
+
+impl SqlxTotpRepository {
+    pub fn new(pool: PgPool, cipher: SecretCipher) -> Self {
+        Self { pool, cipher }
+    }
+}
+
+#[async_trait]
+impl TotpRepository for SqlxTotpRepository {
+    async fn find_by_user_id(&self, user_id: &UserId) -> Result<Option<TotpEnrollment>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT user_id, secret_ciphertext, secret_nonce, created_at, confirmed_at, last_used_step
+             FROM totp_enrollments WHERE user_id = $1",
+            Uuid::from(user_id.clone())
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(row) = row else { return Ok(None) };
+
+        let secret = self
+            .cipher
+            .decrypt(&row.secret_ciphertext, &row.secret_nonce)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        let codes = sqlx::query!(
+            "SELECT code_hash, used_at FROM recovery_codes WHERE user_id = $1",
+            Uuid::from(user_id.clone())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Some(TotpEnrollment::from_persistence(
+            UserId::from_persistence(row.user_id),
+            TotpSecret::from_persistence(secret),
+            row.created_at,
+            row.confirmed_at,
+            row.last_used_step,
+            codes
+                .into_iter()
+                .map(|c| RecoveryCode {
+                    code_hash: c.code_hash,
+                    used_at: c.used_at,
+                })
+                .collect(),
+        )))
+    }
+
+    async fn save(&self, enrollment: &TotpEnrollment) -> Result<(), RepoError> {
+        let (ciphertext, nonce) = self.cipher.encrypt(enrollment.secret().as_bytes());
+        let user_id = Uuid::from(enrollment.user_id().clone());
+        let mut tx = self.pool.begin().await?;
+
+        // a new enrollment replaces any earlier unconfirmed one, so the secret is rewritten too
+        sqlx::query!(
+            "INSERT INTO totp_enrollments (user_id, secret_ciphertext, secret_nonce, created_at, confirmed_at, last_used_step)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (user_id) DO UPDATE
+               SET secret_ciphertext = $2, secret_nonce = $3, created_at = $4, confirmed_at = $5, last_used_step = $6",
+            user_id,
+            ciphertext,
+            nonce,
+            *enrollment.created_at(),
+            *enrollment.confirmed_at(),
+            *enrollment.last_used_step()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for code in enrollment.recovery_codes() {
+            sqlx::query!(
+                "INSERT INTO recovery_codes (user_id, code_hash, used_at) VALUES ($1, $2, $3)",
+                user_id,
+                code.code_hash.as_slice(),
+                code.used_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn redeem(&self, user_id: &UserId, used: &TwoFactorUse, now: DateTime<Utc>) -> Result<bool, RepoError> {
+        let user_id = Uuid::from(user_id.clone());
+        let result = match used {
+            TwoFactorUse::TotpStep(step) => {
+                sqlx::query!(
+                    "UPDATE totp_enrollments SET last_used_step = $2
+                     WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)",
+                    user_id,
+                    step
+                )
+                .execute(&self.pool)
+                .await?
+            }
+            TwoFactorUse::RecoveryCode(code_hash) => {
+                sqlx::query!(
+                    "UPDATE recovery_codes SET used_at = $3 WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+                    user_id,
+                    code_hash.as_slice(),
+                    now
+                )
+                .execute(&self.pool)
+                .await?
+            }
+        };
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, user_id: &UserId) -> Result<(), RepoError> {
+        sqlx::query!("DELETE FROM totp_enrollments WHERE user_id = $1", Uuid::from(user_id.clone()))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
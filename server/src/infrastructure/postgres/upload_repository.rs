@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use crate::domain::attachment::Attachment;
+use crate::domain::repository::{RepoError, UploadRepository};
+use crate::domain::voice_note::VoiceNote;
+use crate::infrastructure::media::stored_name;
+
+// files are served from /files and voice notes from /media, see LocalMediaStore
+#[derive(Clone)]
+pub struct SqlxUploadRepository {
+    pool: PgPool,
+    public_url: String,
+}
+
+impl SqlxUploadRepository {
+    pub fn new(pool: PgPool, public_url: impl Into<String>) -> Self {
+        Self {
+            pool,
+            public_url: public_url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl UploadRepository for SqlxUploadRepository {
+    async fn save_file(&self, url: &str, attachment: &Attachment) -> Result<(), RepoError> {
+        let Some(name) = stored_name(&self.public_url, "/files/", url) else {
+            return Ok(());
+        };
+
+        sqlx::query!(
+            "INSERT INTO uploaded_files (stored_name, filename, size_bytes, mime_type) VALUES ($1, $2, $3, $4)",
+            name,
+            attachment.filename().as_str(),
+            *attachment.size_bytes(),
+            attachment.mime_type().as_str()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_file(&self, url: &str) -> Result<Option<Attachment>, RepoError> {
+        let Some(name) = stored_name(&self.public_url, "/files/", url) else {
+            return Ok(None);
+        };
+
+        let row = sqlx::query!(
+            "SELECT filename, size_bytes, mime_type FROM uploaded_files WHERE stored_name = $1",
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| Attachment::from_persistence(r.filename, r.size_bytes, r.mime_type)))
+    }
+
+    async fn save_voice_note(&self, url: &str, voice_note: &VoiceNote) -> Result<(), RepoError> {
+        let Some(name) = stored_name(&self.public_url, "/media/", url) else {
+            return Ok(());
+        };
+
+        sqlx::query!(
+            "INSERT INTO uploaded_voice_notes (stored_name, duration_ms, peaks) VALUES ($1, $2, $3)",
+            name,
+            *voice_note.duration_ms(),
+            voice_note.peaks().as_slice()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_voice_note(&self, url: &str) -> Result<Option<VoiceNote>, RepoError> {
+        let Some(name) = stored_name(&self.public_url, "/media/", url) else {
+            return Ok(None);
+        };
+
+        let row = sqlx::query!("SELECT duration_ms, peaks FROM uploaded_voice_notes WHERE stored_name = $1", name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| VoiceNote::from_persistence(r.duration_ms, r.peaks)))
+    }
+}
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::domain::credentials::Credentials;
 use crate::domain::ids::UserId;
 use crate::domain::repository::{RepoError, UserRepository};
 use crate::domain::user::{DisplayName, User, Username};
@@ -21,7 +22,7 @@ impl SqlxUserRepository {
 impl UserRepository for SqlxUserRepository {
     async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, RepoError> {
         let row = sqlx::query!(
-            "SELECT id, username, display_name, created_at FROM users WHERE id = $1",
+            "SELECT id, username, display_name, two_factor_enabled, created_at FROM users WHERE id = $1",
             Uuid::from(id.clone())
         )
         .fetch_optional(&self.pool)
@@ -32,13 +33,58 @@ impl UserRepository for SqlxUserRepository {
                 UserId::from_persistence(r.id),
                 Username::from_persistence(r.username),
                 DisplayName::from_persistence(r.display_name),
+                r.two_factor_enabled,
+                r.created_at,
+            )
+        }))
+    }
+
+    async fn find_by_username(&self, username: &Username) -> Result<Option<User>, RepoError> {
+        let row = sqlx::query!(
+            "SELECT id, username, display_name, two_factor_enabled, created_at FROM users WHERE username = $1",
+            username.as_str()
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            User::from_persistence(
+                UserId::from_persistence(r.id),
+                Username::from_persistence(r.username),
+                DisplayName::from_persistence(r.display_name),
+                r.two_factor_enabled,
                 r.created_at,
             )
         }))
     }
 
     async fn find_all(&self) -> Result<Vec<User>, RepoError> {
-        let rows = sqlx::query!("SELECT id, username, display_name, created_at FROM users ORDER BY username").fetch_all(&self.pool).await?;
+        let rows = sqlx::query!("SELECT id, username, display_name, two_factor_enabled, created_at FROM users ORDER BY username")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                User::from_persistence(
+                    UserId::from_persistence(r.id),
+                    Username::from_persistence(r.username),
+                    DisplayName::from_persistence(r.display_name),
+                    r.two_factor_enabled,
+                    r.created_at,
+                )
+            })
+            .collect())
+    }
+
+    async fn find_many(&self, ids: &[UserId]) -> Result<Vec<User>, RepoError> {
+        let ids: Vec<Uuid> = ids.iter().cloned().map(Uuid::from).collect();
+        let rows = sqlx::query!(
+            "SELECT id, username, display_name, two_factor_enabled, created_at FROM users WHERE id = ANY($1)",
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
         Ok(rows
             .into_iter()
@@ -47,6 +93,7 @@ impl UserRepository for SqlxUserRepository {
                     UserId::from_persistence(r.id),
                     Username::from_persistence(r.username),
                     DisplayName::from_persistence(r.display_name),
+                    r.two_factor_enabled,
                     r.created_at,
                 )
             })
@@ -55,12 +102,13 @@ impl UserRepository for SqlxUserRepository {
 
     async fn save(&self, user: &User) -> Result<(), RepoError> {
         sqlx::query!(
-            "INSERT INTO users (id, username, display_name, created_at)
-             VALUES ($1, $2, $3, $4)
-             ON CONFLICT (id) DO UPDATE SET display_name = $3",
+            "INSERT INTO users (id, username, display_name, two_factor_enabled, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (id) DO UPDATE SET display_name = $3, two_factor_enabled = $4",
             Uuid::from(user.id().clone()),
             user.username().as_str(),
             user.display_name().as_str(),
+            *user.two_factor_enabled(),
             *user.created_at()
         )
         .execute(&self.pool)
@@ -68,4 +116,36 @@ impl UserRepository for SqlxUserRepository {
 
         Ok(())
     }
+
+    async fn create(&self, user: &User, credentials: &Credentials) -> Result<bool, RepoError> {
+        let mut tx = self.pool.begin().await?;
+
+        // the username check before this is only a shortcut, the unique index decides who gets it
+        let inserted = sqlx::query!(
+            "INSERT INTO users (id, username, display_name, two_factor_enabled, created_at) VALUES ($1, $2, $3, $4, $5)",
+            Uuid::from(user.id().clone()),
+            user.username().as_str(),
+            user.display_name().as_str(),
+            *user.two_factor_enabled(),
+            *user.created_at()
+        )
+        .execute(&mut *tx)
+        .await;
+        match inserted {
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(false),
+            inserted => inserted?,
+        };
+
+        sqlx::query!(
+            "INSERT INTO credentials (user_id, password_hash, updated_at) VALUES ($1, $2, $3)",
+            Uuid::from(credentials.user_id().clone()),
+            credentials.password_hash().as_str(),
+            *credentials.updated_at()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
 }
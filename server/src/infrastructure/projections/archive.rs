@@ -0,0 +1,29 @@
+use crate::domain::events::DomainEvent;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+// a new message brings an archived conversation back into the list, unless the participant muted it
+// for now or turned its notifications off altogether
+pub async fn project_auto_unarchive(pool: &PgPool, event: &DomainEvent) -> Result<(), sqlx::Error> {
+    if let DomainEvent::MessageSent {
+        conversation_id,
+        thread_root_id: None,
+        created_at,
+        ..
+    } = event
+    {
+        sqlx::query!(
+            "UPDATE user_conversations
+               SET archived_at = NULL
+               WHERE conversation_id = $1
+                 AND archived_at IS NOT NULL
+                 AND (muted_until IS NULL OR muted_until <= $2)
+                 AND notify_level <> 'none'",
+            Uuid::from(conversation_id.clone()),
+            created_at
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
@@ -3,21 +3,41 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 pub async fn project_last_message(pool: &PgPool, event: &DomainEvent) -> Result<(), sqlx::Error> {
-    if let DomainEvent::MessageSent {
-        message_id,
-        conversation_id,
-        created_at,
-        ..
-    } = event
-    {
-        sqlx::query!(
-            "UPDATE conversations SET last_message_id = $1, updated_at = $2 WHERE id = $3",
-            Uuid::from(message_id.clone()),
+    match event {
+        // thread replies don't surface in the conversation preview, only in their thread
+        DomainEvent::MessageSent {
+            message_id,
+            conversation_id,
+            thread_root_id: None,
             created_at,
-            Uuid::from(conversation_id.clone())
-        )
-        .execute(pool)
-        .await?;
+            ..
+        } => {
+            sqlx::query!(
+                "UPDATE conversations SET last_message_id = $1, updated_at = $2 WHERE id = $3",
+                Uuid::from(message_id.clone()),
+                created_at,
+                Uuid::from(conversation_id.clone())
+            )
+            .execute(pool)
+            .await?;
+        }
+        // the preview falls back to the newest message still standing; updated_at is left alone
+        // so unsending doesn't bump the conversation to the top of everyone's list
+        DomainEvent::MessageDeleted { conversation_id, .. } | DomainEvent::MessageExpired { conversation_id, .. } => {
+            sqlx::query!(
+                "UPDATE conversations SET last_message_id = (
+                     SELECT id FROM messages
+                     WHERE conversation_id = $1 AND thread_root_id IS NULL AND deleted_at IS NULL
+                     ORDER BY created_at DESC
+                     LIMIT 1
+                 )
+                 WHERE id = $1",
+                Uuid::from(conversation_id.clone())
+            )
+            .execute(pool)
+            .await?;
+        }
+        _ => {}
     }
     Ok(())
 }
@@ -7,18 +7,35 @@ use uuid::Uuid;
 
 use crate::application::commands::send_message::{SendMessageCommand, SendMessageHandler};
 use crate::application::queries::conversation_list::ConversationViewQueries;
+use crate::domain::conversation::ParticipantRole;
 use crate::domain::events::DomainEvent;
-use crate::domain::ids::{ConversationId, UserId};
+use crate::domain::ids::{ConversationId, MessageId, UserId};
 use crate::domain::message::MessageKind;
-use crate::domain::repository::{ConversationRepository, EventPublisher, MessageRepository};
+use crate::domain::notification::{Notification, NotificationSettings, NotifyLevel};
+use crate::domain::poll::Poll;
+use crate::domain::repository::{
+    BlockRepository, ConversationRepository, EventPublisher, MessageRepository, UploadRepository, UserRepository,
+};
 
 #[derive(Deserialize)]
 struct IncomingMessage {
     conversation_id: String,
-    sender_id: String,
     content: String,
     #[serde(default)]
     kind: Option<String>,
+    #[serde(default)]
+    reply_to: Option<String>,
+    #[serde(default)]
+    poll: Option<IncomingPoll>,
+}
+
+#[derive(Deserialize)]
+struct IncomingPoll {
+    options: Vec<String>,
+    #[serde(default)]
+    multiple_choice: bool,
+    #[serde(default)]
+    closes_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Serialize)]
@@ -28,9 +45,43 @@ struct OutgoingMessage {
     sender_id: String,
     content: String,
     kind: String,
+    thread_root_id: Option<String>,
     edited: bool,
     created_at: chrono::DateTime<chrono::Utc>,
     updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    forwarded_from: Option<OutgoingForwardedFrom>,
+    poll: Option<OutgoingPoll>,
+    attachment: Option<OutgoingAttachment>,
+    voice_note: Option<OutgoingVoiceNote>,
+    // whether the client should alert for this message, per the recipient's settings
+    notify: bool,
+}
+
+#[derive(Serialize)]
+struct OutgoingForwardedFrom {
+    message_id: String,
+    conversation_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingPoll {
+    options: Vec<String>,
+    multiple_choice: bool,
+    closes_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize)]
+struct OutgoingAttachment {
+    filename: String,
+    size_bytes: i64,
+    mime_type: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingVoiceNote {
+    duration_ms: i32,
+    peaks: Vec<u8>,
 }
 
 #[derive(Serialize)]
@@ -41,16 +92,132 @@ struct OutgoingEdit {
     updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-pub async fn handle_socket<C, M, P, V>(
+#[derive(Serialize)]
+struct OutgoingDelete {
+    id: String,
+    conversation_id: String,
+    deleted_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize)]
+struct OutgoingExpiry {
+    id: String,
+    conversation_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingPin {
+    message_id: String,
+    conversation_id: String,
+    user_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingMention {
+    message_id: String,
+    conversation_id: String,
+    mentioned_by: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingNotificationSettings {
+    conversation_id: String,
+    notify_level: String,
+    muted_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize)]
+struct OutgoingListEntry {
+    conversation_id: String,
+    archived: bool,
+    pinned: bool,
+}
+
+#[derive(Serialize)]
+struct OutgoingReaction {
+    message_id: String,
+    conversation_id: String,
+    user_id: String,
+    emoji: String,
+}
+
+// votes per option after the change, so clients don't have to keep count themselves
+#[derive(Serialize)]
+struct OutgoingPollVotes {
+    message_id: String,
+    conversation_id: String,
+    user_id: String,
+    choices: Vec<usize>,
+    tallies: Vec<i64>,
+}
+
+#[derive(Serialize)]
+struct OutgoingRename {
+    conversation_id: String,
+    name: String,
+    user_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingDetails {
+    conversation_id: String,
+    description: Option<String>,
+    avatar_url: Option<String>,
+    user_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingMessageTimer {
+    conversation_id: String,
+    ttl_seconds: Option<i64>,
+    user_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingParticipant {
+    conversation_id: String,
+    user_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingRemoval {
+    conversation_id: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingKick {
+    conversation_id: String,
+    user_id: String,
+    kicked_by: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingInvitation {
+    conversation_id: String,
+    title: String,
+    invited_by: String,
+}
+
+#[derive(Serialize)]
+struct OutgoingRoleChange {
+    conversation_id: String,
+    user_id: String,
+    role: String,
+}
+
+pub async fn handle_socket<C, M, U, B, F, P, V>(
     socket: WebSocket,
     user_id: UserId,
     pool: PgPool,
-    send_message: std::sync::Arc<SendMessageHandler<C, M, P>>,
+    send_message: std::sync::Arc<SendMessageHandler<C, M, U, B, F, P>>,
     views: V,
     mut rx: broadcast::Receiver<DomainEvent>,
 ) where
     C: ConversationRepository,
     M: MessageRepository,
+    U: UserRepository,
+    B: BlockRepository,
+    F: UploadRepository,
     P: EventPublisher,
     V: ConversationViewQueries,
 {
@@ -62,18 +229,35 @@ pub async fn handle_socket<C, M, P, V>(
                 let Some(Ok(WsMessage::Text(text))) = incoming else { break };
                 let Ok(payload) = serde_json::from_str::<IncomingMessage>(&text) else { continue };
                 let Ok(conversation_id) = Uuid::parse_str(&payload.conversation_id) else { continue };
-                let Ok(sender_id) = Uuid::parse_str(&payload.sender_id) else { continue };
+                let reply_to = match payload.reply_to.as_deref().map(Uuid::parse_str) {
+                    Some(Ok(id)) => Some(MessageId::from_persistence(id)),
+                    Some(Err(_)) => continue,
+                    None => None,
+                };
 
                 let kind = match payload.kind.as_deref() {
                     Some("image") => MessageKind::Image,
+                    Some("poll") => MessageKind::Poll,
+                    Some("file") => MessageKind::File,
+                    Some("audio") => MessageKind::Audio,
                     _ => MessageKind::Text,
                 };
+                let poll = match payload.poll.map(|poll| Poll::new(poll.options, poll.multiple_choice, poll.closes_at)) {
+                    Some(Ok(poll)) => Some(poll),
+                    Some(Err(err)) => {
+                        tracing::warn!("failed to send message: {err}");
+                        continue;
+                    }
+                    None => None,
+                };
 
                 let command = SendMessageCommand {
                     conversation_id: ConversationId::from_persistence(conversation_id),
-                    sender_id: UserId::from_persistence(sender_id),
+                    sender_id: user_id.clone(),
                     content: payload.content,
                     kind,
+                    reply_to,
+                    poll,
                 };
 
                 if let Err(err) = send_message.handle(command).await {
@@ -88,15 +272,15 @@ pub async fn handle_socket<C, M, P, V>(
                 };
 
                 let json = match &event {
-                    DomainEvent::MessageSent { message_id, conversation_id, sender_id, content, kind, created_at } => {
-                        match user_is_in(&pool, &user_id, conversation_id).await {
-                            Ok(true) => {}
-                            _ => continue,
-                        }
+                    DomainEvent::MessageSent { message_id, conversation_id, sender_id, content, kind, thread_root_id, created_at, expires_at, forwarded_from, poll, attachment, voice_note } => {
+                        let Ok(Some(settings)) = notification_settings(&pool, &user_id, conversation_id).await else { continue };
 
                         let kind_str = match kind {
                             MessageKind::Text => "text",
                             MessageKind::Image => "image",
+                            MessageKind::Poll => "poll",
+                            MessageKind::File => "file",
+                            MessageKind::Audio => "audio",
                         };
 
                         let payload = OutgoingMessage {
@@ -105,12 +289,37 @@ pub async fn handle_socket<C, M, P, V>(
                             sender_id: sender_id.to_string(),
                             content: content.clone(),
                             kind: kind_str.to_string(),
+                            thread_root_id: thread_root_id.as_ref().map(|id| id.to_string()),
                             edited: false,
                             created_at: *created_at,
                             updated_at: None,
+                            expires_at: *expires_at,
+                            forwarded_from: forwarded_from.as_ref().map(|origin| OutgoingForwardedFrom {
+                                message_id: origin.message_id.to_string(),
+                                conversation_id: origin.conversation_id.to_string(),
+                            }),
+                            poll: poll.as_ref().map(|poll| OutgoingPoll {
+                                options: poll.options().clone(),
+                                multiple_choice: *poll.multiple_choice(),
+                                closes_at: *poll.closes_at(),
+                            }),
+                            attachment: attachment.as_ref().map(|attachment| OutgoingAttachment {
+                                filename: attachment.filename().clone(),
+                                size_bytes: *attachment.size_bytes(),
+                                mime_type: attachment.mime_type().clone(),
+                            }),
+                            voice_note: voice_note.as_ref().map(|voice_note| OutgoingVoiceNote {
+                                duration_ms: *voice_note.duration_ms(),
+                                peaks: voice_note.peaks().clone(),
+                            }),
+                            notify: sender_id != &user_id && settings.allows(Notification::Message, chrono::Utc::now()),
                         };
 
-                        serde_json::to_string(&serde_json::json!({ "type": "message", "message": payload }))
+                        if thread_root_id.is_some() {
+                            serde_json::to_string(&serde_json::json!({ "type": "thread_reply", "thread_reply": payload }))
+                        } else {
+                            serde_json::to_string(&serde_json::json!({ "type": "message", "message": payload }))
+                        }
                     }
                     DomainEvent::MessageEdited { message_id, conversation_id, content, updated_at } => {
                         match user_is_in(&pool, &user_id, conversation_id).await {
@@ -127,13 +336,285 @@ pub async fn handle_socket<C, M, P, V>(
 
                         serde_json::to_string(&serde_json::json!({ "type": "message_edited", "message_edited": payload }))
                     }
+                    DomainEvent::MessageDeleted { message_id, conversation_id, deleted_at, .. } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingDelete {
+                            id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            deleted_at: *deleted_at,
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "message_deleted", "message_deleted": payload }))
+                    }
+                    // unlike an unsend there's no tombstone; clients drop the message entirely
+                    DomainEvent::MessageExpired { message_id, conversation_id } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingExpiry {
+                            id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "message_expired", "message_expired": payload }))
+                    }
+                    DomainEvent::MessagePinned { message_id, conversation_id, pinned_by, .. } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingPin {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            user_id: pinned_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "message_pinned", "message_pinned": payload }))
+                    }
+                    DomainEvent::MessageUnpinned { message_id, conversation_id, unpinned_by } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingPin {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            user_id: unpinned_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "message_unpinned", "message_unpinned": payload }))
+                    }
+                    // addressed to the mentioned user alone; it gets through a mute but not notifications turned off
+                    DomainEvent::UserMentioned { message_id, conversation_id, user_id: mentioned_id, mentioned_by } if mentioned_id == &user_id => {
+                        match notification_settings(&pool, &user_id, conversation_id).await {
+                            Ok(Some(settings)) if settings.allows(Notification::Mention, chrono::Utc::now()) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingMention {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            mentioned_by: mentioned_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "mention", "mention": payload }))
+                    }
+                    // keeps the user's other devices in sync
+                    DomainEvent::NotificationSettingsChanged { conversation_id, user_id: owner_id, level, muted_until } if owner_id == &user_id => {
+                        let level_str = match level {
+                            NotifyLevel::All => "all",
+                            NotifyLevel::Mentions => "mentions",
+                            NotifyLevel::None => "none",
+                        };
+
+                        let payload = OutgoingNotificationSettings {
+                            conversation_id: conversation_id.to_string(),
+                            notify_level: level_str.to_string(),
+                            muted_until: *muted_until,
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "notification_settings_changed", "notification_settings_changed": payload }))
+                    }
+                    DomainEvent::ListEntryChanged { conversation_id, user_id: owner_id, archived, pinned } if owner_id == &user_id => {
+                        let payload = OutgoingListEntry {
+                            conversation_id: conversation_id.to_string(),
+                            archived: *archived,
+                            pinned: *pinned,
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "list_entry_changed", "list_entry_changed": payload }))
+                    }
+                    DomainEvent::ReactionAdded { message_id, conversation_id, user_id: reactor_id, emoji, .. } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingReaction {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            user_id: reactor_id.to_string(),
+                            emoji: emoji.clone(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "reaction_added", "reaction_added": payload }))
+                    }
+                    DomainEvent::ReactionRemoved { message_id, conversation_id, user_id: reactor_id, emoji } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingReaction {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            user_id: reactor_id.to_string(),
+                            emoji: emoji.clone(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "reaction_removed", "reaction_removed": payload }))
+                    }
+                    DomainEvent::PollVoteCast { message_id, conversation_id, user_id: voter_id, choices } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+                        let Ok(tallies) = poll_tallies(&pool, message_id).await else { continue };
+
+                        let payload = OutgoingPollVotes {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            user_id: voter_id.to_string(),
+                            choices: choices.clone(),
+                            tallies,
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "poll_votes", "poll_votes": payload }))
+                    }
+                    DomainEvent::PollVoteRetracted { message_id, conversation_id, user_id: voter_id } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+                        let Ok(tallies) = poll_tallies(&pool, message_id).await else { continue };
+
+                        let payload = OutgoingPollVotes {
+                            message_id: message_id.to_string(),
+                            conversation_id: conversation_id.to_string(),
+                            user_id: voter_id.to_string(),
+                            choices: Vec::new(),
+                            tallies,
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "poll_votes", "poll_votes": payload }))
+                    }
+                    DomainEvent::ParticipantRoleChanged { conversation_id, user_id: changed_user_id, role } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let role_str = match role {
+                            ParticipantRole::Owner => "owner",
+                            ParticipantRole::Admin => "admin",
+                            ParticipantRole::Member => "member",
+                        };
+
+                        let payload = OutgoingRoleChange {
+                            conversation_id: conversation_id.to_string(),
+                            user_id: changed_user_id.to_string(),
+                            role: role_str.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "participant_role_changed", "participant_role_changed": payload }))
+                    }
+                    DomainEvent::ConversationRenamed { conversation_id, title, renamed_by } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingRename {
+                            conversation_id: conversation_id.to_string(),
+                            name: title.clone(),
+                            user_id: renamed_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "conversation_renamed", "conversation_renamed": payload }))
+                    }
+                    DomainEvent::ConversationDetailsChanged { conversation_id, description, avatar_url, changed_by } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingDetails {
+                            conversation_id: conversation_id.to_string(),
+                            description: description.clone(),
+                            avatar_url: avatar_url.clone(),
+                            user_id: changed_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "conversation_details_changed", "conversation_details_changed": payload }))
+                    }
+                    DomainEvent::MessageTimerChanged { conversation_id, ttl_seconds, changed_by } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingMessageTimer {
+                            conversation_id: conversation_id.to_string(),
+                            ttl_seconds: *ttl_seconds,
+                            user_id: changed_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "message_timer_changed", "message_timer_changed": payload }))
+                    }
                     // fires for both brand-new conversations and later invites — either way, this
                     // user now belongs to a conversation their client doesn't know about yet, so
                     // push the full view rather than making them wait for a page refresh.
                     DomainEvent::ParticipantAdded { conversation_id, user_id: added_user_id } if added_user_id == &user_id => {
-                        let Ok(Some(view)) = views.by_id(conversation_id).await else { continue };
+                        let Ok(Some(view)) = views.by_id(conversation_id, &user_id).await else { continue };
                         serde_json::to_string(&serde_json::json!({ "type": "conversation", "conversation": view }))
                     }
+                    // the kicked user is no longer a participant, so this is the last thing they hear about the
+                    // conversation; their client drops it
+                    DomainEvent::ParticipantKicked { conversation_id, user_id: kicked_id, .. } if kicked_id == &user_id => {
+                        let payload = OutgoingRemoval {
+                            conversation_id: conversation_id.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "conversation_removed", "conversation_removed": payload }))
+                    }
+                    DomainEvent::ParticipantKicked { conversation_id, user_id: kicked_id, kicked_by } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingKick {
+                            conversation_id: conversation_id.to_string(),
+                            user_id: kicked_id.to_string(),
+                            kicked_by: kicked_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "participant_kicked", "participant_kicked": payload }))
+                    }
+                    // invitees aren't participants yet, so this only goes to them
+                    DomainEvent::InvitationReceived { conversation_id, title, user_id: invited_id, invited_by }
+                        if invited_id == &user_id =>
+                    {
+                        let payload = OutgoingInvitation {
+                            conversation_id: conversation_id.to_string(),
+                            title: title.clone(),
+                            invited_by: invited_by.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "invitation", "invitation": payload }))
+                    }
+                    // everyone already in the conversation just needs to know who joined
+                    DomainEvent::ParticipantAdded { conversation_id, user_id: added_user_id } => {
+                        match user_is_in(&pool, &user_id, conversation_id).await {
+                            Ok(true) => {}
+                            _ => continue,
+                        }
+
+                        let payload = OutgoingParticipant {
+                            conversation_id: conversation_id.to_string(),
+                            user_id: added_user_id.to_string(),
+                        };
+
+                        serde_json::to_string(&serde_json::json!({ "type": "participant_added", "participant_added": payload }))
+                    }
                     _ => continue,
                 };
 
@@ -158,3 +639,39 @@ async fn user_is_in(pool: &PgPool, user_id: &UserId, conversation_id: &Conversat
 
     Ok(row.is_some())
 }
+
+async fn poll_tallies(pool: &PgPool, message_id: &MessageId) -> Result<Vec<i64>, sqlx::Error> {
+    let tallies = sqlx::query_scalar!(
+        "SELECT ARRAY(
+             SELECT COUNT(v.user_id)
+             FROM generate_subscripts(p.options, 1) AS i
+             LEFT JOIN poll_votes v ON v.message_id = p.message_id AND (i - 1)::smallint = ANY(v.choices)
+             GROUP BY i
+             ORDER BY i
+         ) AS \"tallies!\"
+         FROM message_polls p WHERE p.message_id = $1",
+        Uuid::from(message_id.clone())
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(tallies.unwrap_or_default())
+}
+
+// None when the user isn't a participant, so this doubles as the membership check
+async fn notification_settings(
+    pool: &PgPool,
+    user_id: &UserId,
+    conversation_id: &ConversationId,
+) -> Result<Option<NotificationSettings>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT notify_level AS \"notify_level: NotifyLevel\", muted_until
+         FROM user_conversations WHERE user_id = $1 AND conversation_id = $2",
+        Uuid::from(user_id.clone()),
+        Uuid::from(conversation_id.clone())
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| NotificationSettings::from_persistence(conversation_id.clone(), user_id.clone(), r.notify_level, r.muted_until)))
+}
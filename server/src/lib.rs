@@ -11,12 +11,46 @@ use secrecy::ExposeSecret;
 use sqlx::postgres::PgPoolOptions;
 
 use crate::{
-    application::commands::create_conversation::CreateConversationHandler, application::commands::create_user::CreateUserHandler,
-    application::commands::edit_message::EditMessageHandler, application::commands::leave_conversation::LeaveConversationHandler,
-    application::commands::mark_message_read::MarkReadHandler, application::commands::send_message::SendMessageHandler,
-    config::AppConfig, infrastructure::events::bus::EventBus,
+    application::commands::accept_invitation::AcceptInvitationHandler,
+    application::commands::add_participants::AddParticipantsHandler, application::commands::add_reaction::AddReactionHandler,
+    application::commands::archive_conversation::ArchiveConversationHandler, application::commands::block_user::BlockUserHandler,
+    application::commands::cancel_scheduled_message::CancelScheduledMessageHandler, application::commands::cast_vote::CastVoteHandler,
+    application::commands::change_participant_role::ChangeParticipantRoleHandler,
+    application::commands::change_password::ChangePasswordHandler, application::commands::confirm_totp::ConfirmTotpHandler,
+    application::commands::create_conversation::CreateConversationHandler, application::commands::create_invite::CreateInviteHandler,
+    application::commands::decline_invitation::DeclineInvitationHandler, application::commands::delete_message::DeleteMessageHandler,
+    application::commands::disable_totp::DisableTotpHandler,
+    application::commands::dispatch_scheduled_messages::DispatchScheduledMessagesHandler,
+    application::commands::edit_message::EditMessageHandler, application::commands::enroll_totp::EnrollTotpHandler,
+    application::commands::expire_messages::ExpireMessagesHandler, application::commands::forward_message::ForwardMessageHandler,
+    application::commands::join_via_invite::JoinViaInviteHandler, application::commands::kick_participant::KickParticipantHandler,
+    application::commands::leave_conversation::LeaveConversationHandler, application::commands::login::LoginHandler,
+    application::commands::logout::LogoutHandler, application::commands::mark_message_read::MarkReadHandler,
+    application::commands::pin_conversation::PinConversationHandler, application::commands::pin_message::PinMessageHandler,
+    application::commands::register::RegisterHandler, application::commands::remove_reaction::RemoveReactionHandler,
+    application::commands::rename_conversation::RenameConversationHandler, application::commands::retract_vote::RetractVoteHandler,
+    application::commands::revoke_invite::RevokeInviteHandler, application::commands::schedule_message::ScheduleMessageHandler,
+    application::commands::send_message::SendMessageHandler, application::commands::set_message_timer::SetMessageTimerHandler,
+    application::commands::unblock_user::UnblockUserHandler, application::commands::unpin_message::UnpinMessageHandler,
+    application::commands::update_conversation_details::UpdateConversationDetailsHandler,
+    application::commands::update_notification_settings::UpdateNotificationSettingsHandler,
+    application::commands::verify_login::VerifyLoginHandler, config::AppConfig, domain::clock::SystemClock,
+    infrastructure::crypto::SecretCipher, infrastructure::events::bus::EventBus, infrastructure::media::LocalMediaStore,
+    infrastructure::postgres::block_repository::SqlxBlockRepository,
     infrastructure::postgres::conversation_repository::SqlxConversationRepository,
-    infrastructure::postgres::message_repository::SqlxMessageRepository, infrastructure::postgres::queries::SqlxViewQueries,
+    infrastructure::postgres::credential_repository::SqlxCredentialRepository,
+    infrastructure::postgres::invitation_repository::SqlxInvitationRepository,
+    infrastructure::postgres::invite_repository::SqlxInviteRepository,
+    infrastructure::postgres::list_entry_repository::SqlxListEntryRepository,
+    infrastructure::postgres::login_challenge_repository::SqlxLoginChallengeRepository,
+    infrastructure::postgres::message_repository::SqlxMessageRepository,
+    infrastructure::postgres::notification_settings_repository::SqlxNotificationSettingsRepository,
+    infrastructure::postgres::pin_repository::SqlxPinRepository,
+    infrastructure::postgres::poll_vote_repository::SqlxPollVoteRepository, infrastructure::postgres::queries::SqlxViewQueries,
+    infrastructure::postgres::reaction_repository::SqlxReactionRepository,
+    infrastructure::postgres::scheduled_message_repository::SqlxScheduledMessageRepository,
+    infrastructure::postgres::session_repository::SqlxSessionRepository,
+    infrastructure::postgres::totp_repository::SqlxTotpRepository, infrastructure::postgres::upload_repository::SqlxUploadRepository,
     infrastructure::postgres::user_repository::SqlxUserRepository,
 };
 
@@ -24,14 +58,80 @@ pub struct AppState {
     pub pool: sqlx::PgPool,
     pub event_bus: EventBus,
     pub users: SqlxUserRepository,
+    pub conversations: SqlxConversationRepository,
+    pub messages: SqlxMessageRepository,
+    pub sessions: SqlxSessionRepository,
+    pub blocks: SqlxBlockRepository,
+    pub uploads: SqlxUploadRepository,
     pub views: SqlxViewQueries,
-    pub create_user: CreateUserHandler<SqlxUserRepository>,
-    pub create_conversation: CreateConversationHandler<SqlxConversationRepository, EventBus>,
-    pub send_message: Arc<SendMessageHandler<SqlxConversationRepository, SqlxMessageRepository, EventBus>>,
-    pub edit_message: EditMessageHandler<SqlxMessageRepository, EventBus>,
+    pub register: RegisterHandler<SqlxUserRepository>,
+    pub login:
+        LoginHandler<SqlxUserRepository, SqlxCredentialRepository, SqlxSessionRepository, SqlxLoginChallengeRepository, SystemClock>,
+    pub verify_login:
+        VerifyLoginHandler<SqlxUserRepository, SqlxTotpRepository, SqlxSessionRepository, SqlxLoginChallengeRepository, SystemClock>,
+    pub logout: LogoutHandler<SqlxSessionRepository>,
+    pub change_password: ChangePasswordHandler<SqlxCredentialRepository, SqlxSessionRepository>,
+    pub enroll_totp: EnrollTotpHandler<SqlxUserRepository, SqlxTotpRepository, SystemClock>,
+    pub confirm_totp: ConfirmTotpHandler<SqlxUserRepository, SqlxTotpRepository, SystemClock>,
+    pub disable_totp: DisableTotpHandler<SqlxUserRepository, SqlxCredentialRepository, SqlxTotpRepository, SystemClock>,
+    pub block_user: BlockUserHandler<SqlxUserRepository, SqlxBlockRepository>,
+    pub unblock_user: UnblockUserHandler<SqlxBlockRepository>,
+    pub create_conversation: CreateConversationHandler<SqlxConversationRepository, SqlxUserRepository, SqlxBlockRepository, EventBus>,
+    pub send_message: Arc<
+        SendMessageHandler<
+            SqlxConversationRepository,
+            SqlxMessageRepository,
+            SqlxUserRepository,
+            SqlxBlockRepository,
+            SqlxUploadRepository,
+            EventBus,
+        >,
+    >,
+    pub edit_message: EditMessageHandler<SqlxConversationRepository, SqlxMessageRepository, SqlxUserRepository, EventBus>,
+    pub forward_message: ForwardMessageHandler<SqlxConversationRepository, SqlxMessageRepository, SqlxBlockRepository, EventBus>,
+    pub schedule_message: ScheduleMessageHandler<SqlxConversationRepository, SqlxScheduledMessageRepository, SystemClock>,
+    pub cancel_scheduled_message: CancelScheduledMessageHandler<SqlxScheduledMessageRepository>,
+    #[allow(clippy::type_complexity)]
+    pub dispatch_scheduled_messages: Arc<
+        DispatchScheduledMessagesHandler<
+            SqlxConversationRepository,
+            SqlxMessageRepository,
+            SqlxUserRepository,
+            SqlxBlockRepository,
+            SqlxUploadRepository,
+            EventBus,
+            SqlxScheduledMessageRepository,
+            SystemClock,
+        >,
+    >,
+    pub expire_messages: Arc<ExpireMessagesHandler<SqlxMessageRepository, LocalMediaStore, EventBus, SystemClock>>,
+    pub delete_message: DeleteMessageHandler<SqlxConversationRepository, SqlxMessageRepository, EventBus>,
+    pub add_reaction: AddReactionHandler<SqlxConversationRepository, SqlxMessageRepository, SqlxReactionRepository, EventBus>,
+    pub remove_reaction: RemoveReactionHandler<SqlxReactionRepository, EventBus>,
+    pub cast_vote: CastVoteHandler<SqlxConversationRepository, SqlxMessageRepository, SqlxPollVoteRepository, EventBus, SystemClock>,
+    pub retract_vote: RetractVoteHandler<SqlxMessageRepository, SqlxPollVoteRepository, EventBus, SystemClock>,
     pub leave_conversation: LeaveConversationHandler<SqlxConversationRepository, EventBus>,
+    pub add_participants: AddParticipantsHandler<SqlxConversationRepository, SqlxUserRepository, EventBus>,
+    pub create_invite: CreateInviteHandler<SqlxConversationRepository, SqlxInviteRepository, SystemClock>,
+    pub revoke_invite: RevokeInviteHandler<SqlxConversationRepository, SqlxInviteRepository, SystemClock>,
+    pub join_via_invite: JoinViaInviteHandler<SqlxConversationRepository, SqlxInviteRepository, EventBus, SystemClock>,
+    pub accept_invitation: AcceptInvitationHandler<SqlxConversationRepository, SqlxInvitationRepository, EventBus>,
+    pub decline_invitation: DeclineInvitationHandler<SqlxInvitationRepository>,
+    pub kick_participant: KickParticipantHandler<SqlxConversationRepository, EventBus>,
+    pub change_participant_role: ChangeParticipantRoleHandler<SqlxConversationRepository, EventBus>,
+    pub rename_conversation: RenameConversationHandler<SqlxConversationRepository, EventBus>,
+    pub update_conversation_details: UpdateConversationDetailsHandler<SqlxConversationRepository, EventBus>,
+    pub set_message_timer: SetMessageTimerHandler<SqlxConversationRepository, EventBus>,
+    pub update_notification_settings:
+        UpdateNotificationSettingsHandler<SqlxConversationRepository, SqlxNotificationSettingsRepository, EventBus, SystemClock>,
+    pub archive_conversation: ArchiveConversationHandler<SqlxListEntryRepository, EventBus, SystemClock>,
+    pub pin_conversation: PinConversationHandler<SqlxListEntryRepository, EventBus, SystemClock>,
+    pub pin_message: PinMessageHandler<SqlxConversationRepository, SqlxMessageRepository, SqlxPinRepository, EventBus>,
+    pub unpin_message: UnpinMessageHandler<SqlxConversationRepository, SqlxPinRepository, EventBus>,
     pub mark_read: MarkReadHandler<EventBus>,
     pub upload_dir: String,
+    pub file_dir: String,
+    pub allowed_file_types: Vec<String>,
     pub public_url: String,
 }
 
@@ -43,36 +143,192 @@ pub async fn configure_state(config: &AppConfig) -> Result<Arc<AppState>, anyhow
         .await?;
 
     tokio::fs::create_dir_all(&config.upload_dir).await?;
+    tokio::fs::create_dir_all(&config.file_dir).await?;
 
     let event_bus = EventBus::new(pool.clone());
     let users_repo = SqlxUserRepository::new(pool.clone());
     let conversations_repo = SqlxConversationRepository::new(pool.clone());
     let messages_repo = SqlxMessageRepository::new(pool.clone());
+    let sessions_repo = SqlxSessionRepository::new(pool.clone());
+    let credentials_repo = SqlxCredentialRepository::new(pool.clone());
+    let challenges_repo = SqlxLoginChallengeRepository::new(pool.clone());
+    let totp_repo = SqlxTotpRepository::new(
+        pool.clone(),
+        SecretCipher::from_base64_key(config.totp_encryption_key.expose_secret())?,
+    );
+    let reactions_repo = SqlxReactionRepository::new(pool.clone());
+    let pins_repo = SqlxPinRepository::new(pool.clone());
+    let votes_repo = SqlxPollVoteRepository::new(pool.clone());
+    let notification_settings_repo = SqlxNotificationSettingsRepository::new(pool.clone());
+    let list_entries_repo = SqlxListEntryRepository::new(pool.clone());
+    let invites_repo = SqlxInviteRepository::new(pool.clone());
+    let invitations_repo = SqlxInvitationRepository::new(pool.clone());
+    let blocks_repo = SqlxBlockRepository::new(pool.clone());
+    let scheduled_repo = SqlxScheduledMessageRepository::new(pool.clone());
+    let uploads_repo = SqlxUploadRepository::new(pool.clone(), &config.public_url);
     let views = SqlxViewQueries::new(pool.clone());
+    let media = LocalMediaStore::new(&config.upload_dir, &config.file_dir, &config.public_url);
 
-    let create_user = CreateUserHandler::new(users_repo.clone());
-    let create_conversation = CreateConversationHandler::new(conversations_repo.clone(), event_bus.clone());
+    let register = RegisterHandler::new(users_repo.clone());
+    let login = LoginHandler::new(
+        users_repo.clone(),
+        credentials_repo.clone(),
+        sessions_repo.clone(),
+        challenges_repo.clone(),
+        SystemClock,
+    );
+    let verify_login = VerifyLoginHandler::new(
+        users_repo.clone(),
+        totp_repo.clone(),
+        sessions_repo.clone(),
+        challenges_repo.clone(),
+        SystemClock,
+    );
+    let logout = LogoutHandler::new(sessions_repo.clone());
+    let change_password = ChangePasswordHandler::new(credentials_repo.clone(), sessions_repo.clone());
+    let enroll_totp = EnrollTotpHandler::new(users_repo.clone(), totp_repo.clone(), SystemClock);
+    let confirm_totp = ConfirmTotpHandler::new(users_repo.clone(), totp_repo.clone(), SystemClock);
+    let disable_totp = DisableTotpHandler::new(users_repo.clone(), credentials_repo.clone(), totp_repo, SystemClock);
+    let block_user = BlockUserHandler::new(users_repo.clone(), blocks_repo.clone());
+    let unblock_user = UnblockUserHandler::new(blocks_repo.clone());
+    let create_conversation = CreateConversationHandler::new(
+        conversations_repo.clone(),
+        users_repo.clone(),
+        blocks_repo.clone(),
+        event_bus.clone(),
+    );
     let send_message = Arc::new(SendMessageHandler::new(
         conversations_repo.clone(),
         messages_repo.clone(),
+        users_repo.clone(),
+        blocks_repo.clone(),
+        uploads_repo.clone(),
+        event_bus.clone(),
+    ));
+    let edit_message = EditMessageHandler::new(
+        conversations_repo.clone(),
+        messages_repo.clone(),
+        users_repo.clone(),
+        event_bus.clone(),
+    );
+    let forward_message = ForwardMessageHandler::new(
+        conversations_repo.clone(),
+        messages_repo.clone(),
+        blocks_repo.clone(),
+        event_bus.clone(),
+    );
+    let schedule_message = ScheduleMessageHandler::new(conversations_repo.clone(), scheduled_repo.clone(), SystemClock);
+    let cancel_scheduled_message = CancelScheduledMessageHandler::new(scheduled_repo.clone());
+    let dispatch_scheduled_messages = Arc::new(DispatchScheduledMessagesHandler::new(
+        send_message.clone(),
+        scheduled_repo,
+        SystemClock,
+    ));
+    let expire_messages = Arc::new(ExpireMessagesHandler::new(
+        messages_repo.clone(),
+        media,
         event_bus.clone(),
+        SystemClock,
     ));
-    let edit_message = EditMessageHandler::new(messages_repo.clone(), event_bus.clone());
+    let delete_message = DeleteMessageHandler::new(conversations_repo.clone(), messages_repo.clone(), event_bus.clone());
+    let add_reaction = AddReactionHandler::new(
+        conversations_repo.clone(),
+        messages_repo.clone(),
+        reactions_repo.clone(),
+        event_bus.clone(),
+    );
+    let remove_reaction = RemoveReactionHandler::new(reactions_repo, event_bus.clone());
+    let cast_vote = CastVoteHandler::new(
+        conversations_repo.clone(),
+        messages_repo.clone(),
+        votes_repo.clone(),
+        event_bus.clone(),
+        SystemClock,
+    );
+    let retract_vote = RetractVoteHandler::new(messages_repo.clone(), votes_repo, event_bus.clone(), SystemClock);
     let leave_conversation = LeaveConversationHandler::new(conversations_repo.clone(), event_bus.clone());
+    let add_participants = AddParticipantsHandler::new(conversations_repo.clone(), users_repo.clone(), event_bus.clone());
+    let create_invite = CreateInviteHandler::new(conversations_repo.clone(), invites_repo.clone(), SystemClock);
+    let revoke_invite = RevokeInviteHandler::new(conversations_repo.clone(), invites_repo.clone(), SystemClock);
+    let join_via_invite = JoinViaInviteHandler::new(conversations_repo.clone(), invites_repo, event_bus.clone(), SystemClock);
+    let accept_invitation = AcceptInvitationHandler::new(conversations_repo.clone(), invitations_repo.clone(), event_bus.clone());
+    let decline_invitation = DeclineInvitationHandler::new(invitations_repo);
+    let kick_participant = KickParticipantHandler::new(conversations_repo.clone(), event_bus.clone());
+    let change_participant_role = ChangeParticipantRoleHandler::new(conversations_repo.clone(), event_bus.clone());
+    let rename_conversation = RenameConversationHandler::new(conversations_repo.clone(), event_bus.clone());
+    let update_conversation_details = UpdateConversationDetailsHandler::new(conversations_repo.clone(), event_bus.clone());
+    let set_message_timer = SetMessageTimerHandler::new(conversations_repo.clone(), event_bus.clone());
+    let update_notification_settings = UpdateNotificationSettingsHandler::new(
+        conversations_repo.clone(),
+        notification_settings_repo,
+        event_bus.clone(),
+        SystemClock,
+    );
+    let archive_conversation = ArchiveConversationHandler::new(list_entries_repo.clone(), event_bus.clone(), SystemClock);
+    let pin_conversation = PinConversationHandler::new(list_entries_repo, event_bus.clone(), SystemClock);
+    let pin_message = PinMessageHandler::new(
+        conversations_repo.clone(),
+        messages_repo.clone(),
+        pins_repo.clone(),
+        event_bus.clone(),
+    );
+    let unpin_message = UnpinMessageHandler::new(conversations_repo.clone(), pins_repo, event_bus.clone());
     let mark_read = MarkReadHandler::new(event_bus.clone());
 
     let state = Arc::new(AppState {
         pool,
         event_bus,
         users: users_repo,
+        conversations: conversations_repo,
+        messages: messages_repo,
+        sessions: sessions_repo,
+        blocks: blocks_repo,
+        uploads: uploads_repo,
         views,
-        create_user,
+        register,
+        login,
+        verify_login,
+        logout,
+        change_password,
+        enroll_totp,
+        confirm_totp,
+        disable_totp,
+        block_user,
+        unblock_user,
         create_conversation,
         send_message,
         edit_message,
+        forward_message,
+        schedule_message,
+        cancel_scheduled_message,
+        dispatch_scheduled_messages,
+        expire_messages,
+        delete_message,
+        add_reaction,
+        remove_reaction,
+        cast_vote,
+        retract_vote,
         leave_conversation,
+        add_participants,
+        create_invite,
+        revoke_invite,
+        join_via_invite,
+        accept_invitation,
+        decline_invitation,
+        kick_participant,
+        change_participant_role,
+        rename_conversation,
+        update_conversation_details,
+        set_message_timer,
+        update_notification_settings,
+        archive_conversation,
+        pin_conversation,
+        pin_message,
+        unpin_message,
         mark_read,
         upload_dir: config.upload_dir.clone(),
+        file_dir: config.file_dir.clone(),
+        allowed_file_types: config.allowed_file_types.clone(),
         public_url: config.public_url.clone(),
     });
 
@@ -1,6 +1,11 @@
 use axum::Router;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use volt::{config::AppConfig, configure_state, handlers::routes};
+use volt::{
+    config::AppConfig,
+    configure_state,
+    handlers::routes,
+    infrastructure::jobs::{spawn_message_reaper, spawn_scheduled_message_dispatcher},
+};
 
 #[tokio::main]
 async fn main() {
@@ -14,6 +19,8 @@ async fn main() {
 
     let config = AppConfig::from_env().unwrap();
     let state = configure_state(&config).await.unwrap();
+    spawn_scheduled_message_dispatcher(state.dispatch_scheduled_messages.clone());
+    spawn_message_reaper(state.expire_messages.clone());
 
     let app = Router::new().merge(routes(&config)).with_state(state);
 